@@ -4,7 +4,6 @@ use parry2d_f64::{
     shape::ConvexPolygon,
 };
 
-use crate::polygon::Polygon;
 use crate::transform::Transform2D;
 
 fn transform_to_isometry(t: &Transform2D) -> Isometry<f64> {
@@ -14,23 +13,14 @@ fn transform_to_isometry(t: &Transform2D) -> Isometry<f64> {
     )
 }
 
-/// Returns true if two placed polygons (potentially concave) overlap.
-pub fn shapes_collide(
-    poly_a: &Polygon,
+/// Narrow phase only: tests all convex sub-part pairs. For callers that have
+/// already done the AABB check (`Scene`, through its spatial index).
+pub fn parts_collide(
     transform_a: &Transform2D,
     parts_a: &[ConvexPolygon],
-    poly_b: &Polygon,
     transform_b: &Transform2D,
     parts_b: &[ConvexPolygon],
 ) -> bool {
-    // Broad phase: AABB check
-    let world_a = poly_a.transformed(transform_a);
-    let world_b = poly_b.transformed(transform_b);
-    if !world_a.bounding_box().overlaps(&world_b.bounding_box()) {
-        return false;
-    }
-
-    // Narrow phase: test all convex sub-part pairs
     let iso_a = transform_to_isometry(transform_a);
     let iso_b = transform_to_isometry(transform_b);
 
@@ -51,6 +41,20 @@ mod tests {
     use crate::polygon::Polygon;
     use crate::transform::Transform2D;
 
+    /// The AABB check `Scene` gets from its spatial index, then the narrow phase.
+    fn shapes_collide(
+        poly_a: &Polygon,
+        transform_a: &Transform2D,
+        parts_a: &[ConvexPolygon],
+        poly_b: &Polygon,
+        transform_b: &Transform2D,
+        parts_b: &[ConvexPolygon],
+    ) -> bool {
+        let box_a = poly_a.transformed(transform_a).bounding_box();
+        let box_b = poly_b.transformed(transform_b).bounding_box();
+        box_a.overlaps(&box_b) && parts_collide(transform_a, parts_a, transform_b, parts_b)
+    }
+
     #[test]
     fn rectangles_collide_when_overlapping() {
         let r = Polygon::rectangle(2.0, 2.0);
@@ -0,0 +1,255 @@
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+
+use crate::polygon::Polygon;
+use crate::scene::{ObjectId, Scene, ShapeMetadata};
+use crate::transform::Transform2D;
+
+/// Version of the `SceneDocument` layout written by `Scene::to_json`.
+///
+/// - 0: the original `exportJson` output — no `version` field, shapes only have
+///   `world_vertices`.
+/// - 1: adds `version` and each shape's local `vertices`.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// A saved scene: what `exportJson` writes and `importJson` reads.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SceneDocument {
+    /// Missing in version 0 documents.
+    #[serde(default)]
+    pub version: u32,
+    pub boundary: Option<Vec<[f64; 2]>>,
+    pub shapes: Vec<ShapeRecord>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShapeRecord {
+    pub id: ObjectId,
+    /// The shape in its own coordinates, before `transform`. Version 0 documents
+    /// don't have it; it is recovered from `world_vertices` instead.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vertices: Option<Vec<[f64; 2]>>,
+    /// Written for readers that draw the scene as-is; ignored by `from_document`
+    /// when `vertices` is present.
+    #[serde(default)]
+    pub world_vertices: Vec<[f64; 2]>,
+    pub transform: Transform2D,
+    #[serde(default)]
+    pub metadata: ShapeMetadata,
+}
+
+/// What `Scene::from_json` loaded, and which shapes no longer pass validation —
+/// e.g. saved before the boundary was tightened. Those shapes are still loaded,
+/// so nothing the user placed disappears; the UI can flag them for fixing.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ImportReport {
+    pub version: u32,
+    pub shapes: usize,
+    pub invalid: Vec<InvalidShape>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InvalidShape {
+    pub id: ObjectId,
+    pub collisions: Vec<ObjectId>,
+    pub exceeds_boundary: bool,
+}
+
+fn to_pairs(polygon: &Polygon) -> Vec<[f64; 2]> {
+    polygon.vertices().iter().map(|p| [p.x, p.y]).collect()
+}
+
+impl Scene {
+    /// The scene as a `SCHEMA_VERSION` document, shapes in id order.
+    pub fn to_document(&self) -> SceneDocument {
+        SceneDocument {
+            version: SCHEMA_VERSION,
+            boundary: self.boundary_polygon().map(to_pairs),
+            shapes: self
+                .placed_shapes()
+                .into_iter()
+                .map(|s| ShapeRecord {
+                    id: s.id,
+                    vertices: Some(to_pairs(&s.polygon)),
+                    world_vertices: to_pairs(&s.world),
+                    transform: s.transform.clone(),
+                    metadata: s.metadata.clone(),
+                })
+                .collect(),
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(&self.to_document()).unwrap_or_default()
+    }
+
+    /// Rebuilds a scene from a document of any version up to `SCHEMA_VERSION`,
+    /// keeping shape ids and metadata, then validates every shape against the
+    /// others and the boundary.
+    pub fn from_document(document: SceneDocument) -> Result<(Scene, ImportReport), String> {
+        if document.version > SCHEMA_VERSION {
+            return Err(format!(
+                "Scene schema version {} is newer than this engine supports ({SCHEMA_VERSION})",
+                document.version
+            ));
+        }
+
+        let mut scene = Scene::new();
+        if let Some(boundary) = document.boundary {
+            scene.set_boundary(Polygon::custom(boundary).map_err(|e| format!("Boundary: {e}"))?);
+        }
+
+        let mut seen = HashSet::new();
+        for record in document.shapes {
+            if !seen.insert(record.id) {
+                return Err(format!("Duplicate shape id {}", record.id));
+            }
+            // Ids of new shapes continue after the highest loaded one.
+            if record.id.checked_add(1).is_none() {
+                return Err(format!("Shape id {} is too large: no id is left after it", record.id));
+            }
+            let local = match record.vertices {
+                Some(vertices) => vertices,
+                None => record
+                    .world_vertices
+                    .iter()
+                    .map(|&[x, y]| {
+                        let p = record.transform.apply_inverse(&[x, y].into());
+                        [p.x, p.y]
+                    })
+                    .collect(),
+            };
+            let polygon = Polygon::custom(local).map_err(|e| format!("Shape {}: {e}", record.id))?;
+            scene.insert_unchecked(record.id, polygon, record.transform, record.metadata);
+        }
+
+        let mut report = ImportReport { version: document.version, shapes: seen.len(), invalid: Vec::new() };
+        for shape in scene.placed_shapes() {
+            let result = scene.validate_move(shape.id, &shape.transform);
+            if !result.valid {
+                report.invalid.push(InvalidShape {
+                    id: shape.id,
+                    collisions: result.collisions,
+                    exceeds_boundary: result.exceeds_boundary,
+                });
+            }
+        }
+        Ok((scene, report))
+    }
+
+    pub fn from_json(json: &str) -> Result<(Scene, ImportReport), String> {
+        let document: SceneDocument =
+            serde_json::from_str(json).map_err(|e| format!("Invalid scene JSON: {e}"))?;
+        Self::from_document(document)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_scene() -> Scene {
+        let mut scene = Scene::new();
+        scene.set_rectangle_boundary_for_test(20.0, 20.0);
+        let metadata = ShapeMetadata { name: Some("sofa".into()), color: Some("#336699".into()) };
+        scene.add_shape(Polygon::l_shape(4.0, 3.0, 2.0, 1.5), Transform2D::from_degrees(5.0, 5.0, 30.0), metadata).unwrap();
+        scene.add_shape(Polygon::rectangle(2.0, 1.0), Transform2D::new(15.0, 15.0, 0.0), Default::default()).unwrap();
+        scene
+    }
+
+    impl Scene {
+        fn set_rectangle_boundary_for_test(&mut self, w: f64, h: f64) {
+            self.set_boundary(Polygon::custom(vec![[0.0, 0.0], [w, 0.0], [w, h], [0.0, h]]).unwrap());
+        }
+    }
+
+    fn assert_close(a: &[[f64; 2]], b: &[[f64; 2]]) {
+        assert_eq!(a.len(), b.len());
+        for (p, q) in a.iter().zip(b) {
+            assert!((p[0] - q[0]).abs() < 1e-9 && (p[1] - q[1]).abs() < 1e-9, "{p:?} != {q:?}");
+        }
+    }
+
+    #[test]
+    fn round_trip_keeps_ids_metadata_and_geometry() {
+        let scene = sample_scene();
+        let (loaded, report) = Scene::from_json(&scene.to_json()).unwrap();
+        assert_eq!(report.shapes, 2);
+        assert!(report.invalid.is_empty());
+        assert_eq!(loaded.boundary(), scene.boundary());
+        for (a, b) in scene.placed_shapes().into_iter().zip(loaded.placed_shapes()) {
+            assert_eq!(a.id, b.id);
+            assert_eq!(a.metadata.name, b.metadata.name);
+            assert_eq!(a.metadata.color, b.metadata.color);
+            assert_close(&to_pairs(&a.polygon), &to_pairs(&b.polygon));
+            assert_close(&to_pairs(&a.world), &to_pairs(&b.world));
+        }
+    }
+
+    #[test]
+    fn new_ids_continue_after_the_loaded_ones() {
+        let (mut loaded, _) = Scene::from_json(&sample_scene().to_json()).unwrap();
+        let id = loaded.add_shape(Polygon::rectangle(1.0, 1.0), Transform2D::new(10.0, 2.0, 0.0), Default::default()).unwrap();
+        assert_eq!(id, 2);
+    }
+
+    #[test]
+    fn version_0_documents_recover_local_vertices() {
+        let scene = sample_scene();
+        let mut document = scene.to_document();
+        document.version = 0;
+        for shape in &mut document.shapes {
+            shape.vertices = None;
+        }
+        let json = serde_json::to_string(&document).unwrap().replace("\"version\":0,", "");
+        let (loaded, report) = Scene::from_json(&json).unwrap();
+        assert_eq!(report.version, 0);
+        for (a, b) in scene.placed_shapes().into_iter().zip(loaded.placed_shapes()) {
+            assert_close(&to_pairs(&a.polygon), &to_pairs(&b.polygon));
+        }
+    }
+
+    #[test]
+    fn newer_schema_is_rejected() {
+        let mut document = sample_scene().to_document();
+        document.version = SCHEMA_VERSION + 1;
+        assert!(Scene::from_document(document).unwrap_err().contains("newer"));
+    }
+
+    #[test]
+    fn duplicate_ids_are_rejected() {
+        let mut document = sample_scene().to_document();
+        document.shapes[1].id = document.shapes[0].id;
+        assert!(Scene::from_document(document).unwrap_err().contains("Duplicate"));
+    }
+
+    #[test]
+    fn the_largest_id_is_rejected() {
+        let mut document = sample_scene().to_document();
+        document.shapes[1].id = ObjectId::MAX;
+        assert!(Scene::from_document(document).unwrap_err().contains("too large"));
+    }
+
+    #[test]
+    fn invalid_shapes_are_loaded_and_reported() {
+        let mut document = sample_scene().to_document();
+        // Shrink the room so the shape at (15, 15) is outside it
+        document.boundary = Some(vec![[0.0, 0.0], [12.0, 0.0], [12.0, 12.0], [0.0, 12.0]]);
+        let (loaded, report) = Scene::from_document(document).unwrap();
+        assert_eq!(loaded.all_shapes().len(), 2);
+        assert_eq!(report.invalid.len(), 1);
+        assert_eq!(report.invalid[0].id, 1);
+        assert!(report.invalid[0].exceeds_boundary);
+    }
+
+    #[test]
+    fn overlapping_shapes_report_each_other() {
+        let mut document = sample_scene().to_document();
+        document.shapes[1].transform = document.shapes[0].transform.clone();
+        document.shapes[1].vertices = Some(vec![[-0.5, -0.5], [0.5, -0.5], [0.5, 0.5], [-0.5, 0.5]]);
+        let (_, report) = Scene::from_document(document).unwrap();
+        let ids: Vec<_> = report.invalid.iter().map(|s| (s.id, s.collisions.clone())).collect();
+        assert_eq!(ids, vec![(0, vec![1]), (1, vec![0])]);
+    }
+}
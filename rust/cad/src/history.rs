@@ -0,0 +1,362 @@
+use crate::polygon::Polygon;
+use crate::scene::{ObjectId, PlacementError, Scene, ShapeMetadata};
+use crate::transform::Transform2D;
+
+/// Undo steps kept before the oldest is dropped.
+const MAX_STEPS: usize = 200;
+
+/// One scene edit, holding what it needs to be applied again or reversed.
+#[derive(Debug, Clone)]
+enum Command {
+    Add { id: ObjectId, polygon: Polygon, transform: Transform2D, metadata: ShapeMetadata },
+    Remove { id: ObjectId, polygon: Polygon, transform: Transform2D, metadata: ShapeMetadata },
+    Move { id: ObjectId, from: Transform2D, to: Transform2D },
+    SetBoundary { from: Option<Polygon>, to: Option<Polygon> },
+}
+
+impl Command {
+    fn inverse(&self) -> Command {
+        match self.clone() {
+            Self::Add { id, polygon, transform, metadata } => Self::Remove { id, polygon, transform, metadata },
+            Self::Remove { id, polygon, transform, metadata } => Self::Add { id, polygon, transform, metadata },
+            Self::Move { id, from, to } => Self::Move { id, from: to, to: from },
+            Self::SetBoundary { from, to } => Self::SetBoundary { from: to, to: from },
+        }
+    }
+
+    /// Replays the command, validated as the matching `Scene` method would be.
+    fn apply(&self, scene: &mut Scene) -> Result<(), PlacementError> {
+        match self {
+            Self::Add { id, polygon, transform, metadata } => {
+                scene.restore_shape(*id, polygon.clone(), transform.clone(), metadata.clone())
+            }
+            Self::Remove { id, .. } => match scene.remove_shape(*id) {
+                true => Ok(()),
+                false => Err(PlacementError::ObjectNotFound(*id)),
+            },
+            Self::Move { id, to, .. } => scene.move_shape(*id, to.clone()),
+            Self::SetBoundary { to, .. } => scene.replace_boundary(to.clone()),
+        }
+    }
+}
+
+/// Applies `commands` in order, all or nothing: if one is rejected, the ones
+/// before it are reversed and the scene is left as it was.
+fn apply_all(scene: &mut Scene, commands: &[Command]) -> Result<(), PlacementError> {
+    for (i, command) in commands.iter().enumerate() {
+        if let Err(e) = command.apply(scene) {
+            for done in commands[..i].iter().rev() {
+                // Back to a state the scene was just in, so this can't be rejected
+                let _ = done.inverse().apply(scene);
+            }
+            return Err(e);
+        }
+    }
+    Ok(())
+}
+
+/// Undo/redo for a `Scene`: edits made through these methods are recorded as
+/// commands, and each undo or redo step replays them through the scene's own
+/// validation. A step that no longer fits — e.g. undoing a removal after
+/// something else was put in that spot — fails with the `PlacementError` and
+/// leaves both the scene and the history unchanged.
+///
+/// Edits between `begin_group` and `end_group` form one step, so moving several
+/// shapes together is undone together.
+#[derive(Debug, Default)]
+pub struct History {
+    undo: Vec<Vec<Command>>,
+    redo: Vec<Vec<Command>>,
+    group: Option<Vec<Command>>,
+}
+
+impl History {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_shape(
+        &mut self,
+        scene: &mut Scene,
+        polygon: Polygon,
+        transform: Transform2D,
+        metadata: ShapeMetadata,
+    ) -> Result<ObjectId, PlacementError> {
+        let id = scene.add_shape(polygon.clone(), transform.clone(), metadata.clone())?;
+        self.record(Command::Add { id, polygon, transform, metadata });
+        Ok(id)
+    }
+
+    pub fn move_shape(&mut self, scene: &mut Scene, id: ObjectId, transform: Transform2D) -> Result<(), PlacementError> {
+        let from = scene
+            .placed_shape(id)
+            .map(|s| s.transform.clone())
+            .ok_or(PlacementError::ObjectNotFound(id))?;
+        scene.move_shape(id, transform.clone())?;
+        self.record(Command::Move { id, from, to: transform });
+        Ok(())
+    }
+
+    pub fn remove_shape(&mut self, scene: &mut Scene, id: ObjectId) -> bool {
+        let Some(shape) = scene.placed_shape(id) else {
+            return false;
+        };
+        let command = Command::Remove {
+            id,
+            polygon: shape.polygon.clone(),
+            transform: shape.transform.clone(),
+            metadata: shape.metadata.clone(),
+        };
+        scene.remove_shape(id);
+        self.record(command);
+        true
+    }
+
+    pub fn set_boundary(&mut self, scene: &mut Scene, polygon: Polygon) {
+        let from = scene.boundary_polygon().cloned();
+        scene.set_boundary(polygon.clone());
+        self.record(Command::SetBoundary { from, to: Some(polygon) });
+    }
+
+    /// Starts collecting edits into one step. Nested calls join the open group.
+    pub fn begin_group(&mut self) {
+        self.group.get_or_insert_with(Vec::new);
+    }
+
+    /// Closes the open group as one undo step (nothing, if it has no edits).
+    pub fn end_group(&mut self) {
+        if let Some(group) = self.group.take() {
+            if !group.is_empty() {
+                self.push_undo(group);
+            }
+        }
+    }
+
+    /// Reverses the last step (closing an open group first). Returns false if
+    /// there was nothing to undo.
+    pub fn undo(&mut self, scene: &mut Scene) -> Result<bool, PlacementError> {
+        self.end_group();
+        let Some(step) = self.undo.pop() else {
+            return Ok(false);
+        };
+        let inverse: Vec<Command> = step.iter().rev().map(Command::inverse).collect();
+        match apply_all(scene, &inverse) {
+            Ok(()) => {
+                self.redo.push(step);
+                Ok(true)
+            }
+            Err(e) => {
+                self.undo.push(step);
+                Err(e)
+            }
+        }
+    }
+
+    /// Re-applies the last undone step. Returns false if there was nothing to redo.
+    pub fn redo(&mut self, scene: &mut Scene) -> Result<bool, PlacementError> {
+        self.end_group();
+        let Some(step) = self.redo.pop() else {
+            return Ok(false);
+        };
+        match apply_all(scene, &step) {
+            Ok(()) => {
+                self.undo.push(step);
+                Ok(true)
+            }
+            Err(e) => {
+                self.redo.push(step);
+                Err(e)
+            }
+        }
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty() || self.group.as_ref().is_some_and(|g| !g.is_empty())
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    /// Forgets every step, e.g. when a different scene is loaded.
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    fn record(&mut self, command: Command) {
+        self.redo.clear();
+        match &mut self.group {
+            Some(group) => group.push(command),
+            None => self.push_undo(vec![command]),
+        }
+    }
+
+    fn push_undo(&mut self, step: Vec<Command>) {
+        if self.undo.len() == MAX_STEPS {
+            self.undo.remove(0);
+        }
+        self.undo.push(step);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn room() -> (Scene, History) {
+        let mut scene = Scene::new();
+        scene.set_boundary(Polygon::custom(vec![[0.0, 0.0], [20.0, 0.0], [20.0, 20.0], [0.0, 20.0]]).unwrap());
+        (scene, History::new())
+    }
+
+    fn add_square(scene: &mut Scene, history: &mut History, x: f64, y: f64) -> ObjectId {
+        history.add_shape(scene, Polygon::rectangle(2.0, 2.0), Transform2D::new(x, y, 0.0), Default::default()).unwrap()
+    }
+
+    fn position(scene: &Scene, id: ObjectId) -> (f64, f64) {
+        let t = &scene.placed_shape(id).unwrap().transform;
+        (t.translation.x, t.translation.y)
+    }
+
+    #[test]
+    fn undo_and_redo_an_add() {
+        let (mut scene, mut history) = room();
+        let id = add_square(&mut scene, &mut history, 5.0, 5.0);
+        assert!(history.can_undo());
+        assert!(history.undo(&mut scene).unwrap());
+        assert!(scene.placed_shape(id).is_none());
+        assert!(!history.can_undo());
+        assert!(history.can_redo());
+        assert!(history.redo(&mut scene).unwrap());
+        // Back under the same id
+        assert_eq!(position(&scene, id), (5.0, 5.0));
+    }
+
+    #[test]
+    fn undo_a_move_and_a_remove() {
+        let (mut scene, mut history) = room();
+        let id = add_square(&mut scene, &mut history, 5.0, 5.0);
+        history.move_shape(&mut scene, id, Transform2D::new(10.0, 10.0, 0.0)).unwrap();
+        assert!(history.remove_shape(&mut scene, id));
+        history.undo(&mut scene).unwrap();
+        assert_eq!(position(&scene, id), (10.0, 10.0));
+        history.undo(&mut scene).unwrap();
+        assert_eq!(position(&scene, id), (5.0, 5.0));
+    }
+
+    #[test]
+    fn rejected_edits_are_not_recorded() {
+        let (mut scene, mut history) = room();
+        let a = add_square(&mut scene, &mut history, 5.0, 5.0);
+        add_square(&mut scene, &mut history, 10.0, 5.0);
+        assert!(history.move_shape(&mut scene, a, Transform2D::new(10.0, 5.0, 0.0)).is_err());
+        assert!(!history.remove_shape(&mut scene, 99));
+        history.undo(&mut scene).unwrap();
+        history.undo(&mut scene).unwrap();
+        assert!(!history.undo(&mut scene).unwrap());
+    }
+
+    #[test]
+    fn a_new_edit_clears_redo() {
+        let (mut scene, mut history) = room();
+        add_square(&mut scene, &mut history, 5.0, 5.0);
+        history.undo(&mut scene).unwrap();
+        add_square(&mut scene, &mut history, 10.0, 10.0);
+        assert!(!history.can_redo());
+        assert!(!history.redo(&mut scene).unwrap());
+    }
+
+    #[test]
+    fn a_group_is_one_step() {
+        let (mut scene, mut history) = room();
+        let a = add_square(&mut scene, &mut history, 5.0, 5.0);
+        let b = add_square(&mut scene, &mut history, 10.0, 5.0);
+        history.begin_group();
+        history.move_shape(&mut scene, a, Transform2D::new(5.0, 12.0, 0.0)).unwrap();
+        history.move_shape(&mut scene, b, Transform2D::new(10.0, 12.0, 0.0)).unwrap();
+        history.end_group();
+        history.undo(&mut scene).unwrap();
+        assert_eq!(position(&scene, a), (5.0, 5.0));
+        assert_eq!(position(&scene, b), (10.0, 5.0));
+        history.redo(&mut scene).unwrap();
+        assert_eq!(position(&scene, a), (5.0, 12.0));
+        assert_eq!(position(&scene, b), (10.0, 12.0));
+    }
+
+    #[test]
+    fn undo_is_revalidated_and_all_or_nothing() {
+        let (mut scene, mut history) = room();
+        let a = add_square(&mut scene, &mut history, 5.0, 5.0);
+        let b = add_square(&mut scene, &mut history, 10.0, 5.0);
+        history.begin_group();
+        history.move_shape(&mut scene, a, Transform2D::new(5.0, 12.0, 0.0)).unwrap();
+        history.move_shape(&mut scene, b, Transform2D::new(10.0, 12.0, 0.0)).unwrap();
+        history.end_group();
+        // Edited outside the history: something now sits where `a` came from
+        let c = scene.add_shape(Polygon::rectangle(1.0, 1.0), Transform2D::new(5.0, 5.0, 0.0), Default::default()).unwrap();
+
+        let err = history.undo(&mut scene).unwrap_err();
+        assert!(matches!(err, PlacementError::CollidesWithObject { other_id } if other_id == c));
+        // `b` was moved back before `a` failed; that was reverted too
+        assert_eq!(position(&scene, b), (10.0, 12.0));
+        assert!(history.can_undo());
+
+        scene.remove_shape(c);
+        assert!(history.undo(&mut scene).unwrap());
+        assert_eq!(position(&scene, a), (5.0, 5.0));
+    }
+
+    #[test]
+    fn undo_a_boundary_change() {
+        let (mut scene, mut history) = room();
+        let before = scene.boundary();
+        history.set_boundary(&mut scene, Polygon::l_shape(20.0, 20.0, 10.0, 10.0));
+        assert_ne!(scene.boundary(), before);
+        history.undo(&mut scene).unwrap();
+        assert_eq!(scene.boundary(), before);
+
+        let mut empty = Scene::new();
+        history.set_boundary(&mut empty, Polygon::rectangle(4.0, 4.0));
+        history.undo(&mut empty).unwrap();
+        assert_eq!(empty.boundary(), None);
+    }
+
+    #[test]
+    fn undoing_a_larger_room_revalidates_the_shapes() {
+        let (mut scene, mut history) = room();
+        let a = add_square(&mut scene, &mut history, 5.0, 5.0);
+        let larger = Polygon::custom(vec![[0.0, 0.0], [40.0, 0.0], [40.0, 20.0], [0.0, 20.0]]).unwrap();
+        history.begin_group();
+        history.set_boundary(&mut scene, larger);
+        history.move_shape(&mut scene, a, Transform2D::new(5.0, 12.0, 0.0)).unwrap();
+        history.end_group();
+        // Edited outside the history: a shape in the space the smaller room lacks
+        let c = scene.add_shape(Polygon::rectangle(2.0, 2.0), Transform2D::new(30.0, 10.0, 0.0), Default::default()).unwrap();
+
+        let before = scene.boundary();
+        let err = history.undo(&mut scene).unwrap_err();
+        assert!(matches!(err, PlacementError::ExceedsBoundary));
+        // The move was undone before the boundary failed; that was reverted too
+        assert_eq!(scene.boundary(), before);
+        assert_eq!(position(&scene, a), (5.0, 12.0));
+        assert!(history.can_undo());
+
+        scene.remove_shape(c);
+        assert!(history.undo(&mut scene).unwrap());
+        assert_eq!(position(&scene, a), (5.0, 5.0));
+        assert_ne!(scene.boundary(), before);
+    }
+
+    #[test]
+    fn oldest_steps_are_dropped() {
+        let (mut scene, mut history) = room();
+        let id = add_square(&mut scene, &mut history, 5.0, 5.0);
+        for i in 0..MAX_STEPS {
+            let x = if i % 2 == 0 { 6.0 } else { 5.0 };
+            history.move_shape(&mut scene, id, Transform2D::new(x, 5.0, 0.0)).unwrap();
+        }
+        while history.undo(&mut scene).unwrap() {}
+        // The add fell off the end, so the shape is still there
+        assert!(scene.placed_shape(id).is_some());
+    }
+}
@@ -1,11 +1,18 @@
 mod boundary;
 mod collision;
+mod document;
+mod history;
 mod polygon;
 mod scene;
+mod snap;
+mod spatial;
 mod transform;
 
+pub use document::{ImportReport, InvalidShape, SceneDocument, ShapeRecord, SCHEMA_VERSION};
+pub use history::History;
 pub use polygon::{Aabb, Polygon};
 pub use scene::{ObjectId, PlacedShapeInfo, PlacementError, Scene, ShapeMetadata, ValidationResult};
+pub use snap::{SnapOptions, SnapResult, SnapTarget};
 pub use transform::Transform2D;
 
 use serde::Serialize;
@@ -23,13 +30,23 @@ fn placement_err_to_js(e: PlacementError) -> JsValue {
 #[wasm_bindgen]
 pub struct CadEngine {
     scene: Scene,
+    history: History,
 }
 
+impl Default for CadEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// JS callers pass shape dimensions and placement as flat numbers, so some of
+/// these take more arguments than clippy would like.
 #[wasm_bindgen]
+#[allow(clippy::too_many_arguments)]
 impl CadEngine {
     #[wasm_bindgen(constructor)]
     pub fn new() -> Self {
-        Self { scene: Scene::new() }
+        Self { scene: Scene::new(), history: History::new() }
     }
 
     /// Set the room boundary from [[x,y], ...] vertices.
@@ -37,7 +54,7 @@ impl CadEngine {
     pub fn set_boundary(&mut self, vertices: JsValue) -> Result<(), JsValue> {
         let verts = js_to_vertices(&vertices)?;
         let polygon = Polygon::custom(verts).map_err(|e| JsValue::from_str(&e))?;
-        self.scene.set_boundary(polygon);
+        self.history.set_boundary(&mut self.scene, polygon);
         Ok(())
     }
 
@@ -47,19 +64,19 @@ impl CadEngine {
         let poly = Polygon::custom(vec![
             [0.0, 0.0], [width, 0.0], [width, height], [0.0, height],
         ]).unwrap();
-        self.scene.set_boundary(poly);
+        self.history.set_boundary(&mut self.scene, poly);
     }
 
     /// Set an L-shaped room boundary (origin at 0,0).
     #[wasm_bindgen(js_name = "setLShapeBoundary")]
     pub fn set_l_shape_boundary(&mut self, outer_w: f64, outer_h: f64, cutout_w: f64, cutout_h: f64) {
-        self.scene.set_boundary(Polygon::l_shape(outer_w, outer_h, cutout_w, cutout_h));
+        self.history.set_boundary(&mut self.scene, Polygon::l_shape(outer_w, outer_h, cutout_w, cutout_h));
     }
 
     /// Set a U-shaped room boundary (origin at 0,0).
     #[wasm_bindgen(js_name = "setUShapeBoundary")]
     pub fn set_u_shape_boundary(&mut self, outer_w: f64, outer_h: f64, channel_w: f64, channel_h: f64) {
-        self.scene.set_boundary(Polygon::u_shape(outer_w, outer_h, channel_w, channel_h));
+        self.history.set_boundary(&mut self.scene, Polygon::u_shape(outer_w, outer_h, channel_w, channel_h));
     }
 
     /// Add a rectangle. Returns the object ID.
@@ -74,7 +91,7 @@ impl CadEngine {
     ) -> Result<u64, JsValue> {
         let poly = Polygon::rectangle(width, height);
         let t = Transform2D::from_degrees(x, y, rotation_deg);
-        self.scene.add_shape(poly, t, Default::default()).map_err(placement_err_to_js)
+        self.history.add_shape(&mut self.scene, poly, t, Default::default()).map_err(placement_err_to_js)
     }
 
     /// Add an L-shape. Returns the object ID.
@@ -91,7 +108,7 @@ impl CadEngine {
     ) -> Result<u64, JsValue> {
         let poly = Polygon::l_shape(outer_w, outer_h, cutout_w, cutout_h);
         let t = Transform2D::from_degrees(x, y, rotation_deg);
-        self.scene.add_shape(poly, t, Default::default()).map_err(placement_err_to_js)
+        self.history.add_shape(&mut self.scene, poly, t, Default::default()).map_err(placement_err_to_js)
     }
 
     /// Add a U-shape. Returns the object ID.
@@ -108,7 +125,7 @@ impl CadEngine {
     ) -> Result<u64, JsValue> {
         let poly = Polygon::u_shape(outer_w, outer_h, channel_w, channel_h);
         let t = Transform2D::from_degrees(x, y, rotation_deg);
-        self.scene.add_shape(poly, t, Default::default()).map_err(placement_err_to_js)
+        self.history.add_shape(&mut self.scene, poly, t, Default::default()).map_err(placement_err_to_js)
     }
 
     /// Add a custom polygon from [[x,y], ...] vertices. Returns the object ID.
@@ -123,20 +140,58 @@ impl CadEngine {
         let verts = js_to_vertices(&vertices)?;
         let poly = Polygon::custom(verts).map_err(|e| JsValue::from_str(&e))?;
         let t = Transform2D::from_degrees(x, y, rotation_deg);
-        self.scene.add_shape(poly, t, Default::default()).map_err(placement_err_to_js)
+        self.history.add_shape(&mut self.scene, poly, t, Default::default()).map_err(placement_err_to_js)
     }
 
     /// Move/rotate an existing shape. rotation_deg is in degrees.
     #[wasm_bindgen(js_name = "moveShape")]
     pub fn move_shape(&mut self, id: u64, x: f64, y: f64, rotation_deg: f64) -> Result<(), JsValue> {
         let t = Transform2D::from_degrees(x, y, rotation_deg);
-        self.scene.move_shape(id, t).map_err(placement_err_to_js)
+        self.history.move_shape(&mut self.scene, id, t).map_err(placement_err_to_js)
+    }
+
+    /// Snap a proposed move of a shape to the grid, nearby shapes, the walls and
+    /// rotation steps. `options` is a partial SnapOptions ({grid, distance,
+    /// rotation_step, rotation_tolerance, shapes, walls}), or undefined for the
+    /// defaults. Returns {x, y, rotation_deg, targets}; the result is not
+    /// validated, so follow it with validateMove/moveShape.
+    pub fn snap(&self, id: u64, x: f64, y: f64, rotation_deg: f64, options: JsValue) -> Result<JsValue, JsValue> {
+        #[derive(Serialize)]
+        struct Snapped {
+            x: f64,
+            y: f64,
+            rotation_deg: f64,
+            targets: Vec<SnapTarget>,
+        }
+        let options: SnapOptions = if options.is_undefined() || options.is_null() {
+            SnapOptions::default()
+        } else {
+            serde_wasm_bindgen::from_value(options).map_err(|e| JsValue::from_str(&e.to_string()))?
+        };
+        let t = Transform2D::from_degrees(x, y, rotation_deg);
+        let result = self.scene.snap(id, &t, &options).map_err(placement_err_to_js)?;
+        // The snapped step itself, rather than a round trip through radians
+        let rotation_deg = result
+            .targets
+            .iter()
+            .find_map(|t| match t {
+                SnapTarget::Rotation { degrees } => Some(*degrees),
+                _ => None,
+            })
+            .unwrap_or(rotation_deg);
+        let snapped = Snapped {
+            x: result.transform.translation.x,
+            y: result.transform.translation.y,
+            rotation_deg,
+            targets: result.targets,
+        };
+        serde_wasm_bindgen::to_value(&snapped).map_err(|e| JsValue::from_str(&e.to_string()))
     }
 
     /// Remove a shape by ID. Returns true if it existed.
     #[wasm_bindgen(js_name = "removeShape")]
     pub fn remove_shape(&mut self, id: u64) -> bool {
-        self.scene.remove_shape(id)
+        self.history.remove_shape(&mut self.scene, id)
     }
 
     /// Validate moving an existing shape (excludes self from collision check).
@@ -240,19 +295,54 @@ impl CadEngine {
         }
     }
 
-    /// Export entire scene as a JSON string.
+    /// Export entire scene as a JSON string (a versioned `SceneDocument`).
     #[wasm_bindgen(js_name = "exportJson")]
     pub fn export_json(&self) -> String {
-        #[derive(Serialize)]
-        struct Export {
-            boundary: Option<Vec<[f64; 2]>>,
-            shapes: Vec<PlacedShapeInfo>,
-        }
-        let export = Export {
-            boundary: self.scene.boundary(),
-            shapes: self.scene.all_shapes(),
-        };
-        serde_json::to_string(&export).unwrap_or_default()
+        self.scene.to_json()
+    }
+
+    /// Replace the scene with one from `exportJson`, keeping shape ids.
+    /// Returns {version, shapes, invalid: [{id, collisions, exceeds_boundary}]};
+    /// on error the current scene is left as it was.
+    #[wasm_bindgen(js_name = "importJson")]
+    pub fn import_json(&mut self, json: &str) -> Result<JsValue, JsValue> {
+        let (scene, report) = Scene::from_json(json).map_err(|e| JsValue::from_str(&e))?;
+        self.scene = scene;
+        self.history.clear();
+        serde_wasm_bindgen::to_value(&report).map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// Undo the last edit (or group). Returns false if there was nothing to undo;
+    /// throws, changing nothing, if the edit no longer fits the scene.
+    pub fn undo(&mut self) -> Result<bool, JsValue> {
+        self.history.undo(&mut self.scene).map_err(placement_err_to_js)
+    }
+
+    /// Redo the last undone edit (or group). Returns false if there was nothing to redo.
+    pub fn redo(&mut self) -> Result<bool, JsValue> {
+        self.history.redo(&mut self.scene).map_err(placement_err_to_js)
+    }
+
+    #[wasm_bindgen(js_name = "canUndo")]
+    pub fn can_undo(&self) -> bool {
+        self.history.can_undo()
+    }
+
+    #[wasm_bindgen(js_name = "canRedo")]
+    pub fn can_redo(&self) -> bool {
+        self.history.can_redo()
+    }
+
+    /// Start grouping edits into one undo step, e.g. moving a selection.
+    #[wasm_bindgen(js_name = "beginGroup")]
+    pub fn begin_group(&mut self) {
+        self.history.begin_group();
+    }
+
+    /// Close the group started by `beginGroup`.
+    #[wasm_bindgen(js_name = "endGroup")]
+    pub fn end_group(&mut self) {
+        self.history.end_group();
     }
 
     /// Get boundary vertices as [[x,y], ...], or null if none set.
@@ -152,8 +152,7 @@ impl Polygon {
             return vec![self.clone()];
         }
         // Fall back to triangulation — produces convex triangles
-        let triangles = self.triangulate();
-        triangles
+        self.triangulate()
     }
 
     fn triangulate(&self) -> Vec<Polygon> {
@@ -4,24 +4,19 @@ use parry2d_f64::shape::ConvexPolygon;
 use serde::{Deserialize, Serialize};
 
 use crate::boundary::Boundary;
-use crate::collision::shapes_collide;
-use crate::polygon::Polygon;
+use crate::collision::parts_collide;
+use crate::polygon::{Aabb, Polygon};
+use crate::spatial::SpatialGrid;
 use crate::transform::Transform2D;
 
 pub type ObjectId = u64;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ShapeMetadata {
     pub name: Option<String>,
     pub color: Option<String>,
 }
 
-impl Default for ShapeMetadata {
-    fn default() -> Self {
-        Self { name: None, color: None }
-    }
-}
-
 #[derive(Debug, Clone)]
 pub struct PlacedShape {
     pub id: ObjectId,
@@ -29,6 +24,22 @@ pub struct PlacedShape {
     pub convex_parts: Vec<ConvexPolygon>,
     pub transform: Transform2D,
     pub metadata: ShapeMetadata,
+    /// `polygon` under `transform`, and its bounding box. Cached so validation
+    /// and hit-testing don't transform every shape again on every call; only
+    /// `Scene::place` may change `transform`, and it refreshes both.
+    pub world: Polygon,
+    pub aabb: Aabb,
+}
+
+impl PlacedShape {
+    fn info(&self) -> PlacedShapeInfo {
+        PlacedShapeInfo {
+            id: self.id,
+            world_vertices: self.world.vertices().iter().map(|p| [p.x, p.y]).collect(),
+            transform: self.transform.clone(),
+            metadata: self.metadata.clone(),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -68,6 +79,9 @@ pub struct ValidationResult {
 #[derive(Debug, Default)]
 pub struct Scene {
     objects: HashMap<ObjectId, PlacedShape>,
+    /// World-space AABBs of `objects`, so a placement is only tested against
+    /// the shapes near it.
+    index: SpatialGrid,
     boundary: Option<Boundary>,
     next_id: ObjectId,
 }
@@ -87,19 +101,84 @@ impl Scene {
         transform: Transform2D,
         metadata: ShapeMetadata,
     ) -> Result<ObjectId, PlacementError> {
-        let result = self.validate_for(&polygon, &transform, None);
-        if let Some(id) = result.collisions.first() {
-            return Err(PlacementError::CollidesWithObject { other_id: *id });
-        }
-        if result.exceeds_boundary {
-            return Err(PlacementError::ExceedsBoundary);
+        self.check(&polygon, &transform, None)?;
+        let id = self.next_id;
+        self.insert_unchecked(id, polygon, transform, metadata);
+        Ok(id)
+    }
+
+    /// Puts a removed shape back under its old id, validated like `add_shape`
+    /// (for undoing a removal or redoing an add).
+    pub(crate) fn restore_shape(
+        &mut self,
+        id: ObjectId,
+        polygon: Polygon,
+        transform: Transform2D,
+        metadata: ShapeMetadata,
+    ) -> Result<(), PlacementError> {
+        if self.objects.contains_key(&id) {
+            return Err(PlacementError::CollidesWithObject { other_id: id });
         }
+        self.check(&polygon, &transform, None)?;
+        self.insert_unchecked(id, polygon, transform, metadata);
+        Ok(())
+    }
 
-        let id = self.next_id;
-        self.next_id += 1;
+    /// Places a shape under a given id without validating it (for loading a
+    /// saved scene, which validates afterwards). Replaces any shape with that id.
+    pub(crate) fn insert_unchecked(
+        &mut self,
+        id: ObjectId,
+        polygon: Polygon,
+        transform: Transform2D,
+        metadata: ShapeMetadata,
+    ) {
         let convex_parts = polygon.to_convex_parts();
-        self.objects.insert(id, PlacedShape { id, polygon, convex_parts, transform, metadata });
-        Ok(id)
+        let world = polygon.transformed(&transform);
+        let aabb = world.bounding_box();
+        self.index.insert(id, aabb.clone());
+        self.objects.insert(
+            id,
+            PlacedShape { id, polygon, convex_parts, transform, metadata, world, aabb },
+        );
+        // `from_document` rejects `ObjectId::MAX`, the one id with no successor.
+        if let Some(next) = id.checked_add(1) {
+            self.next_id = self.next_id.max(next);
+        }
+    }
+
+    /// All placed shapes, in id order.
+    pub(crate) fn placed_shapes(&self) -> Vec<&PlacedShape> {
+        let mut shapes: Vec<&PlacedShape> = self.objects.values().collect();
+        shapes.sort_by_key(|s| s.id);
+        shapes
+    }
+
+    pub(crate) fn placed_shape(&self, id: ObjectId) -> Option<&PlacedShape> {
+        self.objects.get(&id)
+    }
+
+    /// Shapes whose bounding boxes overlap `area`, in id order.
+    pub(crate) fn shapes_near(&self, area: &Aabb) -> Vec<&PlacedShape> {
+        self.index.query(area).into_iter().map(|id| &self.objects[&id]).collect()
+    }
+
+    pub(crate) fn boundary_polygon(&self) -> Option<&Polygon> {
+        self.boundary.as_ref().map(Boundary::polygon)
+    }
+
+    /// Sets or clears the boundary for undo/redo. Unlike `set_boundary`, every
+    /// shape already placed must fit inside it; otherwise nothing changes.
+    pub(crate) fn replace_boundary(&mut self, polygon: Option<Polygon>) -> Result<(), PlacementError> {
+        let boundary = polygon.map(Boundary::new);
+        let exceeded = boundary
+            .as_ref()
+            .is_some_and(|b| self.objects.values().any(|s| !b.contains_shape(&s.polygon, &s.transform)));
+        if exceeded {
+            return Err(PlacementError::ExceedsBoundary);
+        }
+        self.boundary = boundary;
+        Ok(())
     }
 
     pub fn move_shape(
@@ -113,19 +192,23 @@ impl Scene {
             .map(|s| s.polygon.clone())
             .ok_or(PlacementError::ObjectNotFound(id))?;
 
-        let result = self.validate_for(&polygon, &new_transform, Some(id));
-        if let Some(other_id) = result.collisions.first() {
-            return Err(PlacementError::CollidesWithObject { other_id: *other_id });
-        }
-        if result.exceeds_boundary {
-            return Err(PlacementError::ExceedsBoundary);
-        }
-
-        self.objects.get_mut(&id).unwrap().transform = new_transform;
+        self.check(&polygon, &new_transform, Some(id))?;
+        self.place(id, new_transform);
         Ok(())
     }
 
+    /// Sets the transform of an existing shape, keeping its cached world
+    /// polygon and its entry in the spatial index in step.
+    fn place(&mut self, id: ObjectId, transform: Transform2D) {
+        let shape = self.objects.get_mut(&id).unwrap();
+        shape.world = shape.polygon.transformed(&transform);
+        shape.aabb = shape.world.bounding_box();
+        shape.transform = transform;
+        self.index.insert(id, shape.aabb.clone());
+    }
+
     pub fn remove_shape(&mut self, id: ObjectId) -> bool {
+        self.index.remove(id);
         self.objects.remove(&id).is_some()
     }
 
@@ -142,34 +225,49 @@ impl Scene {
         }
     }
 
+    /// `validate_for` as an error: the first collision, else the boundary.
+    fn check(
+        &self,
+        polygon: &Polygon,
+        transform: &Transform2D,
+        exclude_id: Option<ObjectId>,
+    ) -> Result<(), PlacementError> {
+        let result = self.validate_for(polygon, transform, exclude_id);
+        if let Some(other_id) = result.collisions.first() {
+            return Err(PlacementError::CollidesWithObject { other_id: *other_id });
+        }
+        if result.exceeds_boundary {
+            return Err(PlacementError::ExceedsBoundary);
+        }
+        Ok(())
+    }
+
     fn validate_for(
         &self,
         polygon: &Polygon,
         transform: &Transform2D,
         exclude_id: Option<ObjectId>,
     ) -> ValidationResult {
-        let mut collisions = Vec::new();
+        let world = polygon.transformed(transform);
         let exceeds_boundary = self
             .boundary
             .as_ref()
             .map(|b| !b.contains_shape(polygon, transform))
             .unwrap_or(false);
 
-        let new_parts = polygon.to_convex_parts();
-
-        for (id, existing) in &self.objects {
-            if exclude_id == Some(*id) {
-                continue;
-            }
-            if shapes_collide(
-                polygon,
-                transform,
-                &new_parts,
-                &existing.polygon,
-                &existing.transform,
-                &existing.convex_parts,
-            ) {
-                collisions.push(*id);
+        // Broad phase: only shapes whose AABBs overlap this one's, in id order.
+        let candidates = self.index.query(&world.bounding_box());
+        let mut collisions = Vec::new();
+        if !candidates.is_empty() {
+            let new_parts = polygon.to_convex_parts();
+            for id in candidates {
+                if exclude_id == Some(id) {
+                    continue;
+                }
+                let existing = &self.objects[&id];
+                if parts_collide(transform, &new_parts, &existing.transform, &existing.convex_parts) {
+                    collisions.push(id);
+                }
             }
         }
 
@@ -181,37 +279,18 @@ impl Scene {
     }
 
     pub fn get_shape_info(&self, id: ObjectId) -> Option<PlacedShapeInfo> {
-        self.objects.get(&id).map(|s| {
-            let world = s.polygon.transformed(&s.transform);
-            PlacedShapeInfo {
-                id: s.id,
-                world_vertices: world.vertices().iter().map(|p| [p.x, p.y]).collect(),
-                transform: s.transform.clone(),
-                metadata: s.metadata.clone(),
-            }
-        })
+        self.objects.get(&id).map(PlacedShape::info)
     }
 
     pub fn all_shapes(&self) -> Vec<PlacedShapeInfo> {
-        self.objects.values().map(|s| {
-            let world = s.polygon.transformed(&s.transform);
-            PlacedShapeInfo {
-                id: s.id,
-                world_vertices: world.vertices().iter().map(|p| [p.x, p.y]).collect(),
-                transform: s.transform.clone(),
-                metadata: s.metadata.clone(),
-            }
-        }).collect()
+        self.objects.values().map(PlacedShape::info).collect()
     }
 
     pub fn query_point(&self, x: f64, y: f64) -> Option<ObjectId> {
-        for (id, shape) in &self.objects {
-            let world = shape.polygon.transformed(&shape.transform);
-            if world.contains_point(x, y) {
-                return Some(*id);
-            }
-        }
-        None
+        self.index
+            .query_point(x, y)
+            .into_iter()
+            .find(|id| self.objects[id].world.contains_point(x, y))
     }
 
     pub fn boundary(&self) -> Option<Vec<[f64; 2]>> {
@@ -360,6 +439,48 @@ mod tests {
         assert!(ok.is_ok());
     }
 
+    #[test]
+    fn moved_shape_is_only_found_at_its_new_position() {
+        let mut scene = Scene::new();
+        let r = Polygon::rectangle(2.0, 2.0);
+        let id = scene.add_shape(r.clone(), Transform2D::new(0.0, 0.0, 0.0), Default::default()).unwrap();
+        scene.move_shape(id, Transform2D::new(10.0, 0.0, 0.0)).unwrap();
+        assert_eq!(scene.query_point(0.0, 0.0), None);
+        assert_eq!(scene.query_point(10.0, 0.0), Some(id));
+        // The old spot is free, the new one taken
+        assert!(scene.validate_placement(&r, &Transform2D::new(0.0, 0.0, 0.0)).valid);
+        assert!(!scene.validate_placement(&r, &Transform2D::new(10.5, 0.0, 0.0)).valid);
+    }
+
+    #[test]
+    fn removed_shape_is_not_hit() {
+        let mut scene = Scene::new();
+        let id = scene.add_shape(Polygon::rectangle(2.0, 2.0), Transform2D::new(0.0, 0.0, 0.0), Default::default()).unwrap();
+        scene.remove_shape(id);
+        assert_eq!(scene.query_point(0.0, 0.0), None);
+    }
+
+    #[test]
+    fn collisions_are_listed_in_id_order() {
+        let mut scene = Scene::new();
+        let r = Polygon::rectangle(2.0, 2.0);
+        let ids: Vec<ObjectId> = (0..3)
+            .map(|i| scene.add_shape(r.clone(), Transform2D::new(i as f64 * 2.5, 0.0, 0.0), Default::default()).unwrap())
+            .collect();
+        let wide = Polygon::rectangle(8.0, 1.0);
+        let result = scene.validate_placement(&wide, &Transform2D::new(2.5, 0.0, 0.0));
+        assert_eq!(result.collisions, ids);
+    }
+
+    #[test]
+    fn shape_info_uses_the_current_transform() {
+        let mut scene = Scene::new();
+        let id = scene.add_shape(Polygon::rectangle(2.0, 2.0), Transform2D::new(0.0, 0.0, 0.0), Default::default()).unwrap();
+        scene.move_shape(id, Transform2D::new(5.0, 5.0, 0.0)).unwrap();
+        let info = scene.get_shape_info(id).unwrap();
+        assert!(info.world_vertices.iter().all(|[x, y]| (4.0..=6.0).contains(x) && (4.0..=6.0).contains(y)));
+    }
+
     #[test]
     fn get_shape_info_returns_correct_id_and_vertex_count() {
         let mut scene = Scene::new();
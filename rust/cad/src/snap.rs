@@ -0,0 +1,407 @@
+use nalgebra::{Point2, Vector2};
+use serde::{Deserialize, Serialize};
+
+use crate::polygon::{Aabb, Polygon};
+use crate::scene::{ObjectId, PlacementError, Scene};
+use crate::transform::Transform2D;
+
+/// Gap left between a snapped shape and the wall or shape it is flush against.
+/// Exact contact with a wall counts as crossing it (see `Boundary::contains_shape`),
+/// and rounding could otherwise leave a hair's overlap with a neighbour.
+const CLEARANCE: f64 = 1e-6;
+
+/// Two snaps are only combined if their directions differ by more than ~15°
+/// (this is the sine of the angle between them). Nearly parallel lines meet
+/// far away, and solving for both would throw the shape across the room.
+const MIN_CROSS: f64 = 0.25;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SnapOptions {
+    /// Grid spacing. The shape's origin snaps to the grid along any direction
+    /// nothing nearer snapped it; 0 turns the grid off.
+    pub grid: f64,
+    /// How near (in world units) a wall, edge or vertex must be to snap to it.
+    pub distance: f64,
+    /// Rotation step in degrees, e.g. 15 or 90; 0 turns rotation snapping off.
+    pub rotation_step: f64,
+    /// How near (in degrees) the rotation must be to a step to snap to it.
+    pub rotation_tolerance: f64,
+    /// Snap to other shapes' edges and line up with their vertices.
+    pub shapes: bool,
+    /// Snap flush against the boundary.
+    pub walls: bool,
+}
+
+impl Default for SnapOptions {
+    fn default() -> Self {
+        Self {
+            grid: 0.0,
+            distance: 0.1,
+            rotation_step: 15.0,
+            rotation_tolerance: 5.0,
+            shapes: true,
+            walls: true,
+        }
+    }
+}
+
+/// What a snap lined the shape up with, for drawing guide lines.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SnapTarget {
+    /// The shape's origin is on this grid point.
+    Grid { point: [f64; 2] },
+    /// Flush against this boundary wall.
+    Wall { from: [f64; 2], to: [f64; 2] },
+    /// Flush against this edge of shape `id`.
+    Edge { id: ObjectId, from: [f64; 2], to: [f64; 2] },
+    /// The snapped shape's point `at` lines up with, or touches, vertex `point` of shape `id`.
+    Vertex { id: ObjectId, point: [f64; 2], at: [f64; 2] },
+    Rotation { degrees: f64 },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapResult {
+    pub transform: Transform2D,
+    pub targets: Vec<SnapTarget>,
+}
+
+/// One snap candidate: move the shape by `d` with `d · normal == offset`.
+struct Constraint {
+    normal: Vector2<f64>,
+    offset: f64,
+    /// Touching a wall or edge, rather than lining up with a vertex or the grid.
+    contact: bool,
+    target: SnapTarget,
+}
+
+impl Scene {
+    /// Where shape `id` lands if dropped at `proposed`, snapped according to
+    /// `options`, and what it snapped to. Rotation snaps first; then the
+    /// nearest contact (wall or edge), or failing that the nearest vertex
+    /// alignment, fixes one direction and the next nearest that isn't parallel
+    /// to it fixes the other, so a shape pushed into a corner ends up flush with
+    /// both walls. The grid fills in whatever is left.
+    ///
+    /// The result is not validated: pass it to `validate_move`/`move_shape` as
+    /// with any other transform.
+    pub fn snap(&self, id: ObjectId, proposed: &Transform2D, options: &SnapOptions) -> Result<SnapResult, PlacementError> {
+        let shape = self.placed_shape(id).ok_or(PlacementError::ObjectNotFound(id))?;
+        let mut transform = proposed.clone();
+        let mut targets = Vec::new();
+        if let Some(degrees) = snap_rotation(proposed.rotation_rad.to_degrees(), options) {
+            transform.rotation_rad = degrees.to_radians();
+            targets.push(SnapTarget::Rotation { degrees });
+        }
+
+        let world = shape.polygon.transformed(&transform);
+        let mut candidates = Vec::new();
+        if options.walls {
+            if let Some(boundary) = self.boundary_polygon() {
+                wall_constraints(&world, boundary, options.distance, &mut candidates);
+            }
+        }
+        if options.shapes {
+            let area = expand(&world.bounding_box(), options.distance);
+            for other in self.shapes_near(&area) {
+                if other.id != id {
+                    shape_constraints(&world, &other.world, other.id, options.distance, &mut candidates);
+                }
+            }
+        }
+
+        let origin = transform.translation;
+        let chosen = choose(candidates, &origin, options.grid);
+        let d = solve(&chosen);
+        transform.translation += d;
+
+        for constraint in chosen {
+            let target = match constraint.target {
+                SnapTarget::Grid { .. } => SnapTarget::Grid { point: [transform.translation.x, transform.translation.y] },
+                SnapTarget::Vertex { id, point, at } => SnapTarget::Vertex { id, point, at: [at[0] + d.x, at[1] + d.y] },
+                target => target,
+            };
+            if !targets.contains(&target) {
+                targets.push(target);
+            }
+        }
+        Ok(SnapResult { transform, targets })
+    }
+}
+
+fn snap_rotation(degrees: f64, options: &SnapOptions) -> Option<f64> {
+    if options.rotation_step <= 0.0 {
+        return None;
+    }
+    let snapped = (degrees / options.rotation_step).round() * options.rotation_step;
+    ((degrees - snapped).abs() <= options.rotation_tolerance).then_some(snapped)
+}
+
+fn expand(aabb: &Aabb, by: f64) -> Aabb {
+    Aabb {
+        min: [aabb.min.x - by, aabb.min.y - by].into(),
+        max: [aabb.max.x + by, aabb.max.y + by].into(),
+    }
+}
+
+fn pair(p: &Point2<f64>) -> [f64; 2] {
+    [p.x, p.y]
+}
+
+fn cross(a: &Vector2<f64>, b: &Vector2<f64>) -> f64 {
+    a.x * b.y - a.y * b.x
+}
+
+/// The unit direction of edge `a`→`b`, its outward normal (polygons are
+/// counter-clockwise, so that is the right-hand side) and its length.
+fn edge_frame(a: &Point2<f64>, b: &Point2<f64>) -> Option<(Vector2<f64>, Vector2<f64>, f64)> {
+    let length = (b - a).norm();
+    if length <= 0.0 {
+        return None;
+    }
+    let dir = (b - a) / length;
+    Some((dir, Vector2::new(dir.y, -dir.x), length))
+}
+
+/// Vertices of the moving shape within `distance` of a wall, pulled to just
+/// inside it.
+fn wall_constraints(world: &Polygon, boundary: &Polygon, distance: f64, out: &mut Vec<Constraint>) {
+    for (a, b) in boundary.edges() {
+        let Some((dir, outward, length)) = edge_frame(&a, &b) else { continue };
+        for v in world.vertices() {
+            let along = (v - a).dot(&dir);
+            let inside = -(v - a).dot(&outward);
+            if (0.0..=length).contains(&along) && inside.abs() <= distance {
+                out.push(Constraint {
+                    normal: -outward,
+                    offset: CLEARANCE - inside,
+                    contact: true,
+                    target: SnapTarget::Wall { from: pair(&a), to: pair(&b) },
+                });
+            }
+        }
+    }
+}
+
+/// Contacts between the moving shape and `other` — a vertex of either near an
+/// edge of the other — plus vertices of the two within `distance` of lining up
+/// horizontally or vertically.
+fn shape_constraints(world: &Polygon, other: &Polygon, id: ObjectId, distance: f64, out: &mut Vec<Constraint>) {
+    for (a, b) in other.edges() {
+        let Some((dir, outward, length)) = edge_frame(&a, &b) else { continue };
+        for v in world.vertices() {
+            let along = (v - a).dot(&dir);
+            let gap = (v - a).dot(&outward);
+            if (0.0..=length).contains(&along) && gap.abs() <= distance {
+                out.push(Constraint {
+                    normal: outward,
+                    offset: CLEARANCE - gap,
+                    contact: true,
+                    target: SnapTarget::Edge { id, from: pair(&a), to: pair(&b) },
+                });
+            }
+        }
+    }
+    for (p, q) in world.edges() {
+        let Some((dir, outward, length)) = edge_frame(&p, &q) else { continue };
+        for w in other.vertices() {
+            let along = (w - p).dot(&dir);
+            let gap = (w - p).dot(&outward);
+            if (0.0..=length).contains(&along) && gap.abs() <= distance {
+                out.push(Constraint {
+                    normal: outward,
+                    offset: gap - CLEARANCE,
+                    contact: true,
+                    target: SnapTarget::Vertex { id, point: pair(w), at: pair(&(p + dir * along)) },
+                });
+            }
+        }
+    }
+    for v in world.vertices() {
+        for w in other.vertices() {
+            for axis in [Vector2::x(), Vector2::y()] {
+                let offset = (w - v).dot(&axis);
+                if offset.abs() <= distance {
+                    out.push(Constraint {
+                        normal: axis,
+                        offset,
+                        contact: false,
+                        target: SnapTarget::Vertex { id, point: pair(w), at: pair(v) },
+                    });
+                }
+            }
+        }
+    }
+}
+
+/// Up to two non-parallel constraints: contacts before alignments, nearest
+/// first, then grid lines for whatever direction is still free.
+fn choose(mut candidates: Vec<Constraint>, origin: &Vector2<f64>, grid: f64) -> Vec<Constraint> {
+    candidates.sort_by(|a, b| b.contact.cmp(&a.contact).then(a.offset.abs().total_cmp(&b.offset.abs())));
+    let mut chosen: Vec<Constraint> = Vec::new();
+    for candidate in candidates {
+        if chosen.len() == 2 {
+            break;
+        }
+        if chosen.iter().all(|c| cross(&c.normal, &candidate.normal).abs() > MIN_CROSS) {
+            chosen.push(candidate);
+        }
+    }
+    if grid > 0.0 {
+        for axis in [Vector2::x(), Vector2::y()] {
+            if chosen.len() < 2 && chosen.iter().all(|c| cross(&c.normal, &axis).abs() > MIN_CROSS) {
+                let value = origin.dot(&axis);
+                chosen.push(Constraint {
+                    normal: axis,
+                    offset: (value / grid).round() * grid - value,
+                    contact: false,
+                    // The point is filled in once the move is known
+                    target: SnapTarget::Grid { point: [0.0, 0.0] },
+                });
+            }
+        }
+    }
+    chosen
+}
+
+/// The move that satisfies every chosen constraint: along the normal for one,
+/// the intersection of the two lines for two.
+fn solve(chosen: &[Constraint]) -> Vector2<f64> {
+    match chosen {
+        [a] => a.normal * a.offset,
+        [a, b] => {
+            let det = cross(&a.normal, &b.normal);
+            Vector2::new(
+                (a.offset * b.normal.y - a.normal.y * b.offset) / det,
+                (a.normal.x * b.offset - a.offset * b.normal.x) / det,
+            )
+        }
+        _ => Vector2::zeros(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn room() -> Scene {
+        let mut scene = Scene::new();
+        scene.set_boundary(Polygon::custom(vec![[0.0, 0.0], [10.0, 0.0], [10.0, 10.0], [0.0, 10.0]]).unwrap());
+        scene
+    }
+
+    fn square(scene: &mut Scene, x: f64, y: f64) -> ObjectId {
+        scene.add_shape(Polygon::rectangle(2.0, 2.0), Transform2D::new(x, y, 0.0), Default::default()).unwrap()
+    }
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-4
+    }
+
+    #[test]
+    fn nothing_nearby_leaves_the_move_alone() {
+        let mut scene = room();
+        let id = square(&mut scene, 5.0, 5.0);
+        let result = scene.snap(id, &Transform2D::new(4.3, 5.6, 0.0), &SnapOptions::default()).unwrap();
+        assert_eq!((result.transform.translation.x, result.transform.translation.y), (4.3, 5.6));
+        // 0° is itself a rotation step
+        assert_eq!(result.targets, vec![SnapTarget::Rotation { degrees: 0.0 }]);
+    }
+
+    #[test]
+    fn rotation_snaps_within_tolerance() {
+        let mut scene = room();
+        let id = square(&mut scene, 5.0, 5.0);
+        let options = SnapOptions::default();
+        let near = scene.snap(id, &Transform2D::from_degrees(5.0, 5.0, 47.0), &options).unwrap();
+        assert!(close(near.transform.rotation_rad.to_degrees(), 45.0));
+        let far = scene.snap(id, &Transform2D::from_degrees(5.0, 5.0, 52.0), &options).unwrap();
+        assert!(close(far.transform.rotation_rad.to_degrees(), 52.0));
+        let right_angles = SnapOptions { rotation_step: 90.0, rotation_tolerance: 10.0, ..options };
+        let result = scene.snap(id, &Transform2D::from_degrees(5.0, 5.0, 83.0), &right_angles).unwrap();
+        assert!(result.targets.contains(&SnapTarget::Rotation { degrees: 90.0 }));
+    }
+
+    #[test]
+    fn origin_snaps_to_the_grid() {
+        let mut scene = room();
+        let id = square(&mut scene, 5.0, 5.0);
+        let options = SnapOptions { grid: 0.5, ..Default::default() };
+        let result = scene.snap(id, &Transform2D::new(4.3, 5.6, 0.0), &options).unwrap();
+        assert_eq!((result.transform.translation.x, result.transform.translation.y), (4.5, 5.5));
+        assert!(result.targets.contains(&SnapTarget::Grid { point: [4.5, 5.5] }));
+    }
+
+    #[test]
+    fn flush_against_a_wall_is_still_valid() {
+        let mut scene = room();
+        let id = square(&mut scene, 5.0, 5.0);
+        // Left edge 0.05 from the wall, and already 0.02 through the bottom one
+        let result = scene.snap(id, &Transform2D::new(1.05, 0.98, 0.0), &SnapOptions::default()).unwrap();
+        let t = &result.transform;
+        assert!(close(t.translation.x, 1.0) && close(t.translation.y, 1.0), "{t:?}");
+        assert!(result.targets.contains(&SnapTarget::Wall { from: [0.0, 0.0], to: [10.0, 0.0] }));
+        assert!(result.targets.contains(&SnapTarget::Wall { from: [0.0, 10.0], to: [0.0, 0.0] }));
+        assert!(scene.validate_move(id, t).valid);
+    }
+
+    #[test]
+    fn flush_against_a_rotated_wall() {
+        let mut scene = Scene::new();
+        // A diamond-shaped room
+        scene.set_boundary(Polygon::custom(vec![[10.0, 0.0], [20.0, 10.0], [10.0, 20.0], [0.0, 10.0]]).unwrap());
+        let id = scene.add_shape(Polygon::rectangle(2.0, 2.0), Transform2D::from_degrees(10.0, 10.0, 45.0), Default::default()).unwrap();
+        let proposed = Transform2D::from_degrees(14.0, 5.0, 44.0);
+        let result = scene.snap(id, &proposed, &SnapOptions { distance: 0.5, ..Default::default() }).unwrap();
+        // The square's lower-right side lies on x - y = 10, ~1 inside the wall
+        let t = &result.transform;
+        let side = (t.translation.x - t.translation.y - 10.0) / 2f64.sqrt();
+        assert!(close(side, -1.0), "{t:?}");
+        assert!(scene.validate_move(id, t).valid);
+    }
+
+    #[test]
+    fn flush_against_a_neighbour_and_lined_up_with_it() {
+        let mut scene = room();
+        let table = square(&mut scene, 5.0, 5.0);
+        let chair = square(&mut scene, 2.0, 8.0);
+        let result = scene.snap(chair, &Transform2D::new(7.05, 5.04, 0.0), &SnapOptions::default()).unwrap();
+        let t = &result.transform;
+        assert!(close(t.translation.x, 7.0) && close(t.translation.y, 5.0), "{t:?}");
+        assert!(result.targets.contains(&SnapTarget::Edge { id: table, from: [6.0, 4.0], to: [6.0, 6.0] }));
+        assert!(result.targets.iter().any(|t| matches!(t, SnapTarget::Vertex { id, .. } if *id == table)));
+        assert!(scene.validate_move(chair, t).valid);
+    }
+
+    #[test]
+    fn contacts_win_over_the_grid() {
+        let mut scene = room();
+        let id = square(&mut scene, 5.0, 5.0);
+        let options = SnapOptions { grid: 0.25, ..Default::default() };
+        let result = scene.snap(id, &Transform2D::new(1.05, 4.4, 0.0), &options).unwrap();
+        let t = &result.transform;
+        // x from the wall, y from the grid
+        assert!(close(t.translation.x, 1.0), "{t:?}");
+        assert_eq!(t.translation.y, 4.5);
+    }
+
+    #[test]
+    fn walls_and_shapes_can_be_turned_off() {
+        let mut scene = room();
+        let id = square(&mut scene, 5.0, 5.0);
+        let options = SnapOptions { walls: false, shapes: false, rotation_step: 0.0, ..Default::default() };
+        let result = scene.snap(id, &Transform2D::new(1.05, 1.05, 0.0), &options).unwrap();
+        assert_eq!(result.transform.translation.x, 1.05);
+        assert!(result.targets.is_empty());
+    }
+
+    #[test]
+    fn unknown_shape_is_an_error() {
+        let scene = room();
+        assert!(matches!(
+            scene.snap(7, &Transform2D::identity(), &SnapOptions::default()),
+            Err(PlacementError::ObjectNotFound(7))
+        ));
+    }
+}
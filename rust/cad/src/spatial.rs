@@ -0,0 +1,279 @@
+use std::collections::HashMap;
+
+use crate::polygon::Aabb;
+use crate::scene::ObjectId;
+
+/// Cells an AABB may span along either axis before it is kept in the `large`
+/// list instead — one rug across the whole room shouldn't occupy hundreds of cells.
+const MAX_SPAN: i64 = 8;
+
+/// The cell size is re-picked once the mean shape extent is this many times
+/// larger or smaller than it, e.g. after a metres-to-millimetres unit change.
+const REBUILD_RATIO: f64 = 4.0;
+
+type Cell = (i64, i64);
+
+/// Broad phase for `Scene`: a uniform grid (spatial hash) over world-space AABBs.
+///
+/// Each shape is listed in every cell its AABB touches, so the shapes near a
+/// query box are found by looking at the few cells under it rather than at
+/// every shape in the scene. Shapes much larger than the cell size go to a
+/// short `large` list that every query checks.
+#[derive(Debug, Default)]
+pub struct SpatialGrid {
+    /// Side of a cell in world units; 0 until the first insert.
+    cell_size: f64,
+    cells: HashMap<Cell, Vec<ObjectId>>,
+    large: Vec<ObjectId>,
+    aabbs: HashMap<ObjectId, Aabb>,
+    /// Sum of every AABB's larger side, for the mean extent.
+    extent_sum: f64,
+}
+
+impl SpatialGrid {
+    /// Adds `id`, or moves it if it is already indexed.
+    pub fn insert(&mut self, id: ObjectId, aabb: Aabb) {
+        self.remove(id);
+        self.extent_sum += extent(&aabb);
+        self.aabbs.insert(id, aabb.clone());
+        if self.needs_rebuild() {
+            self.rebuild();
+        } else {
+            self.place(id, &aabb);
+        }
+    }
+
+    pub fn remove(&mut self, id: ObjectId) -> bool {
+        let Some(aabb) = self.aabbs.remove(&id) else {
+            return false;
+        };
+        self.extent_sum -= extent(&aabb);
+        match self.cell_range(&aabb) {
+            Some((min, max)) => {
+                for cell in cells(min, max) {
+                    if let Some(ids) = self.cells.get_mut(&cell) {
+                        ids.retain(|other| *other != id);
+                        if ids.is_empty() {
+                            self.cells.remove(&cell);
+                        }
+                    }
+                }
+            }
+            None => self.large.retain(|other| *other != id),
+        }
+        if self.aabbs.is_empty() {
+            *self = Self::default();
+        }
+        true
+    }
+
+    /// Ids whose AABBs overlap `aabb` (touching edges don't count, as in
+    /// `Aabb::overlaps`), in ascending order.
+    pub fn query(&self, aabb: &Aabb) -> Vec<ObjectId> {
+        self.collect(aabb, |other| other.overlaps(aabb))
+    }
+
+    /// Ids whose AABBs contain the point (edges included), in ascending order.
+    pub fn query_point(&self, x: f64, y: f64) -> Vec<ObjectId> {
+        let point = Aabb { min: [x, y].into(), max: [x, y].into() };
+        self.collect(&point, |other| {
+            other.min.x <= x && x <= other.max.x && other.min.y <= y && y <= other.max.y
+        })
+    }
+
+    fn collect(&self, area: &Aabb, keep: impl Fn(&Aabb) -> bool) -> Vec<ObjectId> {
+        let mut ids: Vec<ObjectId> = match self.cell_range(area) {
+            Some((min, max)) => cells(min, max)
+                .filter_map(|cell| self.cells.get(&cell))
+                .flatten()
+                .chain(&self.large)
+                .copied()
+                .collect(),
+            // A query box bigger than the grid is meant for: looking at every
+            // shape is cheaper than visiting that many cells.
+            None => self.aabbs.keys().copied().collect(),
+        };
+        ids.sort_unstable();
+        ids.dedup();
+        ids.retain(|id| keep(&self.aabbs[id]));
+        ids
+    }
+
+    /// The cells under `aabb`, or None when it spans more than `MAX_SPAN` of
+    /// them (or the grid is empty).
+    ///
+    /// The span is measured in f64 before anything is cast: far-out coordinates
+    /// saturate to `i64::MIN`/`MAX` as cell indices, and their difference would
+    /// overflow. Such a box (or a NaN one) simply counts as large.
+    fn cell_range(&self, aabb: &Aabb) -> Option<(Cell, Cell)> {
+        if self.cell_size <= 0.0 {
+            return None;
+        }
+        let (min_x, min_y) = self.cell_of(aabb.min.x, aabb.min.y);
+        let (max_x, max_y) = self.cell_of(aabb.max.x, aabb.max.y);
+        let limit = MAX_SPAN as f64;
+        if !(max_x - min_x < limit && max_y - min_y < limit) {
+            return None;
+        }
+        Some(((min_x as i64, min_y as i64), (max_x as i64, max_y as i64)))
+    }
+
+    /// The cell containing a point, as whole-number floats.
+    fn cell_of(&self, x: f64, y: f64) -> (f64, f64) {
+        ((x / self.cell_size).floor(), (y / self.cell_size).floor())
+    }
+
+    fn place(&mut self, id: ObjectId, aabb: &Aabb) {
+        match self.cell_range(aabb) {
+            Some((min, max)) => {
+                for cell in cells(min, max) {
+                    self.cells.entry(cell).or_default().push(id);
+                }
+            }
+            None => self.large.push(id),
+        }
+    }
+
+    fn mean_extent(&self) -> f64 {
+        self.extent_sum / self.aabbs.len() as f64
+    }
+
+    fn needs_rebuild(&self) -> bool {
+        let mean = self.mean_extent();
+        mean > 0.0
+            && (self.cell_size <= 0.0
+                || mean > self.cell_size * REBUILD_RATIO
+                || mean < self.cell_size / REBUILD_RATIO)
+    }
+
+    /// Re-picks the cell size from the current shapes and re-indexes them all.
+    fn rebuild(&mut self) {
+        self.cell_size = self.mean_extent();
+        self.cells.clear();
+        self.large.clear();
+        let entries: Vec<(ObjectId, Aabb)> =
+            self.aabbs.iter().map(|(id, aabb)| (*id, aabb.clone())).collect();
+        for (id, aabb) in entries {
+            self.place(id, &aabb);
+        }
+    }
+}
+
+fn extent(aabb: &Aabb) -> f64 {
+    (aabb.max.x - aabb.min.x).max(aabb.max.y - aabb.min.y)
+}
+
+fn cells(min: Cell, max: Cell) -> impl Iterator<Item = Cell> {
+    (min.0..=max.0).flat_map(move |x| (min.1..=max.1).map(move |y| (x, y)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn square(x: f64, y: f64, size: f64) -> Aabb {
+        Aabb { min: [x, y].into(), max: [x + size, y + size].into() }
+    }
+
+    #[test]
+    fn query_finds_only_overlapping_boxes() {
+        let mut grid = SpatialGrid::default();
+        grid.insert(1, square(0.0, 0.0, 1.0));
+        grid.insert(2, square(5.0, 5.0, 1.0));
+        grid.insert(3, square(0.5, 0.5, 1.0));
+        assert_eq!(grid.query(&square(0.2, 0.2, 0.5)), vec![1, 3]);
+        assert_eq!(grid.query(&square(5.5, 5.5, 0.1)), vec![2]);
+        assert!(grid.query(&square(20.0, 20.0, 1.0)).is_empty());
+    }
+
+    #[test]
+    fn touching_boxes_do_not_overlap() {
+        let mut grid = SpatialGrid::default();
+        grid.insert(1, square(0.0, 0.0, 1.0));
+        assert!(grid.query(&square(1.0, 0.0, 1.0)).is_empty());
+    }
+
+    #[test]
+    fn reinserting_moves_the_box() {
+        let mut grid = SpatialGrid::default();
+        grid.insert(1, square(0.0, 0.0, 1.0));
+        grid.insert(2, square(3.0, 3.0, 1.0));
+        grid.insert(1, square(10.0, 10.0, 1.0));
+        assert_eq!(grid.aabbs.len(), 2);
+        assert!(grid.query(&square(0.0, 0.0, 1.0)).is_empty());
+        assert_eq!(grid.query(&square(10.0, 10.0, 1.0)), vec![1]);
+    }
+
+    #[test]
+    fn remove_forgets_the_box() {
+        let mut grid = SpatialGrid::default();
+        grid.insert(1, square(0.0, 0.0, 1.0));
+        grid.insert(2, square(0.5, 0.0, 1.0));
+        assert!(grid.remove(1));
+        assert!(!grid.remove(1));
+        assert_eq!(grid.query(&square(0.0, 0.0, 1.0)), vec![2]);
+    }
+
+    #[test]
+    fn negative_coordinates_use_their_own_cells() {
+        let mut grid = SpatialGrid::default();
+        grid.insert(1, square(-3.0, -3.0, 1.0));
+        grid.insert(2, square(0.0, 0.0, 1.0));
+        assert_eq!(grid.query(&square(-2.5, -2.5, 0.1)), vec![1]);
+    }
+
+    #[test]
+    fn oversized_boxes_are_still_found() {
+        let mut grid = SpatialGrid::default();
+        for i in 0..10 {
+            grid.insert(i, square(i as f64 * 2.0, 0.0, 1.0));
+        }
+        grid.insert(100, square(-50.0, -50.0, 100.0));
+        assert_eq!(grid.query(&square(4.2, 0.2, 0.1)), vec![2, 100]);
+        assert_eq!(grid.query_point(-40.0, -40.0), vec![100]);
+        assert!(grid.remove(100));
+        assert!(grid.query_point(-40.0, -40.0).is_empty());
+    }
+
+    #[test]
+    fn boxes_past_the_cell_index_range_are_large() {
+        let mut grid = SpatialGrid::default();
+        grid.insert(1, square(0.0, 0.0, 1.0));
+        // Both corners saturate the i64 cell index, at opposite ends.
+        let huge = Aabb { min: [-1e300, -1e300].into(), max: [1e300, 1e300].into() };
+        assert_eq!(grid.cell_range(&huge), None);
+        assert_eq!(grid.query(&huge), vec![1]);
+        grid.insert(2, huge);
+        assert_eq!(grid.query_point(0.5, 0.5), vec![1, 2]);
+    }
+
+    #[test]
+    fn cell_size_follows_the_scale_of_the_shapes() {
+        let mut grid = SpatialGrid::default();
+        grid.insert(1, square(0.0, 0.0, 2.0));
+        assert_eq!(grid.cell_size, 2.0);
+        // Millimetres instead of metres: the grid adapts rather than spreading
+        // every shape over thousands of cells.
+        for i in 2..10 {
+            grid.insert(i, square(i as f64 * 5000.0, 0.0, 2000.0));
+        }
+        assert!(grid.cell_size > 500.0);
+        assert_eq!(grid.query(&square(10_500.0, 500.0, 10.0)), vec![2]);
+        assert_eq!(grid.query(&square(0.5, 0.5, 0.5)), vec![1]);
+    }
+
+    #[test]
+    fn only_nearby_shapes_are_candidates() {
+        let mut grid = SpatialGrid::default();
+        let mut id = 0;
+        for x in 0..20 {
+            for y in 0..20 {
+                grid.insert(id, square(x as f64 * 3.0, y as f64 * 3.0, 2.0));
+                id += 1;
+            }
+        }
+        let near = grid.query(&square(29.5, 29.5, 2.0));
+        assert_eq!(near.len(), 1);
+        assert_eq!(grid.query_point(31.0, 31.0), near);
+    }
+}
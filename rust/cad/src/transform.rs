@@ -33,6 +33,15 @@ impl Transform2D {
         let ry = point.x * sin + point.y * cos;
         Point2::new(rx + self.translation.x, ry + self.translation.y)
     }
+
+    /// The point that `apply` maps onto `point`: world → local coordinates.
+    pub fn apply_inverse(&self, point: &Point2<f64>) -> Point2<f64> {
+        let cos = self.rotation_rad.cos();
+        let sin = self.rotation_rad.sin();
+        let dx = point.x - self.translation.x;
+        let dy = point.y - self.translation.y;
+        Point2::new(dx * cos + dy * sin, -dx * sin + dy * cos)
+    }
 }
 
 impl Default for Transform2D {
@@ -1,4 +1,4 @@
-use cad::{Polygon, Scene, Transform2D, PlacementError};
+use cad::{Polygon, Scene, SnapOptions, SnapTarget, Transform2D, PlacementError};
 
 // ── Furniture placement scenarios ────────────────────────────────────────────
 
@@ -129,3 +129,89 @@ fn custom_triangle_collision_with_rectangle() {
     let err = scene.add_shape(rect, Transform2D::new(0.5, 0.5, 0.0), Default::default());
     assert!(matches!(err, Err(PlacementError::CollidesWithObject { .. })));
 }
+
+// ── Large scenes ──────────────────────────────────────────────────────────────
+
+#[test]
+fn drag_across_a_dense_floor_plan() {
+    let mut scene = Scene::new();
+    scene.set_boundary(
+        Polygon::custom(vec![[0.0,0.0],[100.0,0.0],[100.0,100.0],[0.0,100.0]]).unwrap(),
+    );
+    let desk = Polygon::rectangle(2.0, 1.0);
+    // 20×20 desks on a 4-unit grid, leaving 2-unit aisles between them
+    for i in 0..20 {
+        for j in 0..20 {
+            let (x, y) = (4.0 + i as f64 * 4.0, 4.0 + j as f64 * 4.0);
+            scene.add_shape(desk.clone(), Transform2D::new(x, y, 0.0), Default::default()).unwrap();
+        }
+    }
+    let chair = scene
+        .add_shape(Polygon::rectangle(1.0, 1.0), Transform2D::new(2.0, 2.0, 0.0), Default::default())
+        .unwrap();
+
+    // Drag the chair along an aisle (y = 6): clear every step of the way
+    for step in 0..80 {
+        let x = 2.0 + step as f64;
+        assert!(scene.validate_move(chair, &Transform2D::new(x, 6.0, 0.0)).valid, "x = {x}");
+    }
+    // Straight through a desk row (y = 4) it hits exactly the desk under it
+    let result = scene.validate_move(chair, &Transform2D::new(40.0, 4.0, 0.0));
+    assert_eq!(result.collisions.len(), 1);
+    let desk_id = result.collisions[0];
+    assert_eq!(scene.query_point(40.0, 4.0), Some(desk_id));
+
+    scene.move_shape(chair, Transform2D::new(40.0, 6.0, 0.0)).unwrap();
+    assert_eq!(scene.query_point(40.0, 6.0), Some(chair));
+    assert_eq!(scene.query_point(2.0, 2.0), None);
+}
+
+// ── Saving and loading ────────────────────────────────────────────────────────
+
+#[test]
+fn saved_plan_loads_back_and_keeps_editing() {
+    let mut scene = Scene::new();
+    scene.set_boundary(
+        Polygon::custom(vec![[0.0,0.0],[10.0,0.0],[10.0,10.0],[0.0,10.0]]).unwrap(),
+    );
+    let sofa = scene
+        .add_shape(Polygon::l_shape(3.0, 2.0, 1.0, 1.0), Transform2D::from_degrees(5.0, 2.0, 90.0), Default::default())
+        .unwrap();
+    let table = scene
+        .add_shape(Polygon::rectangle(2.0, 1.0), Transform2D::new(7.0, 7.0, 0.0), Default::default())
+        .unwrap();
+    scene.remove_shape(sofa);
+
+    let (mut loaded, report) = Scene::from_json(&scene.to_json()).unwrap();
+    assert!(report.invalid.is_empty());
+    assert_eq!(loaded.query_point(7.0, 7.0), Some(table));
+    // The table still blocks the spot it was saved in
+    let blocker = Polygon::rectangle(1.0, 1.0);
+    assert!(matches!(
+        loaded.add_shape(blocker.clone(), Transform2D::new(7.5, 7.0, 0.0), Default::default()),
+        Err(PlacementError::CollidesWithObject { other_id }) if other_id == table
+    ));
+    let id = loaded.add_shape(blocker, Transform2D::new(3.0, 3.0, 0.0), Default::default()).unwrap();
+    assert!(id > table);
+}
+
+// ── Snapping ──────────────────────────────────────────────────────────────────
+
+#[test]
+fn sofa_dropped_near_a_corner_snaps_into_it() {
+    let mut scene = Scene::new();
+    scene.set_boundary(Polygon::l_shape(12.0, 10.0, 4.0, 4.0));
+    let sofa = scene
+        .add_shape(Polygon::rectangle(3.0, 1.0), Transform2D::new(5.0, 3.0, 0.0), Default::default())
+        .unwrap();
+    // Dropped slightly askew, a little short of the bottom-left corner
+    let proposed = Transform2D::from_degrees(1.58, 0.56, 3.0);
+    assert!(!scene.validate_move(sofa, &proposed).valid);
+
+    let snapped = scene.snap(sofa, &proposed, &SnapOptions::default()).unwrap();
+    let t = &snapped.transform;
+    assert_eq!(t.rotation_rad, 0.0);
+    assert!((t.translation.x - 1.5).abs() < 1e-4 && (t.translation.y - 0.5).abs() < 1e-4, "{t:?}");
+    assert_eq!(snapped.targets.iter().filter(|t| matches!(t, SnapTarget::Wall { .. })).count(), 2);
+    scene.move_shape(sofa, t.clone()).unwrap();
+}
@@ -1,19 +1,62 @@
-use anyhow::Result;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
 
 use crate::{
+    consts::eddy_dir,
+    fs::RealFs,
+    history::{self, Action, Entry, Target},
     shared::{
-        chmod_755, download_file, ensure_tool_dir, ensure_tool_dir_check, extract, remove_path,
-        rename_dir, resolve_latest_version, symlink_bin,
+        chmod_755, download_file_with, extract, now_secs, remove_path, rename_dir,
+        resolve_latest_version, sha256_file, verify_checksum,
     },
+    linker::{Platform, link_bin, link_target, remove_links_into},
+    locking::ToolLock,
+    progress::{Progress, TerminalProgress},
+    receipt::Receipt,
     types::{InstallStep, ToolInfo, Version},
 };
 
+// Where `install` gets the artifact from. The default is the release URL in the
+// ToolInfo; the other two serve air-gapped machines and internal mirrors. Every
+// source goes through the same verify → InstallStep pipeline afterwards, so the
+// resulting layout (custom_bin_path, links) is identical.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ArtifactSource {
+    Release,
+    Url(String),
+    File(PathBuf),
+}
+
+impl ArtifactSource {
+    // "latest" is looked up from the release URL, which means nothing for an
+    // archive that comes from somewhere else. Callers check this before building
+    // the ToolInfo, since resolving "latest" may already need the network.
+    pub fn check_version(&self, tool: &str, version: &Version) -> Result<()> {
+        if *version == Version::Latest && *self != ArtifactSource::Release {
+            anyhow::bail!("installing {tool} from a custom source needs an explicit version");
+        }
+        Ok(())
+    }
+}
+
+// Clone is cheap enough to hand a copy to a blocking task: ToolInfo is a few
+// strings and the reporter is shared through its Arc.
+#[derive(Clone)]
 pub struct ToolBlueprint {
     // `pub` on the field makes it readable from outside the module.
     // Improvement: make `info` private (`info: ToolInfo`) and expose only what
     // callers need via methods. Right now tests poke into `info` directly, which
     // couples them to the struct's internals.
     pub info: ToolInfo,
+    pub source: ArtifactSource,
+    // The eddy home this blueprint installs into and links from. eddy_dir() for
+    // the CLI; an `Eddy` client passes its own, so no env var is involved.
+    home: PathBuf,
+    // `Arc<dyn Progress>`: a shared, reference-counted trait object — the
+    // reporter is chosen at runtime and shared by every blueprint of a client.
+    progress: Arc<dyn Progress>,
 }
 
 impl ToolBlueprint {
@@ -23,30 +66,142 @@ impl ToolBlueprint {
     pub fn new(info: ToolInfo) -> Self {
         // Field init shorthand: `{ info }` is short for `{ info: info }`.
         // Identical to ES2015 shorthand property notation in TypeScript.
-        Self { info }
+        Self {
+            info,
+            source: ArtifactSource::Release,
+            home: eddy_dir(),
+            progress: Arc::new(TerminalProgress::new()),
+        }
+    }
+
+    // Consuming builder-style setter: `ToolBlueprint::new(info).with_source(src)`.
+    // Taking `mut self` by value lets it chain straight off `new` without a `let mut`.
+    pub fn with_source(mut self, source: ArtifactSource) -> Self {
+        self.source = source;
+        self
+    }
+
+    pub fn with_home(mut self, home: PathBuf) -> Self {
+        self.home = home;
+        self
+    }
+
+    pub fn with_progress(mut self, progress: Arc<dyn Progress>) -> Self {
+        self.progress = progress;
+        self
+    }
+
+    pub fn home(&self) -> &std::path::Path {
+        &self.home
     }
 
     // `&self`: immutable borrow — download reads the blueprint but doesn't change it.
     // Return type is PathBuf (owned) because we're building a new path value.
     pub async fn download(&self) -> Result<std::path::PathBuf> {
-        let sub = format!("{}/{}/{}", self.info.lang, self.info.name, self.info.version);
-        let dir = ensure_tool_dir(&sub);
-        let file_path = dir.join(&self.info.pkg_name);
-        download_file(&file_path, &self.info.url).await?;
+        let file_path = self.archive_path();
+        self.create_version_path()?;
+        download_file_with(&file_path, &self.info.url, self.progress.as_ref()).await?;
         Ok(file_path)
     }
 
+    fn create_version_path(&self) -> Result<()> {
+        let dir = self.version_path();
+        std::fs::create_dir_all(&dir)
+            .with_context(|| format!("failed to create {}", dir.display()))
+    }
+
+    // Puts the artifact at `archive_path()` according to `self.source`. A local
+    // file is copied under the name the release would have had (`pkg_name`), so
+    // InstallStep::Rename and friends find it exactly where they expect it.
+    async fn fetch(&self) -> Result<PathBuf> {
+        match &self.source {
+            ArtifactSource::Release => self.download().await,
+            ArtifactSource::Url(url) => {
+                let file_path = self.archive_path();
+                self.create_version_path()?;
+                download_file_with(&file_path, url, self.progress.as_ref()).await?;
+                Ok(file_path)
+            }
+            ArtifactSource::File(path) => {
+                let file_path = self.archive_path();
+                self.create_version_path()?;
+                self.progress.message(&format!("Using local archive {}", path.display()));
+                tokio::fs::copy(path, &file_path)
+                    .await
+                    .with_context(|| format!("failed to copy {}", path.display()))?;
+                Ok(file_path)
+            }
+        }
+    }
+
+    // "go/go-language/1.22.3", relative to the eddy home.
+    pub fn version_dir(&self) -> String {
+        format!("{}/{}/{}", self.info.lang, self.info.name, self.info.version)
+    }
+
+    // The same directory as an absolute path under `home`.
+    pub fn version_path(&self) -> PathBuf {
+        self.home.join(self.version_dir())
+    }
+
+    // Where the PATH entries go: `<home>/bin`.
+    pub fn link_dir(&self) -> PathBuf {
+        self.home.join("bin")
+    }
+
+    // Where `download` puts the archive, without creating anything.
+    pub fn archive_path(&self) -> std::path::PathBuf {
+        self.version_path().join(&self.info.pkg_name)
+    }
+
     // `&mut self`: mutable borrow — install may update `self.info.version` when
     // resolving "latest". Only one &mut borrow can exist at a time; the borrow
     // checker prevents aliasing mutations. Callers must declare `let mut blueprint`.
+    //
+    // The span (see logging.rs) carries tool, version and source into every log
+    // line of the install; `err` logs the error the call returns, if any.
+    #[tracing::instrument(
+        skip_all,
+        err,
+        fields(tool = self.info.name, version = %self.info.version, source = ?self.source)
+    )]
     pub async fn install(&mut self) -> Result<()> {
         if self.info.version == Version::Latest {
-            let resolved = resolve_latest_version(&self.info.url).await?;
+            self.source.check_version(self.info.name, &self.info.version)?;
+            let resolved = resolve_latest_version(&self.info.url, self.progress.as_ref()).await?;
+            tracing::info!(version = %resolved, "resolved latest");
             // We can mutate the field because we have `&mut self`.
             self.info.version = Version::SemVer(resolved);
         }
 
-        let archive_path = self.download().await?;
+        // Held until the end of this function (dropped when `_lock` goes out of
+        // scope), covering download, extraction and renames.
+        let (_lock, waited) = ToolLock::acquire_async(
+            self.home.clone(),
+            self.info.lang.to_string(),
+            self.info.name.to_string(),
+            self.info.version.to_string(),
+            self.progress.clone(),
+        )
+        .await?;
+        // Another process installed this exact version while we waited — redoing
+        // it would re-download and extract over files that may already be in use.
+        if waited && self.is_installed() {
+            let archive_path = self.archive_path();
+            if archive_path.exists() {
+                self.verify(&archive_path)?;
+            }
+            self.progress.message(&format!(
+                "{}@{} was installed by another eddy process",
+                self.info.name, self.info.version
+            ));
+            return Ok(());
+        }
+
+        // The URL is on the download's own span; it may not be `info.url`.
+        tracing::info!(expected_sha256 = ?self.info.sha256, "fetching");
+        let archive_path = self.fetch().await?;
+        self.verify(&archive_path)?;
 
         // .parent() returns Option<&Path>. It's None only for paths like "/" or "c:\\",
         // never for a file we just downloaded to a subdirectory. .unwrap() is safe here.
@@ -65,38 +220,182 @@ impl ToolBlueprint {
             // becomes a compile error until we handle it. The compiler gives you a
             // precise list of missing arms. This is the main advantage over TS's
             // `if (step === 'extract')` chains, which silently ignore new values.
+            // `.entered()` makes this the current span until `_step` is dropped at the
+            // end of the iteration. Fine here because the steps never `.await`: a
+            // guard held across an await would tag other tasks' logs with it.
+            let _step = tracing::info_span!("step", ?step).entered();
             match step {
                 InstallStep::Extract => {
+                    self.progress.message(&format!(
+                        "Extracting {} to {}...",
+                        archive_path.display(),
+                        dir.display()
+                    ));
                     extract(&archive_path, &dir)?;
                 }
+                // A downloaded bare binary (bazel) becomes `bazel` — or `bazel.exe`
+                // on Windows, where the suffix is what makes the file executable.
                 InstallStep::Rename => {
-                    rename_dir(&dir, &self.info.pkg_name, self.info.name)?;
+                    let exe = Platform::current().exe_name(self.info.name);
+                    rename_dir(&dir, &self.info.pkg_name, &exe, self.progress.as_ref())?;
                 }
                 InstallStep::Chmod => {
-                    chmod_755(&dir, self.info.name)?;
+                    let exe = Platform::current().exe_name(self.info.name);
+                    chmod_755(&dir, &exe)?;
                 }
             }
         }
+
+        // A release archive has the layout the ToolInfo describes by definition; a
+        // hand-picked one might not (wrong platform, repackaged by a mirror), and
+        // would otherwise only fail later, at `eddy use`.
+        if self.source != ArtifactSource::Release && !self.is_installed() {
+            anyhow::bail!(
+                "{} does not have the layout of a {} release: expected {} in {}",
+                archive_path.display(),
+                self.info.name,
+                self.link_names().join(", "),
+                self.bin_dir().display()
+            );
+        }
+        self.receipt().save(&dir)?;
+        self.record(Action::Install, None);
         Ok(())
     }
 
-    pub fn use_tool(&self) -> Result<()> {
-        let sub = format!("{}/{}/{}", self.info.lang, self.info.name, self.info.version);
-        let dir = ensure_tool_dir_check(&sub);
+    // What `tool`'s links point into before they're changed, for the history.
+    fn active_target(&self) -> Option<Target> {
+        let tool_dir = self.version_path().parent()?.to_path_buf();
+        let first = *self.link_names().first()?;
+        history::active_target(&self.link_dir(), first, &tool_dir)
+    }
+
+    // Appends to the history (history.rs). The change itself already happened,
+    // so a history that can't be written is reported but doesn't fail it.
+    fn record(&self, action: Action, previous: Option<Target>) {
+        let entry = Entry::new(
+            action,
+            self.info.name,
+            self.info.version.as_str(),
+            self.bin_dir(),
+            &self.link_names(),
+        )
+        .with_previous(previous);
+        if let Err(e) = history::append(&self.home, &entry) {
+            self.progress.message(&format!("warning: {e:#}"));
+        }
+    }
+
+    // What `install` records next to the files it unpacked (see receipt.rs).
+    fn receipt(&self) -> Receipt {
+        let (source, url) = match &self.source {
+            ArtifactSource::Release => ("release", self.info.url.clone()),
+            ArtifactSource::Url(url) => ("url", url.clone()),
+            ArtifactSource::File(path) => ("file", format!("file://{}", path.display())),
+        };
+        Receipt {
+            name: self.info.name.to_string(),
+            version: self.info.version.to_string(),
+            url,
+            sha256: self.info.sha256.clone(),
+            license: self.info.license.map(str::to_string),
+            source: source.to_string(),
+            installed_at: now_secs(),
+        }
+    }
+
+    // Checks the downloaded artifact against `info.sha256` when one is expected
+    // (a locked install), or records the digest so callers can write it to the lock.
+    fn verify(&mut self, archive_path: &std::path::Path) -> Result<()> {
+        let what = format!("{}@{}", self.info.name, self.info.version);
+        // `.as_deref()` turns Option<String> into Option<&str> without cloning.
+        let digest = verify_checksum(archive_path, self.info.sha256.as_deref(), &what)?;
+        self.info.sha256 = Some(digest);
+        Ok(())
+    }
+
+    // The checksum of the release artifact behind an existing install, so it can
+    // be locked without reinstalling: the archive still in the version directory
+    // if it came from the release URL, otherwise a fresh download of the release.
+    pub async fn installed_sha256(&self) -> Result<String> {
+        let archive = self.archive_path();
+        // No receipt: installed before eddy wrote them, when releases were the only source.
+        let from_release = Receipt::load(&self.version_path())?.is_none_or(|r| r.source == "release");
+        if from_release && archive.is_file() {
+            return sha256_file(&archive);
+        }
+        self.download_sha256().await
+    }
+
+    // The checksum of the release artifact, for the lock, without installing it.
+    pub async fn release_sha256(&self) -> Result<String> {
+        if self.is_installed() {
+            return self.installed_sha256().await;
+        }
+        self.download_sha256().await
+    }
+
+    // Downloads the release artifact into a scratch directory of its own and
+    // hashes it. Not the version directory: that may be in the middle of a
+    // concurrent `eddy install`, so it is neither created nor cleaned up here.
+    async fn download_sha256(&self) -> Result<String> {
+        let scratch = self.home.join(format!(".lock-{}", std::process::id()));
+        std::fs::create_dir_all(&scratch)
+            .with_context(|| format!("failed to create {}", scratch.display()))?;
+        let archive = scratch.join(&self.info.pkg_name);
+        let digest = match download_file_with(&archive, &self.info.url, self.progress.as_ref()).await {
+            Ok(()) => sha256_file(&archive),
+            Err(e) => Err(e),
+        };
+        remove_path(&scratch)?;
+        digest
+    }
+
+    // The directory the tool's binaries live in: the version directory, or the
+    // `custom_bin_path` inside it when the archive nests them (go/bin, cmake/bin).
+    pub fn bin_dir(&self) -> std::path::PathBuf {
+        let dir = self.version_path();
 
         // `if let Some(ref custom)` pattern-matches the Option and borrows the inner value.
         // Without `ref`, the match would move `custom_bin_path` out of `self.info`,
         // leaving it partially moved — the compiler forbids that.
         // Alternative (idiomatic Rust 2021+): `if let Some(custom) = &self.info.custom_bin_path`
         // which borrows through the reference automatically.
-        let bin_dir = if let Some(ref custom) = self.info.custom_bin_path {
+        if let Some(ref custom) = self.info.custom_bin_path {
             dir.join(custom)
         } else {
-            // .clone() here because `dir` is consumed by join() in the Some branch,
-            // but we still need its value in the None branch. The type must be the
-            // same in both arms. Improvement: use `dir.join(custom.as_deref().unwrap_or(""))`.
-            dir.clone()
-        };
+            dir
+        }
+    }
+
+    // The executables this tool exposes on PATH: its `links`, or just its name.
+    // `.clone()` on Option<Vec<&'static str>> copies the Vec of pointers, not the strings.
+    pub fn link_names(&self) -> Vec<&'static str> {
+        self.info.links.clone().unwrap_or_else(|| vec![self.info.name])
+    }
+
+    // A version directory can exist without a usable install (a download that
+    // failed half-way, or bazel before its Rename step), so check for the binaries.
+    pub fn is_installed(&self) -> bool {
+        let bin_dir = self.bin_dir();
+        let platform = Platform::current();
+        self.link_names()
+            .iter()
+            .all(|link| bin_dir.join(platform.exe_name(link)).exists())
+    }
+
+    #[tracing::instrument(skip_all, err, fields(tool = self.info.name, version = %self.info.version))]
+    pub fn use_tool(&self) -> Result<()> {
+        // Blocks if this version is being installed or deleted right now, so we
+        // never link into a half-extracted (or half-removed) directory.
+        let (_lock, _) = ToolLock::acquire(
+            &self.home,
+            self.info.lang,
+            self.info.name,
+            self.info.version.as_str(),
+            self.progress.as_ref(),
+        )?;
+        let bin_dir = self.bin_dir();
 
         if !bin_dir.exists() {
             // anyhow::bail! expands to: return Err(anyhow::anyhow!("..."))
@@ -104,42 +403,81 @@ impl ToolBlueprint {
             anyhow::bail!("{}@{} is not installed yet", self.info.name, self.info.version);
         }
 
-        if let Some(ref links) = self.info.links {
-            // `for &link in links`: `link` is &&'static str; `&link` destructures
-            // one level of reference, giving us &'static str directly.
-            for &link in links {
-                symlink_bin(&bin_dir, link)?;
-            }
-        } else {
-            symlink_bin(&bin_dir, self.info.name)?;
+        let _links = ToolLock::acquire_links(&self.home, self.info.name, self.progress.as_ref())?;
+        let previous = self.active_target();
+        for link in self.link_names() {
+            link_bin(&RealFs, Platform::current(), &bin_dir, &self.link_dir(), link)?;
+        }
+        // Relinking the version that's already active changes nothing, and would
+        // leave a rollback with nowhere to go.
+        if previous.as_ref().is_none_or(|p| p.version != self.info.version.as_str()) {
+            self.record(Action::Use, previous);
         }
         Ok(())
     }
 
-    pub async fn delete(&self) -> Result<()> {
-        let sub_dir = format!("{}/{}/{}", self.info.lang, self.info.name, self.info.version);
-        // Bug inherited from TS: this builds `lang/name/pkg_name` as a path, but the
-        // archive file actually lives at `lang/name/version/pkg_name`. The delete
-        // of the archive always succeeds vacuously (the path doesn't exist) rather than
-        // removing the archive. In practice the whole version dir is removed by `remove_path(&dir)`
-        // anyway, so behaviour is correct — but the intent is wrong.
-        let sub_archive = format!("{}/{}/{}", self.info.lang, self.info.name, self.info.pkg_name);
+    // True when one of the tool's links in `<home>/bin` points into this
+    // version — i.e. it's what `eddy use` last selected globally.
+    pub fn is_active(&self) -> bool {
+        let dir = self.version_path();
+        let platform = Platform::current();
+        self.link_names().iter().any(|name| {
+            let link = self.link_dir().join(platform.link_name(name));
+            link_target(&RealFs, platform, &link).is_some_and(|target| target.starts_with(&dir))
+        })
+    }
 
-        let dir = ensure_tool_dir_check(&sub_dir);
-        let archive = ensure_tool_dir_check(&sub_archive);
+    // Removes the version directory (the downloaded archive lives inside it).
+    // The active version is only removed with `force`, and then its links go too,
+    // so bin/ never holds links into a directory that no longer exists.
+    #[tracing::instrument(skip(self), err, fields(tool = self.info.name, version = %self.info.version))]
+    pub async fn delete(&self, force: bool) -> Result<()> {
+        let dir = self.version_path();
 
-        // Run both removals even if the first fails (mirrors TS's Promise.allSettled).
-        // The results are plain `Result` values — we collect them and inspect after.
-        // Improvement: use `tokio::join!(remove_path(&archive), remove_path(&dir))`
-        // to run them concurrently (both are I/O-bound). Currently they run sequentially.
-        let r1 = remove_path(&archive);
-        let r2 = remove_path(&dir);
+        let (_lock, _) = ToolLock::acquire_async(
+            self.home.clone(),
+            self.info.lang.to_string(),
+            self.info.name.to_string(),
+            self.info.version.to_string(),
+            self.progress.clone(),
+        )
+        .await?;
 
-        if r1.is_err() || r2.is_err() {
-            eprintln!("Failed to delete {}@{}", self.info.name, self.info.version);
-        } else {
-            eprintln!("Successfully deleted {}@{}", self.info.name, self.info.version);
+        if !dir.exists() {
+            anyhow::bail!("{}@{} is not installed", self.info.name, self.info.version);
+        }
+        // Held through the history append, so a concurrent `eddy use` can't slip
+        // in between the activity check and the links being removed.
+        let _links = ToolLock::acquire_links_async(
+            self.home.clone(),
+            self.info.name.to_string(),
+            self.progress.clone(),
+        )
+        .await?;
+        if self.is_active() && !force {
+            anyhow::bail!(
+                "{}@{} is the active version; `eddy use` another version first, or pass --force",
+                self.info.name,
+                self.info.version
+            );
+        }
+
+        let previous = self.active_target();
+        let removed = remove_links_into(
+            &RealFs,
+            Platform::current(),
+            &self.link_dir(),
+            &self.link_names(),
+            &dir,
+        )?;
+        for link in &removed {
+            self.progress.message(&format!("Removed {}", link.display()));
         }
+        remove_path(&dir).with_context(|| {
+            format!("failed to delete {}@{} ({})", self.info.name, self.info.version, dir.display())
+        })?;
+        self.progress.message(&format!("Successfully deleted {}@{}", self.info.name, self.info.version));
+        self.record(Action::Delete, previous);
         Ok(())
     }
 }
@@ -0,0 +1,184 @@
+// `eddy bundle` / `eddy unbundle`: ship installed toolchains to machines that
+// shouldn't (or can't) download them — prepare once, unpack on every build agent.
+//
+// A bundle is a tar archive of version directories, laid out exactly as they are
+// under eddy_dir() (`<lang>/<name>/<version>/...`), plus a manifest at the root:
+//
+//   eddy-bundle.toml
+//   go/go-language/1.22.3/go/bin/go ...
+//   cpp/cmake/4.1.4/cmake-4.1.4-linux-x86_64/bin/cmake ...
+//
+// Unbundling is therefore just "extract into eddy_dir()" followed by `use_tool`
+// for each entry. Compression follows the output's extension (.tar.zst, .tar.gz,
+// .tar.xz) — `tar -a` picks the compressor, the same system tar `extract` uses.
+use std::path::Path;
+use std::process::Command;
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    blueprint::ToolBlueprint,
+    consts::eddy_dir,
+    languages,
+    progress::Progress,
+    shared::{platform, remove_path},
+    types::Version,
+};
+
+pub const MANIFEST: &str = "eddy-bundle.toml";
+const BUNDLE_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BundleManifest {
+    pub version: u32,
+    // Toolchains are platform-specific binaries; a linux bundle on a mac would
+    // unpack fine and then fail with "exec format error" at first use.
+    pub platform: String,
+    #[serde(rename = "tool", default)]
+    pub tools: Vec<BundledTool>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BundledTool {
+    pub tool: String,
+    pub version: String,
+}
+
+// "go@1.22.3" → ("go", "1.22.3"). Bundles are for exact, installed versions, so
+// neither a missing version nor "latest" is accepted.
+pub fn parse_spec(spec: &str) -> Result<(&str, &str)> {
+    match spec.split_once('@') {
+        Some((tool, version)) if !tool.is_empty() && !version.is_empty() && version != "latest" => {
+            Ok((tool, version))
+        }
+        _ => anyhow::bail!("expected <tool>@<version>, e.g. go@1.22.3 (got \"{spec}\")"),
+    }
+}
+
+async fn blueprint(tool: &str, version: &str, progress: &Arc<dyn Progress>) -> Result<ToolBlueprint> {
+    let info = languages::build_with(tool, Version::SemVer(version.to_string()), progress.clone()).await?;
+    Ok(ToolBlueprint::new(info).with_progress(progress.clone()))
+}
+
+// `eddy bundle go@1.22.3 cmake@4.1.4 -o toolchain.tar.zst`
+pub async fn bundle(specs: &[String], output: &Path, progress: Arc<dyn Progress>) -> Result<()> {
+    let mut manifest = BundleManifest {
+        version: BUNDLE_VERSION,
+        platform: platform(),
+        tools: Vec::new(),
+    };
+    // Version directories relative to eddy_dir(), plus an --exclude for each
+    // downloaded archive (already extracted, so dead weight in a bundle).
+    let mut members: Vec<String> = Vec::new();
+    let mut excludes: Vec<String> = Vec::new();
+
+    for spec in specs {
+        let (tool, version) = parse_spec(spec)?;
+        let blueprint = blueprint(tool, version, &progress).await?;
+        if !blueprint.is_installed() {
+            anyhow::bail!(
+                "{tool}@{version} is not installed; run `eddy install {tool} {version}` first"
+            );
+        }
+        let dir = blueprint.version_dir();
+        excludes.push(format!("--exclude={dir}/{}", blueprint.info.pkg_name));
+        members.push(dir);
+        manifest.tools.push(BundledTool { tool: tool.to_string(), version: version.to_string() });
+    }
+
+    // The manifest is written to a scratch directory and added with its own `-C`,
+    // so it lands at the archive root. The PID keeps parallel runs apart.
+    let scratch = eddy_dir().join(format!(".bundle-{}", std::process::id()));
+    std::fs::create_dir_all(&scratch)?;
+    std::fs::write(scratch.join(MANIFEST), toml::to_string(&manifest)?)?;
+
+    progress.message(&format!("Bundling {} into {}...", specs.join(", "), output.display()));
+    // `-a` (--auto-compress) chooses zstd/gzip/xz from the output's extension.
+    // Option arguments come before the first `-C`: GNU tar applies --exclude only
+    // to members named after it.
+    let status = Command::new("tar")
+        .arg("-caf")
+        .arg(output)
+        .args(&excludes)
+        .arg("-C")
+        .arg(&scratch)
+        .arg(MANIFEST)
+        .arg("-C")
+        .arg(eddy_dir())
+        .args(&members)
+        .status()
+        .context("failed to run tar");
+    // Cleaned up before looking at the result, so a failed tar leaves nothing behind.
+    remove_path(&scratch)?;
+    let status = status?;
+    if !status.success() {
+        anyhow::bail!("tar exited with status {status}");
+    }
+    Ok(())
+}
+
+// Reads the manifest without unpacking anything else: `-O` writes the member to stdout.
+pub fn read_manifest(bundle: &Path) -> Result<BundleManifest> {
+    let output = Command::new("tar")
+        .arg("-xOf")
+        .arg(bundle)
+        .arg(MANIFEST)
+        .output()
+        .context("failed to run tar")?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "{} is not an eddy bundle (no {MANIFEST} inside)",
+            bundle.display()
+        );
+    }
+    let manifest: BundleManifest = toml::from_str(&String::from_utf8_lossy(&output.stdout))
+        .with_context(|| format!("invalid {MANIFEST} in {}", bundle.display()))?;
+    if manifest.version > BUNDLE_VERSION {
+        anyhow::bail!(
+            "{} was made by a newer eddy (bundle format {}); please upgrade eddy",
+            bundle.display(),
+            manifest.version
+        );
+    }
+    Ok(manifest)
+}
+
+// `eddy unbundle toolchain.tar.zst`: restores the version directories and links
+// every bundled tool, like `eddy use` would.
+pub async fn unbundle(bundle: &Path, progress: Arc<dyn Progress>) -> Result<()> {
+    let manifest = read_manifest(bundle)?;
+    if manifest.platform != platform() {
+        anyhow::bail!(
+            "{} was built for {}, this machine is {}",
+            bundle.display(),
+            manifest.platform,
+            platform()
+        );
+    }
+
+    let home = eddy_dir();
+    std::fs::create_dir_all(&home)?;
+    progress.message(&format!("Unpacking {} into {}...", bundle.display(), home.display()));
+    let status = Command::new("tar")
+        .arg("-xf")
+        .arg(bundle)
+        .arg(format!("--exclude={MANIFEST}"))
+        .arg("-C")
+        .arg(&home)
+        .status()
+        .context("failed to run tar")?;
+    if !status.success() {
+        anyhow::bail!("tar exited with status {status}");
+    }
+
+    for entry in &manifest.tools {
+        let blueprint = blueprint(&entry.tool, &entry.version, &progress).await?;
+        // use_tool links through symlink_bin, so the result is the same as
+        // `eddy install` + `eddy use` on this machine.
+        blueprint.use_tool()?;
+        progress.message(&format!("Restored {}@{}", entry.tool, entry.version));
+    }
+    Ok(())
+}
@@ -0,0 +1,165 @@
+// The library entry point for programs that embed eddy — a build orchestrator
+// installing its own toolchains, say — instead of shelling out to the CLI:
+//
+//   let eddy = Eddy::new().with_home("/opt/ci/eddy".into()).with_progress(Arc::new(Silent));
+//   let go = eddy.install("go", "1.22.3").await?;
+//   eddy.use_version("go", "1.22.3").await?;
+//   println!("{}", go.bin_dir().display());
+//
+// An `Eddy` is cheap to clone (a path and an Arc) and is Send + Sync, so one can
+// be shared by — or cloned into — any number of tokio tasks. Tasks working on the
+// same tool version are serialised by the per-version file locks (locking.rs),
+// exactly like two eddy processes would be.
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::Result;
+
+use crate::{
+    blueprint::{ArtifactSource, ToolBlueprint},
+    consts::eddy_dir,
+    languages,
+    progress::{Progress, TerminalProgress},
+    types::Version,
+};
+
+#[derive(Clone)]
+pub struct Eddy {
+    home: PathBuf,
+    progress: Arc<dyn Progress>,
+}
+
+// `Default` lets callers write `Eddy::default()`, and clippy asks for it
+// whenever a type has an argument-less `new`.
+impl Default for Eddy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Eddy {
+    // The same home and output as the CLI: eddy_dir() (EDDY_HOME or ~/.eddy.sh)
+    // and progress bars on stderr.
+    pub fn new() -> Self {
+        Self { home: eddy_dir(), progress: Arc::new(TerminalProgress::new()) }
+    }
+
+    // Installs, links and locks under `home` instead; `home/bin` is the directory
+    // to put on PATH. Nothing is read from the environment.
+    pub fn with_home(mut self, home: PathBuf) -> Self {
+        self.home = home;
+        self
+    }
+
+    pub fn with_progress(mut self, progress: Arc<dyn Progress>) -> Self {
+        self.progress = progress;
+        self
+    }
+
+    pub fn home(&self) -> &Path {
+        &self.home
+    }
+
+    // `<home>/bin`, where `use_version` links executables.
+    pub fn bin_dir(&self) -> PathBuf {
+        self.home.join("bin")
+    }
+
+    // A blueprint for `tool@version` bound to this client's home and reporter.
+    // "latest" is resolved here, so the returned blueprint has a concrete version.
+    pub async fn blueprint(&self, tool: &str, version: &str) -> Result<ToolBlueprint> {
+        let info = languages::build_with(tool, Version::from(version), self.progress.clone()).await?;
+        Ok(ToolBlueprint::new(info)
+            .with_home(self.home.clone())
+            .with_progress(self.progress.clone()))
+    }
+
+    // Downloads and unpacks `tool@version` (an exact version or "latest") unless
+    // it's already installed. The returned blueprint knows the install's paths
+    // (`bin_dir`) and the artifact's checksum (`info.sha256`, when downloaded).
+    pub async fn install(&self, tool: &str, version: &str) -> Result<ToolBlueprint> {
+        self.install_from(tool, version, ArtifactSource::Release).await
+    }
+
+    // `install` with the artifact taken from a mirror URL or a local archive.
+    pub async fn install_from(
+        &self,
+        tool: &str,
+        version: &str,
+        source: ArtifactSource,
+    ) -> Result<ToolBlueprint> {
+        source.check_version(tool, &Version::from(version))?;
+        let mut blueprint = self.blueprint(tool, version).await?.with_source(source);
+        if blueprint.is_installed() {
+            return Ok(blueprint);
+        }
+        blueprint.install().await?;
+        Ok(blueprint)
+    }
+
+    // Links an installed version into `bin_dir()` — `eddy use`.
+    pub async fn use_version(&self, tool: &str, version: &str) -> Result<()> {
+        let blueprint = self.blueprint(tool, version).await?;
+        // use_tool may block on another task's lock; spawn_blocking keeps that
+        // wait off the executor threads. The clone moves into the closure.
+        tokio::task::spawn_blocking(move || blueprint.use_tool()).await?
+    }
+
+    // Every released version of `tool`, newest first, whether installed or not.
+    pub async fn versions(&self, tool: &str) -> Result<Vec<String>> {
+        languages::versions_with(tool, self.progress.clone()).await
+    }
+
+    // Installed versions of a built-in tool, newest first.
+    pub async fn list(&self, tool: &str) -> Result<Vec<String>> {
+        languages::installed_versions_in(&self.home, tool).await
+    }
+
+    // Deletes one installed version. The active one only with `force` — see
+    // `ToolBlueprint::delete`.
+    pub async fn delete(&self, tool: &str, version: &str, force: bool) -> Result<()> {
+        self.blueprint(tool, version).await?.delete(force).await
+    }
+
+    // `eddy delete <tool> --all`: every installed version of a built-in tool. Without
+    // `force` nothing is deleted while one of them is active, so the command either
+    // refuses up front or runs to the end. A version that fails to delete doesn't
+    // stop the others; the failures are reported and turn into the returned error.
+    pub async fn delete_all(&self, tool: &str, force: bool) -> Result<()> {
+        let versions = self.list(tool).await?;
+        if versions.is_empty() {
+            anyhow::bail!("no installed versions of {tool}");
+        }
+
+        let mut blueprints = Vec::new();
+        for version in &versions {
+            blueprints.push(self.blueprint(tool, version).await?);
+        }
+        if !force && let Some(active) = blueprints.iter().find(|b| b.is_active()) {
+            anyhow::bail!(
+                "{tool}@{} is the active version; pass --force to delete it along with the others",
+                active.info.version
+            );
+        }
+
+        let mut failed = 0;
+        for blueprint in &blueprints {
+            if let Err(e) = blueprint.delete(force).await {
+                // `{e:#}` prints the whole context chain on one line.
+                self.progress.message(&format!("{e:#}"));
+                failed += 1;
+            }
+        }
+        if failed > 0 {
+            anyhow::bail!("failed to delete {failed} of {} {tool} versions", blueprints.len());
+        }
+        // The `<lang>/<name>` directory is empty now; `remove_dir` (not `_all`)
+        // leaves it alone if something unexpected is still inside.
+        if let Some(blueprint) = blueprints.first()
+            && let Some(dir) = blueprint.version_path().parent()
+        {
+            let _ = std::fs::remove_dir(dir);
+        }
+        Ok(())
+    }
+}
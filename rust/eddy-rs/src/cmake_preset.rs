@@ -0,0 +1,180 @@
+// `eddy cmake-preset`: point CMake at the eddy-managed build tools without
+// hand-writing absolute paths into CMakeUserPresets.json.
+//
+//   eddy cmake-preset               adds (or refreshes) an "eddy" preset in CMakeUserPresets.json
+//   eddy cmake-preset --toolchain   writes eddy-toolchain.cmake for -DCMAKE_TOOLCHAIN_FILE
+//
+// The paths go into the active `<lang>/<name>/<version>` directories — the same
+// `bin_dir()` `use_tool` links from — rather than to the links in eddy_bin_dir(),
+// so a later `eddy use ninja <other>` doesn't silently change an existing build
+// tree. Rerun the command after switching versions.
+//
+// eddy has no compiler definitions of its own; a compiler comes from a plugin
+// tool pinned in eddy.toml whose executables are named like one (see COMPILERS).
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use serde_json::{Value, json};
+
+use crate::{
+    blueprint::ToolBlueprint,
+    current::selections,
+    languages::{self, TOOLS},
+    linker::Platform,
+    project::Project,
+    types::Version,
+};
+
+pub const PRESETS_FILE: &str = "CMakeUserPresets.json";
+pub const TOOLCHAIN_FILE: &str = "eddy-toolchain.cmake";
+
+// Executable names that make a plugin tool a compiler, most specific first:
+// (C compiler, C++ compiler).
+const COMPILERS: &[(&str, &str)] = &[("clang", "clang++"), ("gcc", "g++"), ("cc", "c++")];
+
+// Absolute paths of the executables CMake should use. None means "leave it to
+// CMake", which then searches PATH as usual.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Toolchain {
+    pub cmake: Option<PathBuf>,
+    pub make_program: Option<PathBuf>,
+    pub c_compiler: Option<PathBuf>,
+    pub cxx_compiler: Option<PathBuf>,
+}
+
+impl Toolchain {
+    pub fn is_empty(&self) -> bool {
+        *self == Toolchain::default()
+    }
+
+    // The cache variables to set, skipping the tools that weren't found.
+    fn cache_variables(&self) -> Vec<(&'static str, &Path)> {
+        [
+            ("CMAKE_MAKE_PROGRAM", &self.make_program),
+            ("CMAKE_C_COMPILER", &self.c_compiler),
+            ("CMAKE_CXX_COMPILER", &self.cxx_compiler),
+        ]
+        .into_iter()
+        .filter_map(|(var, path)| path.as_deref().map(|path| (var, path)))
+        .collect()
+    }
+}
+
+// The installed version of `tool` the build should use: the first scope (shell,
+// project, global — see current.rs) that selects an installed version. The preset
+// points into that version's directory, so a pin counts here even before
+// `eddy sync` has linked it.
+// A pin that isn't locked to an exact version yet selects nothing installable.
+async fn active_blueprint(tool: &str, project: Option<&Project>) -> Result<Option<ToolBlueprint>> {
+    for selection in selections(tool, project).await? {
+        let info = languages::build(tool, Version::SemVer(selection.version)).await?;
+        let blueprint = ToolBlueprint::new(info);
+        if blueprint.is_installed() {
+            return Ok(Some(blueprint));
+        }
+    }
+    Ok(None)
+}
+
+fn executable(blueprint: &ToolBlueprint, name: &str) -> PathBuf {
+    blueprint.bin_dir().join(Platform::current().exe_name(name))
+}
+
+pub async fn resolve(project: Option<&Project>) -> Result<Toolchain> {
+    let mut toolchain = Toolchain::default();
+    if let Some(cmake) = active_blueprint("cmake", project).await? {
+        toolchain.cmake = Some(executable(&cmake, "cmake"));
+    }
+    if let Some(ninja) = active_blueprint("ninja", project).await? {
+        toolchain.make_program = Some(executable(&ninja, "ninja"));
+    }
+
+    // Only plugin tools can be compilers, and only pinned ones are looked at —
+    // asking every plugin on PATH would mean spawning each of them.
+    let pinned: Vec<String> =
+        project.map(|p| p.manifest.tools.keys().cloned().collect()).unwrap_or_default();
+    for tool in pinned.iter().filter(|t| !TOOLS.contains(&t.as_str())) {
+        let Some(blueprint) = active_blueprint(tool, project).await? else {
+            continue;
+        };
+        let links = blueprint.link_names();
+        // `find` returns the first pair whose C compiler the tool ships.
+        if let Some((cc, cxx)) = COMPILERS.iter().find(|(cc, _)| links.contains(cc)) {
+            toolchain.c_compiler.get_or_insert(executable(&blueprint, cc));
+            if links.contains(cxx) {
+                toolchain.cxx_compiler.get_or_insert(executable(&blueprint, cxx));
+            }
+        }
+    }
+    Ok(toolchain)
+}
+
+// CMake reads `\` in a quoted string as an escape, so Windows paths are written
+// with forward slashes, which CMake accepts everywhere.
+fn cmake_path(path: &Path) -> String {
+    path.display().to_string().replace('\\', "/")
+}
+
+// A toolchain file for `cmake -DCMAKE_TOOLCHAIN_FILE=eddy-toolchain.cmake`. It
+// can't choose the cmake binary itself — run the eddy-managed one from PATH.
+pub fn toolchain_file(toolchain: &Toolchain) -> String {
+    let mut text = String::from(
+        "# Generated by `eddy cmake-preset --toolchain`: the eddy-managed build tools.\n\
+         # Rerun it after `eddy use` switches one of them to another version.\n",
+    );
+    for (var, path) in toolchain.cache_variables() {
+        // CACHE ... FORCE so a value cached by an earlier configure is replaced.
+        text += &format!("set({var} \"{}\" CACHE FILEPATH \"\" FORCE)\n", cmake_path(path));
+    }
+    text
+}
+
+// A configure preset named `name` plus a build preset using it. Presets schema
+// version 3 (CMake 3.21) is the first with `cmakeExecutable`.
+pub fn preset(toolchain: &Toolchain, name: &str) -> (Value, Value) {
+    let mut cache = serde_json::Map::new();
+    for (var, path) in toolchain.cache_variables() {
+        cache.insert(var.to_string(), json!(cmake_path(path)));
+    }
+
+    let mut configure = json!({
+        "name": name,
+        "displayName": "eddy-managed tools",
+        "binaryDir": format!("${{sourceDir}}/build/{name}"),
+        "cacheVariables": cache,
+    });
+    if toolchain.make_program.is_some() {
+        configure["generator"] = json!("Ninja");
+    }
+    if let Some(cmake) = &toolchain.cmake {
+        configure["cmakeExecutable"] = json!(cmake_path(cmake));
+    }
+    let build = json!({ "name": name, "configurePreset": name });
+    (configure, build)
+}
+
+// Adds the presets to an existing CMakeUserPresets.json (or `null` for none),
+// replacing earlier presets of the same name and keeping everything else the
+// user wrote.
+pub fn merge_presets(existing: Value, toolchain: &Toolchain, name: &str) -> Result<Value> {
+    let mut presets = match existing {
+        Value::Null => json!({ "version": 3 }),
+        Value::Object(_) => existing,
+        _ => anyhow::bail!("{PRESETS_FILE} is not a JSON object"),
+    };
+    if presets["version"].as_u64().is_none_or(|v| v < 3) {
+        presets["version"] = json!(3);
+    }
+
+    let (configure, build) = preset(toolchain, name);
+    for (key, item) in [("configurePresets", configure), ("buildPresets", build)] {
+        if !presets[key].is_array() {
+            presets[key] = json!([]);
+        }
+        // `unwrap` can't fail: the line above made sure it's an array.
+        let list = presets[key].as_array_mut().unwrap();
+        list.retain(|p| p["name"] != name);
+        list.push(item);
+    }
+    Ok(presets)
+}
@@ -0,0 +1,105 @@
+// Dynamic shell completion. clap_complete generates the static part (subcommands,
+// flags) from the `Cli` definition in main.rs; the snippets below are appended to
+// it and ask eddy itself for the candidates that depend on state:
+//
+//   eddy __complete tools             → every tool name eddy can install
+//   eddy __complete versions <tool>   → versions of <tool> installed under eddy_dir()
+//
+// Both are answered from disk and PATH only — no network — so tab stays instant.
+use std::path::PathBuf;
+
+use clap_complete::Shell;
+
+use crate::{languages::TOOLS, plugin::PREFIX};
+
+// Built-in tools first (in registry order), then plugins found on PATH.
+pub fn tools() -> Vec<String> {
+    let mut tools: Vec<String> = TOOLS.iter().map(|t| t.to_string()).collect();
+    for plugin in plugin_tools() {
+        if !tools.contains(&plugin) {
+            tools.push(plugin);
+        }
+    }
+    tools
+}
+
+// `eddy-plugin-acme` anywhere on PATH → "acme".
+fn plugin_tools() -> Vec<String> {
+    let Some(path) = std::env::var_os("PATH") else {
+        return Vec::new();
+    };
+    let mut found: Vec<String> = std::env::split_paths(&path)
+        // read_dir fails for PATH entries that don't exist; `.ok()` + flatten
+        // skips them instead of aborting the whole listing.
+        .filter_map(|dir| std::fs::read_dir(dir).ok())
+        .flatten()
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            // file_stem drops a Windows `.exe`/`.cmd` extension.
+            let stem = PathBuf::from(&name).file_stem()?.to_string_lossy().to_string();
+            stem.strip_prefix(PREFIX).map(str::to_string)
+        })
+        .collect();
+    found.sort();
+    found.dedup();
+    found
+}
+
+// `eddy __complete versions <tool>` lists what is installed; the lookup is
+// shared with `eddy delete --all`.
+pub use crate::languages::installed_versions;
+
+// Shell code appended after clap's generated script. Each one wraps the
+// generated `_eddy` function: tool and version positions are answered by
+// `eddy __complete`, everything else falls through to clap's completion.
+// PowerShell and Elvish only get the static completions.
+pub fn dynamic_script(shell: Shell) -> Option<&'static str> {
+    match shell {
+        Shell::Bash => Some(BASH),
+        Shell::Zsh => Some(ZSH),
+        Shell::Fish => Some(FISH),
+        _ => None,
+    }
+}
+
+const BASH: &str = r#"
+_eddy_dynamic() {
+    local cur="${COMP_WORDS[COMP_CWORD]}"
+    case "${COMP_WORDS[1]}" in
+        install|use|delete)
+            if [[ $COMP_CWORD -eq 2 ]]; then
+                COMPREPLY=($(compgen -W "$(eddy __complete tools 2>/dev/null)" -- "$cur"))
+                return
+            fi
+            if [[ $COMP_CWORD -eq 3 && "${COMP_WORDS[1]}" != install ]]; then
+                COMPREPLY=($(compgen -W "$(eddy __complete versions "${COMP_WORDS[2]}" 2>/dev/null)" -- "$cur"))
+                return
+            fi
+            ;;
+    esac
+    _eddy "$@"
+}
+complete -F _eddy_dynamic -o bashdefault -o default eddy
+"#;
+
+const ZSH: &str = r#"
+_eddy_dynamic() {
+    if [[ $words[2] == (install|use|delete) ]]; then
+        if (( CURRENT == 3 )); then
+            compadd -- ${(f)"$(eddy __complete tools 2>/dev/null)"}
+            return
+        elif (( CURRENT == 4 )) && [[ $words[2] != install ]]; then
+            compadd -- ${(f)"$(eddy __complete versions $words[3] 2>/dev/null)"}
+            return
+        fi
+    fi
+    _eddy "$@"
+}
+compdef _eddy_dynamic eddy
+"#;
+
+const FISH: &str = r#"
+complete -c eddy -n "__fish_seen_subcommand_from install use delete; and test (count (commandline -opc)) -eq 2" -f -a "(eddy __complete tools 2>/dev/null)"
+complete -c eddy -n "__fish_seen_subcommand_from use delete; and test (count (commandline -opc)) -eq 3" -f -a "(eddy __complete versions (commandline -opc)[3] 2>/dev/null)"
+"#;
@@ -0,0 +1,223 @@
+// Which version of a tool is active, and why. `eddy use` can select a version in
+// three scopes:
+//
+//   shell   `eval "$(eddy use go 1.22.3 --shell)"` — an EDDY_GO_VERSION variable plus
+//           the version's bin directory at the front of PATH, for this session only
+//   local   `eddy use go 1.22 --local` — a pin in the project's eddy.toml
+//   global  `eddy use go 1.22.3` — the link in eddy_bin_dir(), as before
+//
+// Only two of them decide which binary runs: the shell scope (its PATH entry comes
+// first) and otherwise the global link. A local pin is what the project asks for;
+// it runs once `eddy sync` installs and links it globally. `eddy current` lists
+// every scope that selects something and says which one is on PATH.
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+
+use crate::{
+    blueprint::ToolBlueprint,
+    consts::{eddy_bin_dir, eddy_dir},
+    languages::{self, TOOLS},
+    fs::RealFs,
+    linker::{Platform, link_target},
+    project::{Project, spec_matches},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scope {
+    Shell,
+    Local,
+    Global,
+}
+
+// Display is Rust's `toString()`: implementing it makes `{}` in format! work.
+// `pad` (rather than write_str) honours width flags such as `{:<7}`.
+impl fmt::Display for Scope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(match self {
+            Scope::Shell => "shell",
+            Scope::Local => "local",
+            Scope::Global => "global",
+        })
+    }
+}
+
+// One scope selecting a version of a tool. `origin` says where the selection
+// lives: the variable name, the eddy.toml path, or the link in eddy_bin_dir().
+#[derive(Debug, Clone, PartialEq)]
+pub struct Selection {
+    pub tool: String,
+    pub version: String,
+    pub scope: Scope,
+    pub origin: String,
+}
+
+// "go" → "EDDY_GO_VERSION". Plugin names may contain dashes, which aren't valid
+// in variable names: "my-tool" → "EDDY_MY_TOOL_VERSION".
+pub fn shell_var(tool: &str) -> String {
+    format!("EDDY_{}_VERSION", tool.to_ascii_uppercase().replace('-', "_"))
+}
+
+// The syntax `eddy use --shell` prints its exports in.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ShellKind {
+    Posix,
+    Fish,
+    PowerShell,
+}
+
+impl ShellKind {
+    // $SHELL names the user's login shell; fish is the only common one that
+    // doesn't understand `export`. On Windows there is no $SHELL to ask.
+    pub fn detect() -> Self {
+        if cfg!(windows) {
+            return ShellKind::PowerShell;
+        }
+        match std::env::var("SHELL") {
+            Ok(shell) if shell.ends_with("fish") => ShellKind::Fish,
+            _ => ShellKind::Posix,
+        }
+    }
+}
+
+// A child process can't change its parent's environment, so `--shell` prints
+// commands for the shell to evaluate instead of setting anything itself.
+// Improvement: running it twice prepends a second bin directory; the first one
+// is shadowed, so it's harmless, but PATH grows with every switch.
+pub fn shell_exports(kind: ShellKind, tool: &str, version: &str, bin_dir: &Path) -> String {
+    let var = shell_var(tool);
+    let bin = bin_dir.display();
+    match kind {
+        ShellKind::Posix => format!("export {var}=\"{version}\"\nexport PATH=\"{bin}:$PATH\"\n"),
+        ShellKind::Fish => format!("set -gx {var} \"{version}\"\nset -gx PATH \"{bin}\" $PATH\n"),
+        ShellKind::PowerShell => {
+            format!("$env:{var} = \"{version}\"\n$env:PATH = \"{bin};\" + $env:PATH\n")
+        }
+    }
+}
+
+// The version a global link points into, given the tool's `<lang>/<name>`
+// directory: `.../go/go-language/1.22.3/go/bin/go` → "1.22.3".
+pub fn version_from_link(target: &Path, tool_dir: &Path) -> Option<String> {
+    let rest = target.strip_prefix(tool_dir).ok()?;
+    let version = rest.components().next()?.as_os_str().to_string_lossy().to_string();
+    Some(version)
+}
+
+// The globally linked version of a built-in tool, with the link's path.
+// Plugin tools report None — see `languages::offline_info`.
+pub async fn global(tool: &str) -> Result<Option<(String, PathBuf)>> {
+    let Some(info) = languages::offline_info(tool).await? else {
+        return Ok(None);
+    };
+    let tool_dir = eddy_dir().join(info.lang).join(info.name);
+    let platform = Platform::current();
+    // Every link of a tool is switched together, so the first one is enough.
+    let Some(name) = ToolBlueprint::new(info).link_names().first().copied() else {
+        return Ok(None);
+    };
+    let link = eddy_bin_dir().join(platform.link_name(name));
+    let version = link_target(&RealFs, platform, &link).and_then(|t| version_from_link(&t, &tool_dir));
+    Ok(version.map(|v| (v, link)))
+}
+
+// Every scope that selects a version of `tool`: shell, local, global. `note`
+// says which of them is actually on PATH.
+pub async fn selections(tool: &str, project: Option<&Project>) -> Result<Vec<Selection>> {
+    let mut found = Vec::new();
+    let var = shell_var(tool);
+    // An empty variable (`EDDY_GO_VERSION=`) counts as unset, as it does for PATH lookups.
+    if let Ok(version) = std::env::var(&var)
+        && !version.is_empty()
+    {
+        found.push(Selection { tool: tool.to_string(), version, scope: Scope::Shell, origin: var });
+    }
+    if let Some(project) = project
+        && let Some(spec) = project.pin(tool)
+    {
+        // Show what the pin resolved to when the lock still agrees with it.
+        let version = match project.lock.get(tool) {
+            Some(locked) if spec_matches(spec, &locked.version) => locked.version.clone(),
+            _ => spec.to_string(),
+        };
+        found.push(Selection {
+            tool: tool.to_string(),
+            version,
+            scope: Scope::Local,
+            origin: project.manifest_path().display().to_string(),
+        });
+    }
+    if let Some((version, link)) = global(tool).await? {
+        found.push(Selection {
+            tool: tool.to_string(),
+            version,
+            scope: Scope::Global,
+            origin: link.display().to_string(),
+        });
+    }
+    Ok(found)
+}
+
+// The selection that is actually on PATH: the one `eddy current` lists without
+// a note. A pin only wins once it's linked, and then runs the linked version.
+pub fn winner(found: &[Selection]) -> Option<Selection> {
+    let find = |scope| found.iter().find(|s| s.scope == scope);
+    if let Some(shell) = find(Scope::Shell) {
+        return Some(shell.clone());
+    }
+    let global = find(Scope::Global)?;
+    match find(Scope::Local) {
+        Some(local) if note(local, found).is_none() => {
+            Some(Selection { version: global.version.clone(), ..local.clone() })
+        }
+        _ => Some(global.clone()),
+    }
+}
+
+// What `eddy current` says next to a selection, given all of the tool's
+// selections. The shell scope, if set, is what runs; otherwise the global link.
+// A pin runs only once `eddy sync` has linked it globally.
+pub fn note(selection: &Selection, found: &[Selection]) -> Option<&'static str> {
+    let find = |scope| found.iter().find(|s| s.scope == scope);
+    match selection.scope {
+        Scope::Shell => None,
+        Scope::Global if find(Scope::Shell).is_some() => Some("overridden in this shell"),
+        Scope::Global => None,
+        Scope::Local if find(Scope::Global).is_some_and(|g| spec_matches(&selection.version, &g.version)) => None,
+        Scope::Local => Some("pinned, not linked; run `eddy sync`"),
+    }
+}
+
+// `eddy current [tool]`, one row per selection; empty when nothing is selected.
+// Without a tool, every built-in tool plus whatever the project pins (plugin
+// tools included) is listed.
+pub async fn rows(tool: Option<&str>, project: Option<&Project>) -> Result<Vec<String>> {
+    let tools: Vec<String> = match tool {
+        Some(tool) => vec![tool.to_string()],
+        None => {
+            let mut tools: Vec<String> = TOOLS.iter().map(|t| t.to_string()).collect();
+            if let Some(project) = project {
+                for pinned in project.manifest.tools.keys() {
+                    if !tools.contains(pinned) {
+                        tools.push(pinned.clone());
+                    }
+                }
+            }
+            tools
+        }
+    };
+
+    let mut rows = Vec::new();
+    for tool in &tools {
+        let found = selections(tool, project).await?;
+        for selection in &found {
+            let note = note(selection, &found).map(|n| format!("  ({n})")).unwrap_or_default();
+            rows.push(format!(
+                "{:<8} {:<10} {:<7} {}{note}",
+                selection.tool, selection.version, selection.scope, selection.origin
+            ));
+        }
+    }
+    Ok(rows)
+}
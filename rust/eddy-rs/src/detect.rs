@@ -0,0 +1,142 @@
+// `eddy sync --detect`: infer pins from files a repository already has, so the
+// toolchain isn't declared twice (once for asdf/go/cmake/bazelisk, once for eddy).
+//
+// Each parser is a pure `&str -> Option<...>` function — the file reading lives
+// in `detect()` — which keeps them trivially testable without a filesystem.
+use std::path::Path;
+use std::sync::LazyLock;
+
+use anyhow::{Context, Result};
+use regex::Regex;
+
+use crate::languages;
+
+// One inferred pin plus where it came from, so `sync` can tell the user why it's
+// installing something they never wrote into eddy.toml.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Detected {
+    pub tool: String,
+    pub spec: String,
+    pub source: &'static str,
+}
+
+// Reads every known file under `root`. Later sources override earlier ones:
+// `.tool-versions` is a generic catch-all, while go.mod / CMakeLists.txt /
+// .bazelversion are the files the tools themselves obey, so they win.
+pub fn detect(root: &Path) -> Result<Vec<Detected>> {
+    let mut found: Vec<Detected> = Vec::new();
+    // Closure that inserts-or-replaces by tool name. `&mut found` is captured
+    // mutably, so `found` can't be touched elsewhere until the closure's last use.
+    let mut add = |tool: &str, spec: String, source: &'static str| {
+        found.retain(|d| d.tool != tool);
+        found.push(Detected { tool: tool.to_string(), spec, source });
+    };
+
+    if let Some(text) = read_optional(&root.join(".tool-versions"))? {
+        for (tool, spec) in parse_tool_versions(&text) {
+            add(&tool, spec, ".tool-versions");
+        }
+    }
+    if let Some(text) = read_optional(&root.join("go.mod"))?
+        && let Some(spec) = parse_go_mod(&text)
+    {
+        add("go", spec, "go.mod");
+    }
+    if let Some(text) = read_optional(&root.join("CMakeLists.txt"))?
+        && let Some(spec) = parse_cmake_minimum(&text)
+    {
+        add("cmake", spec, "CMakeLists.txt");
+    }
+    if let Some(text) = read_optional(&root.join(".bazelversion"))?
+        && let Some(spec) = parse_bazelversion(&text)
+    {
+        add("bazel", spec, ".bazelversion");
+    }
+    Ok(found)
+}
+
+// Ok(None) for "file doesn't exist", Err only for real I/O failures.
+fn read_optional(path: &Path) -> Result<Option<String>> {
+    if !path.is_file() {
+        return Ok(None);
+    }
+    std::fs::read_to_string(path)
+        .map(Some)
+        .with_context(|| format!("failed to read {}", path.display()))
+}
+
+// asdf's `.tool-versions`: `<plugin> <version> [fallback versions...]` per line,
+// `#` comments. Only the first version counts, and only plugins eddy knows.
+// asdf names Go's plugin `golang`; everything else matches our registry keys.
+pub fn parse_tool_versions(text: &str) -> Vec<(String, String)> {
+    text.lines()
+        // `split('#').next()` strips trailing comments; it always yields at least
+        // one item (possibly empty), so the unwrap_or is just for the type.
+        .map(|line| line.split('#').next().unwrap_or("").trim())
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            let plugin = parts.next()?;
+            let version = parts.next()?;
+            let tool = if plugin == "golang" { "go" } else { plugin };
+            // `system`, `ref:<sha>` and `path:<dir>` mean "not an installable release".
+            let installable = version != "system"
+                && !version.starts_with("ref:")
+                && !version.starts_with("path:");
+            (languages::TOOLS.contains(&tool) && installable)
+                .then(|| (tool.to_string(), version.to_string()))
+        })
+        .collect()
+}
+
+// go.mod: `toolchain go1.22.3` names the exact toolchain; otherwise the
+// `go 1.22` directive is the minimum language version, which we treat as
+// "newest 1.22.x" — always a toolchain that satisfies it.
+pub fn parse_go_mod(text: &str) -> Option<String> {
+    let directive = |name: &str| {
+        text.lines()
+            .map(str::trim)
+            .find_map(|line| line.strip_prefix(name)?.strip_prefix(' '))
+            .map(str::trim)
+    };
+    if let Some(toolchain) = directive("toolchain") {
+        // `toolchain default` means "whatever `go` is on PATH" — nothing to pin.
+        if let Some(ver) = toolchain.strip_prefix("go") {
+            return Some(ver.to_string());
+        }
+    }
+    directive("go").map(str::to_string)
+}
+
+// `\d+(?:\.\d+)*` rather than `[\d.]+`: the latter would swallow the `...`
+// separator and return "3.16...3.28" as one version. LazyLock compiles the
+// pattern once, on first use, instead of on every call.
+static CMAKE_MINIMUM: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)cmake_minimum_required\s*\(\s*VERSION\s+(\d+(?:\.\d+)*)(?:\.\.\.(\d+(?:\.\d+)*))?")
+        .unwrap()
+});
+
+// `cmake_minimum_required(VERSION 3.20)` or the policy-range form
+// `cmake_minimum_required(VERSION 3.20...3.28)`. With a range the upper bound is
+// the newest version the project was tested against, so that's what we pin.
+// The command name is case-insensitive in CMake, hence `(?i)`.
+pub fn parse_cmake_minimum(text: &str) -> Option<String> {
+    let caps = CMAKE_MINIMUM.captures(text)?;
+    // Group 2 (the max of a range) if present, else group 1 (the minimum).
+    let version = caps.get(2).or_else(|| caps.get(1))?.as_str();
+    Some(version.to_string())
+}
+
+// Bazelisk's `.bazelversion`: `7.1.0`, `7.x`, or `latest`. Forks
+// (`user/7.0.0`) and rolling builds (`last_green`) can't be pinned to a release.
+pub fn parse_bazelversion(text: &str) -> Option<String> {
+    let line = text.lines().map(str::trim).find(|l| !l.is_empty())?;
+    if line == "latest" {
+        return Some(line.to_string());
+    }
+    if let Some(major) = line.strip_suffix(".x") {
+        return Some(major.to_string());
+    }
+    line.chars()
+        .all(|c| c.is_ascii_digit() || c == '.')
+        .then(|| line.to_string())
+}
@@ -0,0 +1,127 @@
+// `eddy export dockerfile|devcontainer`: a container image with exactly the
+// toolchains the project pins, installed by eddy itself.
+//
+//   FROM debian:bookworm-slim
+//   ...install eddy...
+//   RUN eddy install cmake 4.1.4 && eddy use cmake 4.1.4
+//   RUN eddy install go 1.22.3 && eddy use go 1.22.3
+//
+// One RUN per tool is one image layer per tool: bumping go rebuilds the go layer
+// (and those after it) while the layers before it come from the build cache.
+//
+// The output is meant to be committed, so it's a pure function of eddy.toml,
+// eddy.lock and the eddy version: tools in name order, the locked version of each
+// (a pin must be locked, or "1.22" would mean a different image next month), and
+// no timestamps.
+use anyhow::Result;
+use serde_json::{Value, json};
+
+use crate::{
+    languages::TOOLS,
+    project::{Project, spec_matches},
+    self_update::{CURRENT_VERSION, REPO, TAG_PREFIX},
+};
+
+pub const DEFAULT_BASE: &str = "debian:bookworm-slim";
+// Inside the image. eddy_dir() appends `.eddy.sh` to EDDY_HOME.
+const EDDY_HOME: &str = "/opt/eddy";
+const EDDY_BIN: &str = "/opt/eddy/.eddy.sh/bin";
+
+// VS Code extensions worth having for a pinned tool, for devcontainer.json.
+const EXTENSIONS: &[(&str, &str)] = &[
+    ("go", "golang.go"),
+    ("cmake", "ms-vscode.cmake-tools"),
+    ("bazel", "BazelBuild.vscode-bazel"),
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pin {
+    pub tool: String,
+    pub version: String,
+}
+
+// The project's pins at their locked versions, in name order (the manifest is a
+// BTreeMap, so iterating it is already sorted).
+pub fn locked_pins(project: &Project) -> Result<Vec<Pin>> {
+    let mut pins = Vec::new();
+    for (tool, spec) in &project.manifest.tools {
+        let Some(locked) = project.lock.get(tool).filter(|l| spec_matches(spec, &l.version)) else {
+            anyhow::bail!(
+                "{tool} = \"{spec}\" is not locked; run `eddy lock` first so the image is reproducible"
+            );
+        };
+        pins.push(Pin { tool: tool.clone(), version: locked.version.clone() });
+    }
+    if pins.is_empty() {
+        anyhow::bail!("eddy.toml pins no tools");
+    }
+    Ok(pins)
+}
+
+// Everything before the per-tool layers. `{...}` are filled in by `dockerfile`;
+// `${EDDY_VERSION}` and `$asset` are left for Docker and the shell.
+const HEADER: &str = r#"# Generated by `eddy export dockerfile` from eddy.toml and eddy.lock; regenerate
+# it rather than editing by hand.
+FROM {base}
+
+# eddy unpacks archives with `tar`; bsdtar also reads the .zip releases (ninja's)
+# that GNU tar can't, so it goes first on PATH under that name.
+RUN apt-get update \
+    && apt-get install -y --no-install-recommends ca-certificates curl libarchive-tools \
+    && rm -rf /var/lib/apt/lists/* \
+    && ln -s /usr/bin/bsdtar /usr/local/bin/tar
+
+ARG EDDY_VERSION={eddy_version}
+RUN asset=eddy-linux-$(uname -m) \
+    && curl -fsSL -o /usr/local/bin/eddy {release}/$asset \
+    && curl -fsSL -o /tmp/eddy.sha256 {release}/$asset.sha256 \
+    && echo "$(cut -d' ' -f1 /tmp/eddy.sha256)  /usr/local/bin/eddy" | sha256sum -c - \
+    && chmod +x /usr/local/bin/eddy \
+    && rm /tmp/eddy.sha256
+
+ENV EDDY_HOME={eddy_home} \
+    EDDY_NO_UPDATE_CHECK=1 \
+    PATH={eddy_bin}:$PATH
+"#;
+
+// A Dockerfile for a Debian or Ubuntu `base` (the setup uses apt-get).
+pub fn dockerfile(pins: &[Pin], base: &str) -> String {
+    let release = format!("https://github.com/{REPO}/releases/download/{TAG_PREFIX}${{EDDY_VERSION}}");
+    let mut text = HEADER
+        .replace("{base}", base)
+        .replace("{eddy_version}", CURRENT_VERSION)
+        .replace("{release}", &release)
+        .replace("{eddy_home}", EDDY_HOME)
+        .replace("{eddy_bin}", EDDY_BIN);
+
+    text.push('\n');
+    for pin in pins {
+        if !TOOLS.contains(&pin.tool.as_str()) {
+            text += &format!("# {0} is a plugin tool: eddy-plugin-{0} must be on PATH.\n", pin.tool);
+        }
+        text += &format!(
+            "RUN eddy install {tool} {version} && eddy use {tool} {version}\n",
+            tool = pin.tool,
+            version = pin.version
+        );
+    }
+    text
+}
+
+// devcontainer.json next to the Dockerfile `dockerfile` writes.
+// https://containers.dev/implementors/json_reference/
+pub fn devcontainer(pins: &[Pin], name: &str) -> Value {
+    let extensions: Vec<&str> = EXTENSIONS
+        .iter()
+        .filter(|(tool, _)| pins.iter().any(|p| p.tool == *tool))
+        .map(|(_, extension)| *extension)
+        .collect();
+    json!({
+        "name": name,
+        "build": { "dockerfile": "Dockerfile", "context": "." },
+        // The image's ENV already has it; remoteEnv also covers the shells and
+        // tasks the editor starts itself.
+        "remoteEnv": { "PATH": format!("{EDDY_BIN}:${{containerEnv:PATH}}") },
+        "customizations": { "vscode": { "extensions": extensions } },
+    })
+}
@@ -0,0 +1,206 @@
+// The filesystem operations the linker needs, behind a trait so the Windows
+// code path can be exercised on a Linux CI box: tests hand the linker a
+// `FakeFs` (an in-memory tree) instead of touching the real disk.
+//
+// This is the "dependency injection through a trait" pattern. The TS version
+// would mock the `fs` module globally; in Rust the function takes `&impl
+// FileSystem`, and the compiler generates one copy per concrete type
+// (monomorphisation) — no runtime cost for the real implementation.
+#[cfg(any(test, feature = "test-util"))]
+use std::collections::BTreeMap;
+use std::io;
+use std::path::{Path, PathBuf};
+#[cfg(any(test, feature = "test-util"))]
+use std::sync::Mutex;
+
+pub trait FileSystem {
+    fn exists(&self, path: &Path) -> bool;
+    // True for a symlink even if its target is gone (a dangling link).
+    fn is_symlink(&self, path: &Path) -> bool;
+    fn read_link(&self, path: &Path) -> io::Result<PathBuf>;
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>>;
+    fn create_dir_all(&self, path: &Path) -> io::Result<()>;
+    fn write(&self, path: &Path, contents: &[u8]) -> io::Result<()>;
+    fn remove_file(&self, path: &Path) -> io::Result<()>;
+    fn rename(&self, from: &Path, to: &Path) -> io::Result<()>;
+    fn copy(&self, from: &Path, to: &Path) -> io::Result<()>;
+    fn symlink(&self, src: &Path, dst: &Path) -> io::Result<()>;
+}
+
+// Unit struct: no fields, zero size. It exists only to carry the trait impl.
+pub struct RealFs;
+
+impl FileSystem for RealFs {
+    fn exists(&self, path: &Path) -> bool {
+        path.exists()
+    }
+
+    fn is_symlink(&self, path: &Path) -> bool {
+        path.is_symlink()
+    }
+
+    fn read_link(&self, path: &Path) -> io::Result<PathBuf> {
+        std::fs::read_link(path)
+    }
+
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+        std::fs::read(path)
+    }
+
+    fn create_dir_all(&self, path: &Path) -> io::Result<()> {
+        std::fs::create_dir_all(path)
+    }
+
+    fn write(&self, path: &Path, contents: &[u8]) -> io::Result<()> {
+        std::fs::write(path, contents)
+    }
+
+    fn remove_file(&self, path: &Path) -> io::Result<()> {
+        std::fs::remove_file(path)
+    }
+
+    fn rename(&self, from: &Path, to: &Path) -> io::Result<()> {
+        std::fs::rename(from, to)
+    }
+
+    fn copy(&self, from: &Path, to: &Path) -> io::Result<()> {
+        // std::fs::copy also copies the permission bits, so an executable stays one.
+        std::fs::copy(from, to).map(|_| ())
+    }
+
+    fn symlink(&self, src: &Path, dst: &Path) -> io::Result<()> {
+        #[cfg(unix)]
+        return std::os::unix::fs::symlink(src, dst);
+
+        // Only reached on Windows, and only if someone asks the real filesystem
+        // for a symlink — the linker itself writes .cmd shims there instead.
+        #[cfg(windows)]
+        return std::os::windows::fs::symlink_file(src, dst);
+    }
+}
+
+// The fake is test scaffolding, not part of the library: it only exists in unit
+// tests and with the `test-util` feature, which the integration tests turn on
+// through the crate's dev-dependency on itself (see Cargo.toml).
+#[cfg(any(test, feature = "test-util"))]
+#[derive(Debug, Clone, PartialEq)]
+pub enum FakeEntry {
+    Dir,
+    File(Vec<u8>),
+    Symlink(PathBuf),
+}
+
+// An in-memory filesystem for tests. Mutex gives interior mutability: the trait
+// methods take `&self` (like the real fs, which needs no `&mut`), yet writes
+// still have to modify the map. Mutex rather than RefCell keeps FakeFs `Sync`.
+#[cfg(any(test, feature = "test-util"))]
+#[derive(Debug, Default)]
+pub struct FakeFs {
+    entries: Mutex<BTreeMap<PathBuf, FakeEntry>>,
+}
+
+#[cfg(any(test, feature = "test-util"))]
+impl FakeFs {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn entry(&self, path: &Path) -> Option<FakeEntry> {
+        self.entries.lock().unwrap().get(path).cloned()
+    }
+
+    // Seeds a file (and its parent directories) for a test.
+    pub fn add_file(&self, path: &Path, contents: &[u8]) {
+        if let Some(parent) = path.parent() {
+            self.create_dir_all(parent).unwrap();
+        }
+        self.entries.lock().unwrap().insert(path.to_path_buf(), FakeEntry::File(contents.to_vec()));
+    }
+
+    fn not_found(path: &Path) -> io::Error {
+        io::Error::new(io::ErrorKind::NotFound, format!("{} not found", path.display()))
+    }
+}
+
+#[cfg(any(test, feature = "test-util"))]
+impl FileSystem for FakeFs {
+    fn exists(&self, path: &Path) -> bool {
+        // Follows symlinks like Path::exists: a dangling link doesn't "exist".
+        match self.entry(path) {
+            Some(FakeEntry::Symlink(target)) => self.exists(&target),
+            Some(_) => true,
+            None => false,
+        }
+    }
+
+    fn is_symlink(&self, path: &Path) -> bool {
+        matches!(self.entry(path), Some(FakeEntry::Symlink(_)))
+    }
+
+    fn read_link(&self, path: &Path) -> io::Result<PathBuf> {
+        match self.entry(path) {
+            Some(FakeEntry::Symlink(target)) => Ok(target),
+            _ => Err(io::Error::new(io::ErrorKind::InvalidInput, "not a symlink")),
+        }
+    }
+
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+        match self.entry(path) {
+            Some(FakeEntry::File(contents)) => Ok(contents),
+            Some(FakeEntry::Symlink(target)) => self.read(&target),
+            Some(FakeEntry::Dir) => Err(io::Error::new(io::ErrorKind::IsADirectory, "is a directory")),
+            None => Err(Self::not_found(path)),
+        }
+    }
+
+    fn create_dir_all(&self, path: &Path) -> io::Result<()> {
+        let mut entries = self.entries.lock().unwrap();
+        for dir in path.ancestors() {
+            entries.entry(dir.to_path_buf()).or_insert(FakeEntry::Dir);
+        }
+        Ok(())
+    }
+
+    fn write(&self, path: &Path, contents: &[u8]) -> io::Result<()> {
+        let parent_exists = path.parent().is_none_or(|p| self.entry(p) == Some(FakeEntry::Dir));
+        if !parent_exists {
+            return Err(Self::not_found(path));
+        }
+        self.entries.lock().unwrap().insert(path.to_path_buf(), FakeEntry::File(contents.to_vec()));
+        Ok(())
+    }
+
+    fn remove_file(&self, path: &Path) -> io::Result<()> {
+        let mut entries = self.entries.lock().unwrap();
+        match entries.get(path) {
+            Some(FakeEntry::Dir) => Err(io::Error::new(io::ErrorKind::IsADirectory, "is a directory")),
+            Some(_) => {
+                entries.remove(path);
+                Ok(())
+            }
+            None => Err(Self::not_found(path)),
+        }
+    }
+
+    fn rename(&self, from: &Path, to: &Path) -> io::Result<()> {
+        let mut entries = self.entries.lock().unwrap();
+        let entry = entries.remove(from).ok_or_else(|| Self::not_found(from))?;
+        // Like POSIX rename(2): an existing file at `to` is replaced.
+        entries.insert(to.to_path_buf(), entry);
+        Ok(())
+    }
+
+    fn copy(&self, from: &Path, to: &Path) -> io::Result<()> {
+        let contents = self.read(from)?;
+        self.write(to, &contents)
+    }
+
+    fn symlink(&self, src: &Path, dst: &Path) -> io::Result<()> {
+        let mut entries = self.entries.lock().unwrap();
+        if entries.contains_key(dst) {
+            return Err(io::Error::new(io::ErrorKind::AlreadyExists, "already exists"));
+        }
+        entries.insert(dst.to_path_buf(), FakeEntry::Symlink(src.to_path_buf()));
+        Ok(())
+    }
+}
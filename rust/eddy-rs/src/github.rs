@@ -0,0 +1,253 @@
+// A small GitHub releases client shared by every tool that ships through GitHub
+// (cmake, bazel, ninja, conan). It replaces the "HEAD /releases/latest/download/..."
+// trick: that only works when the tag is a plain `X.Y.Z` and GitHub's idea of
+// "latest" matches ours, which breaks for Bazel pre-releases and Conan's
+// parallel 1.x / 2.x release lines.
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use reqwest::{Client, StatusCode, header};
+use serde::Deserialize;
+
+use crate::{
+    http::{self, RetryPolicy},
+    progress::{Progress, TerminalProgress},
+    shared::{now_secs, version_key},
+    types::Version,
+};
+
+const API_BASE: &str = "https://api.github.com";
+
+// GitHub pages the release list 100 at a time. CMake alone is past 100 once
+// release candidates are counted, so the pages are followed — up to this many,
+// in case a misbehaving proxy keeps handing out "next" links.
+const MAX_PAGES: usize = 20;
+
+// Only the fields we need are declared; serde skips the rest of the (large)
+// release payload. `#[serde(default)]` fills missing booleans with `false`, so a
+// hand-written JSON fixture in the tests doesn't have to spell out every flag.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Release {
+    pub tag_name: String,
+    #[serde(default)]
+    pub prerelease: bool,
+    #[serde(default)]
+    pub draft: bool,
+    #[serde(default)]
+    pub assets: Vec<Asset>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Asset {
+    pub name: String,
+    pub browser_download_url: String,
+}
+
+impl Release {
+    // Tags are either `v4.1.4` (cmake, ninja) or `8.5.0` (bazel, conan).
+    // The version we store in ToolInfo never carries the `v`.
+    pub fn version(&self) -> &str {
+        self.tag_name.strip_prefix('v').unwrap_or(&self.tag_name)
+    }
+
+    pub fn is_stable(&self) -> bool {
+        !self.draft && !self.prerelease
+    }
+
+    // `impl Fn(&str) -> bool` accepts any closure — the caller decides what
+    // "matches this platform" means (exact name, regex, suffix...).
+    pub fn find_asset(&self, matches: impl Fn(&str) -> bool) -> Option<&Asset> {
+        self.assets.iter().find(|a| matches(&a.name))
+    }
+}
+
+// Pure selection logic, split out of the client so it can be tested without
+// network access. Returns the stable releases for which `asset_name` (computed from
+// the release's version) is actually attached — a release that doesn't ship our
+// platform's archive yet is skipped rather than offered.
+// Collecting into a Vec (instead of returning `impl Iterator`) means the closure
+// doesn't have to outlive the call, only the borrowed releases do.
+pub fn with_asset(
+    releases: &[Release],
+    asset_name: impl Fn(&str) -> String,
+) -> Vec<&Release> {
+    releases
+        .iter()
+        .filter(|r| r.is_stable())
+        .filter(|r| {
+            let expected = asset_name(r.version());
+            r.find_asset(|name| name == expected).is_some()
+        })
+        .collect()
+}
+
+// The returned reference borrows from `releases` (lifetime elision: the only
+// input reference lends its lifetime to the output).
+pub fn pick_latest(
+    releases: &[Release],
+    asset_name: impl Fn(&str) -> String,
+) -> Option<&Release> {
+    with_asset(releases, asset_name)
+        .into_iter()
+        // max_by_key, not "first in the list": the API orders by creation date,
+        // so a 1.x maintenance release published after 2.23 would otherwise win.
+        .max_by_key(|r| version_key(r.version()))
+}
+
+pub struct GithubClient {
+    client: Client,
+    token: Option<String>,
+    retry: RetryPolicy,
+    api_base: String,
+    // Where retry notices go; the terminal unless `with_progress` says otherwise.
+    progress: Arc<dyn Progress>,
+}
+
+impl Default for GithubClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GithubClient {
+    pub fn new() -> Self {
+        // The shared client already sends the User-Agent GitHub insists on.
+        // Cloning a Client is cheap: it's a handle to the same connection pool.
+        let client = http::client().clone();
+        // An empty GITHUB_TOKEN (common in CI templates) is treated as unset.
+        let token = std::env::var("GITHUB_TOKEN").ok().filter(|t| !t.is_empty());
+        Self {
+            client,
+            token,
+            retry: RetryPolicy::default(),
+            api_base: API_BASE.to_string(),
+            progress: Arc::new(TerminalProgress::new()),
+        }
+    }
+
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    pub fn with_progress(mut self, progress: Arc<dyn Progress>) -> Self {
+        self.progress = progress;
+        self
+    }
+
+    // Points the client at another API root — a GitHub Enterprise server, or a
+    // stub server in the tests.
+    pub fn with_api_base(mut self, api_base: impl Into<String>) -> Self {
+        self.api_base = api_base.into();
+        self
+    }
+
+    // `repo` is "owner/name", e.g. "Kitware/CMake". Every page, in API order.
+    pub async fn list_releases(&self, repo: &str) -> Result<Vec<Release>> {
+        let mut url = format!("{}/repos/{repo}/releases?per_page=100", self.api_base);
+        let mut releases = Vec::new();
+        for _ in 0..MAX_PAGES {
+            let (page, next) = self.releases_page(&url, repo).await?;
+            releases.extend(page);
+            match next {
+                Some(next) => url = next,
+                None => return Ok(releases),
+            }
+        }
+        tracing::debug!(repo, pages = MAX_PAGES, "stopped following release pages");
+        Ok(releases)
+    }
+
+    // One page of releases, and the URL of the next page if there is one.
+    async fn releases_page(&self, url: &str, repo: &str) -> Result<(Vec<Release>, Option<String>)> {
+        let mut req = self
+            .client
+            .get(url)
+            .header(header::ACCEPT, "application/vnd.github+json");
+        if let Some(ref token) = self.token {
+            req = req.bearer_auth(token);
+        }
+
+        let resp = http::send(req, self.retry, self.progress.as_ref())
+            .await
+            .with_context(|| format!("failed to list releases for {repo}"))?;
+
+        // Unauthenticated clients get 60 requests/hour. GitHub answers 403 (or 429)
+        // with `x-ratelimit-remaining: 0` once that's used up — turn it into an
+        // actionable message instead of a bare "403 Forbidden".
+        let status = resp.status();
+        if status == StatusCode::FORBIDDEN || status == StatusCode::TOO_MANY_REQUESTS {
+            let headers = resp.headers();
+            let remaining = header_u64(headers, "x-ratelimit-remaining");
+            if remaining == Some(0) || status == StatusCode::TOO_MANY_REQUESTS {
+                let reset = header_u64(headers, "x-ratelimit-reset");
+                anyhow::bail!("{}", rate_limit_message(reset, self.token.is_some()));
+            }
+        }
+
+        let resp = resp
+            .error_for_status()
+            .with_context(|| format!("failed to list releases for {repo}"))?;
+        let next = resp
+            .headers()
+            .get(header::LINK)
+            .and_then(|v| v.to_str().ok())
+            .and_then(next_link);
+        Ok((resp.json().await?, next))
+    }
+
+    // The newest stable version of `repo` that ships this platform's artifact —
+    // what a tool's "latest" resolves to. `asset` names that artifact for a
+    // version; the tools pass `|v| build(v).pkg_name`, so the asset name format
+    // lives in their `build` and nowhere else.
+    pub async fn latest_version(&self, repo: &str, asset: impl Fn(Version) -> String) -> Result<String> {
+        let releases = self.list_releases(repo).await?;
+        let release = pick_latest(&releases, |v| asset(Version::SemVer(v.to_string())))
+            .with_context(|| format!("no stable release of {repo} has an asset for this platform"))?;
+        Ok(release.version().to_string())
+    }
+
+    // Every installable version, newest first.
+    pub async fn versions(&self, repo: &str, asset: impl Fn(Version) -> String) -> Result<Vec<String>> {
+        let releases = self.list_releases(repo).await?;
+        let mut versions: Vec<String> = with_asset(&releases, |v| asset(Version::SemVer(v.to_string())))
+            .into_iter()
+            .map(|r| r.version().to_string())
+            .collect();
+        // sort_by_key sorts ascending; reverse() flips it to newest-first.
+        versions.sort_by_key(|v| version_key(v));
+        versions.reverse();
+        Ok(versions)
+    }
+}
+
+// The `rel="next"` URL from a Link header such as
+//   <https://api.github.com/...&page=2>; rel="next", <https://...&page=5>; rel="last"
+pub fn next_link(link: &str) -> Option<String> {
+    link.split(',').find_map(|part| {
+        let (url, params) = part.split_once(';')?;
+        let is_next = params.split(';').any(|p| p.trim() == r#"rel="next""#);
+        let url = url.trim().strip_prefix('<')?.strip_suffix('>')?;
+        is_next.then(|| url.to_string())
+    })
+}
+
+fn header_u64(headers: &header::HeaderMap, name: &str) -> Option<u64> {
+    headers.get(name)?.to_str().ok()?.parse().ok()
+}
+
+// `reset` is the Unix timestamp (seconds) at which the quota refills.
+pub fn rate_limit_message(reset: Option<u64>, authenticated: bool) -> String {
+    let mut msg = String::from("GitHub API rate limit exceeded");
+    if let Some(reset) = reset {
+        let now = now_secs();
+        // saturating_sub: if our clock is ahead of GitHub's, report 0 instead of
+        // underflowing (u64 subtraction panics on overflow in debug builds).
+        let minutes = reset.saturating_sub(now).div_ceil(60);
+        msg.push_str(&format!("; resets in {minutes} min"));
+    }
+    if !authenticated {
+        msg.push_str(". Set GITHUB_TOKEN to raise the limit");
+    }
+    msg
+}
@@ -0,0 +1,262 @@
+// An append-only log of every install, use and delete, so "what was active before
+// this broke the build?" has an answer:
+//
+//   <eddy_dir>/history.jsonl
+//   {"at":1718000000,"action":"use","tool":"go-language","version":"1.22.3","bin_dir":"...","links":["go","gofmt"],"previous":{"version":"1.21.0","bin_dir":"..."}}
+//
+// JSON Lines rather than TOML: each event is one line appended to the end, so
+// recording one never means reading, parsing and rewriting the whole file.
+//
+// `eddy rollback go` relinks the `previous` of go's last `use`. A rollback is
+// itself logged like a use, so a second rollback undoes the first — `cd -` for
+// toolchains.
+use std::fmt;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    consts::eddy_dir,
+    current::version_from_link,
+    fs::RealFs,
+    languages,
+    linker::{Platform, link_target},
+    locking::ToolLock,
+    progress::Progress,
+    shared::{now_secs, rfc3339, symlink_bin},
+};
+
+pub const HISTORY_FILE: &str = "history.jsonl";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Action {
+    Install,
+    Use,
+    Delete,
+    Rollback,
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(match self {
+            Action::Install => "install",
+            Action::Use => "use",
+            Action::Delete => "delete",
+            Action::Rollback => "rollback",
+        })
+    }
+}
+
+// A version as it was linked: enough to link it again.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Target {
+    pub version: String,
+    pub bin_dir: PathBuf,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Entry {
+    pub at: u64,
+    pub action: Action,
+    // ToolInfo::name ("go-language"), as in install receipts.
+    pub tool: String,
+    pub version: String,
+    pub bin_dir: PathBuf,
+    // The executables the tool links onto PATH.
+    #[serde(default)]
+    pub links: Vec<String>,
+    // What was active when this happened; None if nothing was.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub previous: Option<Target>,
+}
+
+impl Entry {
+    pub fn new(action: Action, tool: &str, version: &str, bin_dir: PathBuf, links: &[&str]) -> Self {
+        Self {
+            at: now_secs(),
+            action,
+            tool: tool.to_string(),
+            version: version.to_string(),
+            bin_dir,
+            links: links.iter().map(|l| l.to_string()).collect(),
+            previous: None,
+        }
+    }
+
+    pub fn with_previous(mut self, previous: Option<Target>) -> Self {
+        self.previous = previous;
+        self
+    }
+
+    // The version the tool's links pointed to after this entry.
+    fn target(&self) -> Target {
+        Target { version: self.version.clone(), bin_dir: self.bin_dir.clone() }
+    }
+}
+
+pub fn history_path(home: &Path) -> PathBuf {
+    home.join(HISTORY_FILE)
+}
+
+// Appends one line. O_APPEND writes land at the end of the file even with other
+// eddy processes appending at the same time, and a single short `write_all` is
+// not interleaved with theirs in practice.
+pub fn append(home: &Path, entry: &Entry) -> Result<()> {
+    let path = history_path(home);
+    let mut line = serde_json::to_string(entry)?;
+    line.push('\n');
+    std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .and_then(|mut file| file.write_all(line.as_bytes()))
+        .with_context(|| format!("failed to write {}", path.display()))
+}
+
+// Oldest first. A line that doesn't parse — the tail of a write cut short by a
+// crash, say — is skipped rather than making the whole history unreadable.
+pub fn load(home: &Path) -> Result<Vec<Entry>> {
+    let path = history_path(home);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let text = std::fs::read_to_string(&path)
+        .with_context(|| format!("failed to read {}", path.display()))?;
+    Ok(text.lines().filter_map(|line| serde_json::from_str(line).ok()).collect())
+}
+
+// What `link` in `link_dir` points into right now, given the directory holding
+// every version of its tool: `.../go/go-language/1.21.0/go/bin/go` → 1.21.0.
+pub fn active_target(link_dir: &Path, link: &str, tool_dir: &Path) -> Option<Target> {
+    let platform = Platform::current();
+    let target = link_target(&RealFs, platform, &link_dir.join(platform.link_name(link)))?;
+    let version = version_from_link(&target, tool_dir)?;
+    Some(Target { version, bin_dir: target.parent()?.to_path_buf() })
+}
+
+// `tool` as typed on the command line ("go") or as recorded ("go-language").
+async fn matches(entry: &Entry, tool: &str) -> Result<bool> {
+    if entry.tool == tool {
+        return Ok(true);
+    }
+    let info = languages::offline_info(tool).await?;
+    Ok(info.is_some_and(|info| info.name == entry.tool))
+}
+
+// `eddy history [tool]`: the entries to show, oldest first like a shell's history.
+pub async fn entries(tool: Option<&str>) -> Result<Vec<Entry>> {
+    let mut entries = Vec::new();
+    for entry in load(&eddy_dir())? {
+        let keep = match tool {
+            Some(tool) => matches(&entry, tool).await?,
+            None => true,
+        };
+        if keep {
+            entries.push(entry);
+        }
+    }
+    Ok(entries)
+}
+
+// One row of `eddy history`.
+impl fmt::Display for Entry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}  {:<8} {:<12} {}", rfc3339(self.at), self.action, self.tool, self.version)?;
+        if let Some(previous) = &self.previous {
+            write!(f, "  (was {})", previous.version)?;
+        }
+        Ok(())
+    }
+}
+
+// `<home>/<lang>/<name>/<version>/...` → `<home>/<lang>/<name>`. Entries don't
+// record the language, but every bin_dir they record sits under it.
+fn tool_dir(home: &Path, name: &str, bin_dir: &Path) -> Option<(String, PathBuf)> {
+    let lang = bin_dir.strip_prefix(home).ok()?.components().next()?;
+    let lang = lang.as_os_str().to_string_lossy().to_string();
+    let dir = home.join(&lang).join(name);
+    bin_dir.starts_with(&dir).then_some((lang, dir))
+}
+
+// `eddy rollback [tool]`: relinks what was active before the last `use` (or
+// rollback) of `tool` — or of whichever tool was switched last.
+pub async fn rollback(tool: Option<&str>, progress: Arc<dyn Progress>) -> Result<()> {
+    let home = eddy_dir();
+    let entries = entries(tool).await?;
+    let Some(last) = entries
+        .iter()
+        .rev()
+        .find(|e| matches!(e.action, Action::Use | Action::Rollback))
+    else {
+        match tool {
+            Some(tool) => anyhow::bail!("nothing to roll back: no `eddy use` of {tool} recorded"),
+            None => anyhow::bail!("nothing to roll back: no `eddy use` recorded"),
+        }
+    };
+    let Some(previous) = &last.previous else {
+        anyhow::bail!(
+            "nothing to roll back to: {}@{} was the first version of it in use",
+            last.tool,
+            last.version
+        );
+    };
+    let (lang, tool_dir) = tool_dir(&home, &last.tool, &previous.bin_dir).with_context(|| {
+        format!("{}@{} is not installed under {}", last.tool, previous.version, home.display())
+    })?;
+
+    // The same locks `eddy use` takes, in the same order: the version being
+    // restored can't be deleted underneath us, and nobody relinks the tool
+    // between the check below and the history entry recording the change.
+    let (_version, _) = ToolLock::acquire_async(
+        home.clone(),
+        lang,
+        last.tool.clone(),
+        previous.version.clone(),
+        progress.clone(),
+    )
+    .await?;
+    let _links = ToolLock::acquire_links_async(home.clone(), last.tool.clone(), progress.clone()).await?;
+
+    // Undoing the last recorded switch only makes sense if it's still in effect;
+    // links rewritten by hand (or by an older eddy) aren't in the history.
+    let active = match last.links.first() {
+        Some(link) => active_target(&home.join("bin"), link, &tool_dir).map(|t| t.version),
+        None => None,
+    };
+    if active.as_deref() != Some(last.version.as_str()) {
+        anyhow::bail!(
+            "{} links no longer point to {} (the last recorded switch; now {}): they were changed \
+             outside eddy, so there is nothing to roll back",
+            last.tool,
+            last.version,
+            active.as_deref().unwrap_or("nothing")
+        );
+    }
+    if !previous.bin_dir.exists() {
+        anyhow::bail!(
+            "{}@{} has been deleted since; reinstall it with `eddy install`",
+            last.tool,
+            previous.version
+        );
+    }
+
+    for link in &last.links {
+        symlink_bin(&previous.bin_dir, link)?;
+    }
+    let links: Vec<&str> = last.links.iter().map(String::as_str).collect();
+    let entry = Entry::new(
+        Action::Rollback,
+        &last.tool,
+        &previous.version,
+        previous.bin_dir.clone(),
+        &links,
+    )
+    .with_previous(Some(last.target()));
+    append(&home, &entry)?;
+    progress.message(&format!("Rolled back {} from {} to {}", last.tool, last.version, previous.version));
+    Ok(())
+}
@@ -0,0 +1,216 @@
+// One HTTP client for every request eddy makes, configured once:
+//   - connect and read timeouts, so a stalled mirror fails instead of hanging
+//   - retries with exponential backoff on 5xx answers and dropped connections
+//   - HTTPS_PROXY / NO_PROXY from the environment
+//   - a `eddy/<version>` User-Agent (GitHub's API rejects requests without one)
+//
+// reqwest::Client holds a connection pool behind an Arc, so sharing one instance
+// also reuses TLS connections across requests — building a fresh client per call
+// (as download_file used to) threw that away every time.
+use std::sync::LazyLock;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use reqwest::{Client, ClientBuilder, Proxy, RequestBuilder, Response, StatusCode, redirect};
+
+use crate::progress::Progress;
+
+pub const USER_AGENT: &str = concat!("eddy/", env!("CARGO_PKG_VERSION"));
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(15);
+// Per read, not for the whole body: a 300 MB toolchain on a slow link is fine
+// as long as bytes keep arriving.
+const READ_TIMEOUT: Duration = Duration::from_secs(30);
+
+// LazyLock (std since 1.80) runs the closure on first access and caches the
+// result for the rest of the process — a thread-safe lazily initialised global.
+// The TS equivalent is a module-level `const client = createClient()`.
+static CLIENT: LazyLock<Client> = LazyLock::new(|| {
+    builder()
+        .redirect(redirect::Policy::custom(follow_redirect))
+        .build()
+        // Only fails for an invalid TLS backend setup or proxy URL — there is no
+        // sensible way to continue without a client.
+        .expect("failed to build HTTP client")
+});
+
+// `resolve_latest_version` inspects the 302 from `releases/latest` itself, so it
+// needs a client that doesn't follow redirects.
+static NO_REDIRECT_CLIENT: LazyLock<Client> = LazyLock::new(|| {
+    builder()
+        .redirect(redirect::Policy::none())
+        .build()
+        .expect("failed to build HTTP client")
+});
+
+// What `Policy::limited(10)` does, plus a log line per hop: a release download
+// usually goes github.com → objects.githubusercontent.com, and a mirror or proxy
+// in between is exactly what a failing install's log needs to show.
+fn follow_redirect(attempt: redirect::Attempt) -> redirect::Action {
+    tracing::debug!(
+        status = %attempt.status(),
+        from = attempt.previous().last().map(|url| url.as_str()),
+        to = %attempt.url(),
+        "redirect"
+    );
+    if attempt.previous().len() > 10 {
+        attempt.error("too many redirects")
+    } else {
+        attempt.follow()
+    }
+}
+
+pub fn client() -> &'static Client {
+    &CLIENT
+}
+
+pub fn no_redirect_client() -> &'static Client {
+    &NO_REDIRECT_CLIENT
+}
+
+fn builder() -> ClientBuilder {
+    let builder = Client::builder()
+        .use_rustls_tls()
+        .user_agent(USER_AGENT)
+        .connect_timeout(CONNECT_TIMEOUT)
+        .read_timeout(READ_TIMEOUT);
+    match https_proxy() {
+        Some((_, Ok(proxy))) => builder.proxy(proxy),
+        _ => builder,
+    }
+}
+
+// Reads HTTPS_PROXY (or the lowercase form curl also accepts) and NO_PROXY.
+// Every download eddy makes is https, so HTTP_PROXY doesn't apply.
+// Returns the URL with the parse result; None when no proxy is set.
+fn https_proxy() -> Option<(String, reqwest::Result<Proxy>)> {
+    let url = ["HTTPS_PROXY", "https_proxy"]
+        .iter()
+        .find_map(|var| std::env::var(var).ok().filter(|v| !v.is_empty()))?;
+    let proxy = Proxy::https(&url).map(|proxy| proxy.no_proxy(reqwest::NoProxy::from_env()));
+    Some((url, proxy))
+}
+
+// An unparsable proxy URL is ignored rather than failing every command. The
+// clients are built lazily, deep inside whatever request comes first, so the
+// CLI asks for this warning up front and prints it itself.
+pub fn proxy_warning() -> Option<String> {
+    match https_proxy()? {
+        (url, Err(e)) => Some(format!("Ignoring HTTPS_PROXY={url}: {e}")),
+        (_, Ok(_)) => None,
+    }
+}
+
+// How often and how patiently to retry. The delay doubles after every failed
+// attempt (500ms, 1s, 2s, ...) up to `max_delay`, so a briefly overloaded
+// mirror gets room to recover instead of being hammered.
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 4,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(8),
+        }
+    }
+}
+
+impl RetryPolicy {
+    // A single attempt: for background requests (the update check) where failing
+    // fast and silently beats retrying — `send` only logs when it retries.
+    pub fn none() -> Self {
+        Self { max_attempts: 1, ..Self::default() }
+    }
+
+    // Delay before retry number `attempt` (1 = the first retry).
+    pub fn delay(&self, attempt: u32) -> Duration {
+        // checked_pow/checked_mul guard against overflow for large attempt counts;
+        // anything that overflows is over the cap anyway.
+        2u32.checked_pow(attempt.saturating_sub(1))
+            .and_then(|factor| self.base_delay.checked_mul(factor))
+            .map_or(self.max_delay, |d| d.min(self.max_delay))
+    }
+}
+
+// 5xx means "the server had a problem", which is often transient (a restarting
+// mirror, an overloaded CDN edge). 4xx means the request itself is wrong —
+// retrying a 404 just delays the inevitable error.
+pub fn is_retryable_status(status: StatusCode) -> bool {
+    status.is_server_error()
+}
+
+// Connection refused/reset, timeouts and bodies cut off mid-stream are worth
+// another try; a malformed URL or a redirect loop is not. reqwest reports a
+// streamed body that ends early as a *decode* error (wrapping the body error),
+// which is why `is_decode` is here — JSON parse failures never reach this check,
+// since they happen after the retry loop.
+pub fn is_retryable_error(err: &reqwest::Error) -> bool {
+    err.is_connect() || err.is_timeout() || err.is_request() || err.is_body() || err.is_decode()
+}
+
+// For errors that already went through anyhow (download_file's per-attempt
+// errors): an HTTP 5xx or a transient transport error is worth retrying; a file
+// write error or a 404 is not.
+pub fn is_transient(err: &anyhow::Error) -> bool {
+    // downcast_ref looks inside the anyhow::Error for a concrete error type — the
+    // Rust version of `err instanceof FetchError`.
+    match err.downcast_ref::<reqwest::Error>() {
+        Some(e) => match e.status() {
+            Some(status) => is_retryable_status(status),
+            None => is_retryable_error(e),
+        },
+        None => false,
+    }
+}
+
+// Sends a request, retrying transient failures according to `policy`. The final
+// response is returned whatever its status, so callers keep their own handling
+// of 4xx answers (the GitHub client turns a 403 into a rate-limit message).
+// Each retry is announced through `progress`: a line on the CLI's stderr, the
+// status bar in the TUI.
+pub async fn send(request: RequestBuilder, policy: RetryPolicy, progress: &dyn Progress) -> Result<Response> {
+    let mut attempt = 1;
+    loop {
+        // A RequestBuilder is consumed by send(), so each attempt sends a copy.
+        // try_clone only fails for streaming bodies, which eddy never sends.
+        let req = request
+            .try_clone()
+            .context("request body cannot be retried")?;
+        // Built by hand (rather than `req.send()`) to log the method and URL first.
+        let (client, req) = req.build_split();
+        let req = req?;
+        tracing::debug!(method = %req.method(), url = %req.url(), attempt, "request");
+        let last = attempt >= policy.max_attempts;
+        let result = client.execute(req).await;
+        match &result {
+            Ok(resp) => tracing::debug!(status = %resp.status(), url = %resp.url(), "response"),
+            Err(e) => tracing::debug!(error = %e, "request failed"),
+        }
+        match result {
+            Ok(resp) if last || !is_retryable_status(resp.status()) => return Ok(resp),
+            Ok(resp) => progress.message(&format!("{} returned {}; retrying", resp.url(), resp.status())),
+            Err(e) if last || !is_retryable_error(&e) => return Err(e.into()),
+            Err(e) => progress.message(&format!("Request failed ({e}); retrying")),
+        }
+        tokio::time::sleep(policy.delay(attempt)).await;
+        attempt += 1;
+    }
+}
+
+// GET `url` and decode the JSON body, with the default retry policy.
+pub async fn get_json<T: serde::de::DeserializeOwned>(url: &str, progress: &dyn Progress) -> Result<T> {
+    let resp = send(client().get(url), RetryPolicy::default(), progress)
+        .await
+        .with_context(|| format!("GET {url}"))?;
+    let body = resp
+        .error_for_status()
+        .with_context(|| format!("GET {url}"))?
+        .json()
+        .await?;
+    Ok(body)
+}
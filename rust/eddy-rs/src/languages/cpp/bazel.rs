@@ -1,4 +1,24 @@
-use crate::types::{InstallStep, ToolInfo, Version};
+use std::sync::Arc;
+
+use anyhow::Result;
+
+use crate::{
+    github::GithubClient,
+    progress::Progress,
+    types::{InstallStep, ToolInfo, Version},
+};
+
+const REPO: &str = "bazelbuild/bazel";
+
+// Bazel publishes release candidates (`9.0.0rc1`) as GitHub pre-releases;
+// the shared client skips those, so "latest" always means the newest stable build.
+pub async fn fetch_latest(progress: Arc<dyn Progress>) -> Result<String> {
+    GithubClient::new().with_progress(progress).latest_version(REPO, |v| build(v).pkg_name).await
+}
+
+pub async fn list_versions(progress: Arc<dyn Progress>) -> Result<Vec<String>> {
+    GithubClient::new().with_progress(progress).versions(REPO, |v| build(v).pkg_name).await
+}
 
 pub fn build(version: Version) -> ToolInfo {
     let ver = match &version {
@@ -42,5 +62,7 @@ pub fn build(version: Version) -> ToolInfo {
         // Rename: downloaded file is `bazel-X.Y.Z-darwin-arm64`, renamed to `bazel`.
         // Chmod: the binary needs execute permission (not set by GitHub downloads).
         steps: vec![InstallStep::Rename, InstallStep::Chmod],
+        sha256: None,
+        license: Some("Apache-2.0"),
     }
 }
@@ -1,10 +1,17 @@
 use std::path::PathBuf;
+use std::sync::Arc;
+
+use anyhow::Result;
 
 use crate::{
+    github::GithubClient,
+    progress::Progress,
     shared::base_pkg_name,
     types::{InstallStep, ToolInfo, Version},
 };
 
+const REPO: &str = "Kitware/CMake";
+
 // Compile-time constants: `const` is evaluated at compile time and inlined wherever
 // used. `&'static str` is the type of a string literal — zero allocation, lives forever.
 // `#[cfg(target_os = "macos")]` makes this constant only exist in the macOS build.
@@ -16,6 +23,16 @@ const CMAKE_BIN_PATH: &str = "CMake.app/Contents/bin";
 #[cfg(not(target_os = "macos"))]
 const CMAKE_BIN_PATH: &str = "bin";
 
+// The release is only accepted if it actually ships the archive `build` would
+// download for this platform.
+pub async fn fetch_latest(progress: Arc<dyn Progress>) -> Result<String> {
+    GithubClient::new().with_progress(progress).latest_version(REPO, |v| build(v).pkg_name).await
+}
+
+pub async fn list_versions(progress: Arc<dyn Progress>) -> Result<Vec<String>> {
+    GithubClient::new().with_progress(progress).versions(REPO, |v| build(v).pkg_name).await
+}
+
 // Synchronous: all information to build ToolInfo is available without I/O.
 // Returns ToolInfo directly (not Result) because nothing can fail here.
 // This is the right signature — don't add Result just for uniformity.
@@ -68,5 +85,7 @@ pub fn build(version: Version) -> ToolInfo {
         custom_bin_path: Some(custom_bin_path),
         links: Some(vec!["ccmake", "cmake", "cpack", "ctest"]),
         steps: vec![InstallStep::Extract],
+        sha256: None,
+        license: Some("BSD-3-Clause"),
     }
 }
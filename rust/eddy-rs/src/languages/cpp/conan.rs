@@ -1,6 +1,26 @@
 use std::path::PathBuf;
+use std::sync::Arc;
 
-use crate::types::{InstallStep, ToolInfo, Version};
+use anyhow::Result;
+
+use crate::{
+    github::GithubClient,
+    progress::Progress,
+    types::{InstallStep, ToolInfo, Version},
+};
+
+const REPO: &str = "conan-io/conan";
+
+// Conan maintains 1.x and 2.x side by side; a 1.x patch published after a 2.x
+// release must not be picked as "latest", which is why the client orders by
+// version number rather than by publish date.
+pub async fn fetch_latest(progress: Arc<dyn Progress>) -> Result<String> {
+    GithubClient::new().with_progress(progress).latest_version(REPO, |v| build(v).pkg_name).await
+}
+
+pub async fn list_versions(progress: Arc<dyn Progress>) -> Result<Vec<String>> {
+    GithubClient::new().with_progress(progress).versions(REPO, |v| build(v).pkg_name).await
+}
 
 pub fn build(version: Version) -> ToolInfo {
     let ver = match &version {
@@ -39,5 +59,7 @@ pub fn build(version: Version) -> ToolInfo {
         // links: None means the symlink uses the tool name ("conan") directly.
         links: None,
         steps: vec![InstallStep::Extract],
+        sha256: None,
+        license: Some("MIT"),
     }
 }
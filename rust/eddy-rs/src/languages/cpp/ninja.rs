@@ -1,4 +1,24 @@
-use crate::types::{InstallStep, ToolInfo, Version};
+use std::sync::Arc;
+
+use anyhow::Result;
+
+use crate::{
+    github::GithubClient,
+    progress::Progress,
+    types::{InstallStep, ToolInfo, Version},
+};
+
+const REPO: &str = "ninja-build/ninja";
+
+// Ninja's asset name carries no version, so every release matches — the
+// highest stable tag wins.
+pub async fn fetch_latest(progress: Arc<dyn Progress>) -> Result<String> {
+    GithubClient::new().with_progress(progress).latest_version(REPO, |v| build(v).pkg_name).await
+}
+
+pub async fn list_versions(progress: Arc<dyn Progress>) -> Result<Vec<String>> {
+    GithubClient::new().with_progress(progress).versions(REPO, |v| build(v).pkg_name).await
+}
 
 pub fn build(version: Version) -> ToolInfo {
     // Ninja's package name doesn't include the version number — it's always
@@ -36,5 +56,7 @@ pub fn build(version: Version) -> ToolInfo {
         links: None,
         // Extract only: the zip contains a single `ninja` executable at the root.
         steps: vec![InstallStep::Extract],
+        sha256: None,
+        license: Some("Apache-2.0"),
     }
 }
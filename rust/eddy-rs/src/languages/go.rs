@@ -4,29 +4,28 @@ use anyhow::Result;
 // proc-macro. No runtime reflection — the code is generated during `cargo build`.
 use serde::Deserialize;
 
-use crate::types::{InstallStep, ToolInfo, Version};
+use crate::{
+    http,
+    progress::Progress,
+    types::{InstallStep, ToolInfo, Version},
+};
 
 // Only the `version` field is needed from the JSON response; serde ignores unknown
 // fields by default. The JSON shape is: [{"version":"go1.25.5","stable":true,...}, ...]
 #[derive(Deserialize)]
 struct GoRelease {
     version: String,
+    // `include=all` also returns betas and release candidates; they carry
+    // `"stable": false`.
+    #[serde(default)]
+    stable: bool,
 }
 
-pub async fn fetch_latest() -> Result<String> {
-    // Method chaining through build → get → send → json:
-    // Each step returns a Result or future. `?` propagates any error immediately.
-    // `.json::<Vec<GoRelease>>()` deserializes the response body using serde.
-    // The turbofish `::<Vec<GoRelease>>` provides the type parameter explicitly;
-    // without it the compiler couldn't infer what type to deserialize into.
-    let releases: Vec<GoRelease> = reqwest::Client::builder()
-        .use_rustls_tls()
-        .build()?
-        .get("https://go.dev/dl/?mode=json")
-        .send()
-        .await?
-        .json()
-        .await?;
+pub async fn fetch_latest(progress: &dyn Progress) -> Result<String> {
+    // `get_json` goes through the shared client (timeouts, retries, proxy) and
+    // deserializes the body with serde. The annotation on `releases` tells it which
+    // type to deserialize into — the compiler infers get_json's type parameter from it.
+    let releases: Vec<GoRelease> = http::get_json("https://go.dev/dl/?mode=json", progress).await?;
 
     let raw = &releases[0].version; // &String, borrowed from the Vec
     // strip_prefix returns Option<&str>: Some("1.25.5") or None if "go" wasn't there.
@@ -36,13 +35,29 @@ pub async fn fetch_latest() -> Result<String> {
     Ok(ver)
 }
 
-pub async fn build(version: Version) -> Result<ToolInfo> {
+// Every stable Go release, newest first (the endpoint already sorts them).
+pub async fn list_versions(progress: &dyn Progress) -> Result<Vec<String>> {
+    let releases: Vec<GoRelease> = http::get_json("https://go.dev/dl/?mode=json&include=all", progress).await?;
+
+    Ok(releases
+        .into_iter()
+        .filter(|r| r.stable)
+        // `into_iter()` above gives owned GoRelease values, so `r.version` can be
+        // moved out when the prefix is absent instead of cloned.
+        .map(|r| match r.version.strip_prefix("go") {
+            Some(v) => v.to_string(),
+            None => r.version,
+        })
+        .collect())
+}
+
+pub async fn build(version: Version, progress: &dyn Progress) -> Result<ToolInfo> {
     // Destructure the enum to get a plain String version string.
     // `ref s` in the SemVer arm borrows `s` from inside the Version enum
     // rather than moving it out — needed because `version` is moved into
     // the returned ToolInfo below, and we can't partially move an enum.
     let ver = match version {
-        Version::Latest => fetch_latest().await?,
+        Version::Latest => fetch_latest(progress).await?,
         Version::SemVer(ref s) => s.clone(),
     };
 
@@ -83,5 +98,7 @@ pub async fn build(version: Version) -> Result<ToolInfo> {
         // &'static str: string literals live in the binary's read-only data segment.
         links: Some(vec!["go", "gofmt"]),
         steps: vec![InstallStep::Extract],
+        sha256: None,
+        license: Some("BSD-3-Clause"),
     })
 }
@@ -0,0 +1,224 @@
+// Go-based developer tools (golangci-lint, gopls, protoc-gen-go, ...) pinned like
+// any other toolchain:
+//
+//   eddy install go-tool golangci-lint@v1.59.0
+//
+// There is no release archive to download: the tool is built with `go install`,
+// using the Go toolchain eddy has linked, with GOBIN pointed at a versioned
+// directory of its own:
+//
+//   <eddy_dir>/go/tools/golangci-lint/v1.59.0/golangci-lint
+//
+// That directory is then linked into `<home>/bin` through `link_bin`, the same
+// as the binaries of an unpacked archive.
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+
+use crate::{
+    consts::eddy_dir,
+    fs::RealFs,
+    history::{self, Action, Entry},
+    linker::{Platform, link_bin, link_target},
+    locking::ToolLock,
+    progress::{Progress, TerminalProgress},
+    receipt::Receipt,
+    shared::{now_secs, sha256_file},
+};
+
+// Well-known tools by the name people actually type. Anything else is given as
+// its full package path: `eddy install go-tool mvdan.cc/gofumpt@v0.6.0`.
+const KNOWN: &[(&str, &str)] = &[
+    ("golangci-lint", "github.com/golangci/golangci-lint/cmd/golangci-lint"),
+    ("gopls", "golang.org/x/tools/gopls"),
+    ("protoc-gen-go", "google.golang.org/protobuf/cmd/protoc-gen-go"),
+    ("protoc-gen-go-grpc", "google.golang.org/grpc/cmd/protoc-gen-go-grpc"),
+    ("goimports", "golang.org/x/tools/cmd/goimports"),
+    ("dlv", "github.com/go-delve/delve/cmd/dlv"),
+    ("staticcheck", "honnef.co/go/tools/cmd/staticcheck"),
+];
+
+#[derive(Clone)]
+pub struct GoTool {
+    // The binary `go install` produces — the last element of the package path.
+    pub name: String,
+    pub package: String,
+    // A module version as Go spells it: "v1.59.0".
+    pub version: String,
+    // The eddy home to build into, link from and find the managed Go in, and
+    // where status lines go — see the same fields on ToolBlueprint.
+    home: PathBuf,
+    progress: Arc<dyn Progress>,
+}
+
+impl GoTool {
+    // "golangci-lint@v1.59.0", or "<package path>@<version>". The version is
+    // required (the install directory is per version), and a missing `v` is
+    // added: Go rejects "1.59.0" as a module version.
+    pub fn parse(spec: &str) -> Result<Self> {
+        let (tool, version) = match spec.rsplit_once('@') {
+            Some((tool, version)) if !tool.is_empty() && !version.is_empty() => (tool, version),
+            _ => anyhow::bail!(
+                "expected <tool>@<version>, e.g. golangci-lint@v1.59.0 (got \"{spec}\")"
+            ),
+        };
+        if version == "latest" {
+            anyhow::bail!("go tools need an explicit version, e.g. {tool}@v1.2.3");
+        }
+        let version = if version.starts_with(|c: char| c.is_ascii_digit()) {
+            format!("v{version}")
+        } else {
+            version.to_string()
+        };
+
+        let package = match KNOWN.iter().find(|(name, _)| *name == tool) {
+            Some((_, package)) => package.to_string(),
+            None if tool.contains('/') => tool.to_string(),
+            None => anyhow::bail!(
+                "unknown go tool {tool}: give its package path instead, e.g. example.com/cmd/{tool}@{version}"
+            ),
+        };
+        Ok(Self {
+            name: binary_name(&package),
+            package,
+            version,
+            home: eddy_dir(),
+            progress: Arc::new(TerminalProgress::new()),
+        })
+    }
+
+    pub fn with_home(mut self, home: PathBuf) -> Self {
+        self.home = home;
+        self
+    }
+
+    pub fn with_progress(mut self, progress: Arc<dyn Progress>) -> Self {
+        self.progress = progress;
+        self
+    }
+
+    // GOBIN for this tool version.
+    pub fn gobin(&self) -> PathBuf {
+        self.home.join("go/tools").join(&self.name).join(&self.version)
+    }
+
+    // Where the tool is linked, and where the managed `go` is looked up.
+    fn link_dir(&self) -> PathBuf {
+        self.home.join("bin")
+    }
+
+    pub fn is_installed(&self) -> bool {
+        self.gobin().join(Platform::current().exe_name(&self.name)).exists()
+    }
+
+    // Builds the tool (unless this version is already built) and links it onto PATH.
+    pub async fn install(&self) -> Result<()> {
+        let lock_name = format!("tool-{}", self.name);
+        let (_lock, _) = ToolLock::acquire_async(
+            self.home.clone(),
+            "go".to_string(),
+            lock_name,
+            self.version.clone(),
+            self.progress.clone(),
+        )
+        .await?;
+
+        if self.is_installed() {
+            self.progress.message(&format!("{}@{} is already installed", self.name, self.version));
+        } else {
+            self.go_install().await?;
+        }
+        let gobin = self.gobin();
+        let link_dir = self.link_dir();
+        // gobin's parent holds every version of the tool, as `<lang>/<name>` does.
+        let previous =
+            gobin.parent().and_then(|dir| history::active_target(&link_dir, &self.name, dir));
+        link_bin(&RealFs, Platform::current(), &gobin, &link_dir, &self.name)?;
+        if previous.as_ref().is_none_or(|p| p.version != self.version) {
+            self.record(Action::Use, previous);
+        }
+        self.progress.message(&format!(
+            "Installed {}@{} into {}",
+            self.name,
+            self.version,
+            gobin.display()
+        ));
+        Ok(())
+    }
+
+    async fn go_install(&self) -> Result<()> {
+        let go = managed_go(&self.link_dir())?;
+        let gobin = self.gobin();
+        std::fs::create_dir_all(&gobin)
+            .with_context(|| format!("failed to create {}", gobin.display()))?;
+
+        let target = format!("{}@{}", self.package, self.version);
+        self.progress.message(&format!("Running {} install {target}...", go.display()));
+        // GOTOOLCHAIN=local: a module asking for a newer Go must fail here rather
+        // than have `go` quietly download a toolchain eddy doesn't manage.
+        // tokio::process so the build (often a minute or more) doesn't block the executor.
+        let status = tokio::process::Command::new(&go)
+            .arg("install")
+            .arg(&target)
+            .env("GOBIN", &gobin)
+            .env("GOTOOLCHAIN", "local")
+            .status()
+            .await
+            .with_context(|| format!("failed to run {}", go.display()))?;
+        if !status.success() || !self.is_installed() {
+            // Nothing half-built is left behind to look like an install.
+            let _ = std::fs::remove_dir_all(&gobin);
+            anyhow::bail!("go install {target} failed ({status})");
+        }
+        // The binary is built locally, so its own digest is the one to record.
+        let binary = gobin.join(Platform::current().exe_name(&self.name));
+        Receipt {
+            name: self.name.clone(),
+            version: self.version.clone(),
+            url: format!("https://pkg.go.dev/{target}"),
+            sha256: Some(sha256_file(&binary)?),
+            license: None,
+            source: "go-install".to_string(),
+            installed_at: now_secs(),
+        }
+        .save(&gobin)?;
+        self.record(Action::Install, None);
+        Ok(())
+    }
+
+    // See `ToolBlueprint::record`.
+    fn record(&self, action: Action, previous: Option<history::Target>) {
+        let entry = Entry::new(action, &self.name, &self.version, self.gobin(), &[self.name.as_str()])
+            .with_previous(previous);
+        if let Err(e) = history::append(&self.home, &entry) {
+            self.progress.message(&format!("warning: {e:#}"));
+        }
+    }
+}
+
+// "github.com/golangci/golangci-lint/cmd/golangci-lint" → "golangci-lint". A
+// trailing major-version element is skipped, as `go install` does:
+// "example.com/cmd/tool/v2" builds "tool".
+pub fn binary_name(package: &str) -> String {
+    let mut parts = package.rsplit('/');
+    let last = parts.next().unwrap_or(package);
+    let is_major = last.len() > 1
+        && last.starts_with('v')
+        && last[1..].chars().all(|c| c.is_ascii_digit());
+    match parts.next() {
+        Some(parent) if is_major => parent.to_string(),
+        _ => last.to_string(),
+    }
+}
+
+// The `go` eddy has linked into `link_dir` with `eddy use go <version>`.
+// Its real path is used rather than the link, so the build is unaffected by a
+// concurrent `eddy use go` switching versions.
+fn managed_go(link_dir: &Path) -> Result<PathBuf> {
+    let platform = Platform::current();
+    let link = link_dir.join(platform.link_name("go"));
+    link_target(&RealFs, platform, &link)
+        .filter(|go| go.exists())
+        .context("no eddy-managed Go is active; run `eddy install go <version>` and `eddy use go <version>` first")
+}
@@ -2,33 +2,147 @@
 // The directory structure mirrors the module tree:
 //   src/languages/mod.rs      → crate::languages
 //   src/languages/go.rs       → crate::languages::go
+//   src/languages/go_tool.rs  → crate::languages::go_tool
 //   src/languages/cpp/mod.rs  → crate::languages::cpp
 pub mod cpp;
 pub mod go;
+pub mod go_tool;
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 use anyhow::Result;
 
-use crate::types::{ToolInfo, Version};
+use crate::{
+    consts::eddy_dir,
+    plugin::Plugin,
+    progress::{Progress, TerminalProgress},
+    shared::version_key,
+    types::{ToolInfo, Version},
+};
+
+// Every tool name `build` accepts, in the order they're listed to users.
+// Keep in sync with the match arms below — the compiler can't check that for us.
+pub const TOOLS: &[&str] = &["go", "cmake", "bazel", "ninja", "conan"];
+
+// Swap `Version::Latest` for a concrete version using the tool's own
+// `fetch_latest`. Generic over the future type `F` because every `async fn`
+// has its own anonymous future type — we can't name it, only bound it.
+async fn resolved<F>(version: Version, fetch_latest: impl FnOnce() -> F) -> Result<Version>
+where
+    F: std::future::Future<Output = Result<String>>,
+{
+    match version {
+        Version::Latest => Ok(Version::SemVer(fetch_latest().await?)),
+        other => Ok(other),
+    }
+}
 
 // `build` is the registry dispatch function — the equivalent of `languages[tool](version)`
-// from the TS index. It's async because `go::build` may need to fetch the latest version.
-// The cpp builders are synchronous; wrapping them in `Ok(...)` lifts their return type
-// into `Result<ToolInfo>` to match the async arm.
+// from the TS index. It's async because every tool may need to look up the latest version.
+// `go::build` resolves "latest" itself; the cpp builders are synchronous URL templates,
+// so "latest" is resolved here (through the GitHub releases API) before they run.
+// That way the returned ToolInfo never points at a `releases/latest/download` URL.
+// Retry notices and plugin output go to the terminal; see `build_with`.
 pub async fn build(tool: &str, version: Version) -> Result<ToolInfo> {
+    build_with(tool, version, Arc::new(TerminalProgress::new())).await
+}
+
+// `build`, reporting to `progress` (the Eddy client passes its own).
+pub async fn build_with(tool: &str, version: Version, progress: Arc<dyn Progress>) -> Result<ToolInfo> {
     // String patterns in `match` work on &str. The compiler checks that the arms
     // cover all specified cases — but it can't verify completeness for strings
     // (unlike enum variants, which are exhaustive). The catch-all `other` arm
     // handles any unknown tool name.
     match tool {
-        "go" => go::build(version).await,
+        "go" => go::build(version, progress.as_ref()).await,
         // `Ok(...)` wraps the synchronous Result-free value into the Result type
         // expected by the async match arm. This is the "lift into context" pattern.
-        "cmake" => Ok(cpp::cmake::build(version)),
-        "bazel" => Ok(cpp::bazel::build(version)),
-        "ninja" => Ok(cpp::ninja::build(version)),
-        "conan" => Ok(cpp::conan::build(version)),
+        // The closure defers the call — nothing is fetched unless the version is
+        // actually Latest.
+        "cmake" => Ok(cpp::cmake::build(resolved(version, || cpp::cmake::fetch_latest(progress)).await?)),
+        "bazel" => Ok(cpp::bazel::build(resolved(version, || cpp::bazel::fetch_latest(progress)).await?)),
+        "ninja" => Ok(cpp::ninja::build(resolved(version, || cpp::ninja::fetch_latest(progress)).await?)),
+        "conan" => Ok(cpp::conan::build(resolved(version, || cpp::conan::fetch_latest(progress)).await?)),
         // `other` binds the unmatched value — useful for the error message.
-        // Alternative: use `_` if you don't need the value.
-        other => anyhow::bail!("unknown tool: {other}"),
+        // Anything not built in may still come from an `eddy-plugin-<tool>` on PATH.
+        other => plugin(other)?.with_progress(progress).build(version).await,
     }
 }
+
+// All installable versions of `tool`, newest first. Used to resolve partial pins
+// such as `go = "1.22"` to a concrete release.
+pub async fn versions(tool: &str) -> Result<Vec<String>> {
+    versions_with(tool, Arc::new(TerminalProgress::new())).await
+}
+
+pub async fn versions_with(tool: &str, progress: Arc<dyn Progress>) -> Result<Vec<String>> {
+    match tool {
+        "go" => go::list_versions(progress.as_ref()).await,
+        "cmake" => cpp::cmake::list_versions(progress).await,
+        "bazel" => cpp::bazel::list_versions(progress).await,
+        "ninja" => cpp::ninja::list_versions(progress).await,
+        "conan" => cpp::conan::list_versions(progress).await,
+        other => plugin(other)?.with_progress(progress).list_versions().await,
+    }
+}
+
+fn plugin(tool: &str) -> Result<Plugin> {
+    // ok_or_else builds the error lazily — only when the lookup actually failed.
+    Plugin::find(tool).ok_or_else(|| {
+        anyhow::anyhow!("unknown tool: {tool} (no built-in support and no eddy-plugin-{tool} on PATH)")
+    })
+}
+
+// Fails with the "unknown tool" error unless `tool` is built in or provided by
+// a plugin — for commands that only record a tool name (`eddy use --local`).
+pub fn ensure_known(tool: &str) -> Result<()> {
+    if !TOOLS.contains(&tool) {
+        plugin(tool)?;
+    }
+    Ok(())
+}
+
+// A ToolInfo for a built-in tool with a placeholder version, for callers that
+// only need its `lang`, `name` and `links`. Building one for an exact version is
+// offline for every built-in tool. None for plugin tools: only the plugin knows
+// those, and asking it means spawning a process — too slow for completion.
+pub async fn offline_info(tool: &str) -> Result<Option<ToolInfo>> {
+    if !TOOLS.contains(&tool) {
+        return Ok(None);
+    }
+    Ok(Some(build(tool, Version::SemVer(String::new())).await?))
+}
+
+// `<eddy_dir>/<lang>/<name>`, the directory holding every installed version of a
+// built-in tool.
+pub async fn tool_dir(tool: &str) -> Result<Option<PathBuf>> {
+    let info = offline_info(tool).await?;
+    Ok(info.map(|info| eddy_dir().join(info.lang).join(info.name)))
+}
+
+// Installed versions of a built-in tool, newest first. Plugin tools aren't
+// listed: where they install is only known by asking the plugin, which could be
+// slow — completion must not be.
+pub async fn installed_versions(tool: &str) -> Result<Vec<String>> {
+    installed_versions_in(&eddy_dir(), tool).await
+}
+
+// `installed_versions` under an explicit eddy home (see client.rs).
+pub async fn installed_versions_in(home: &Path, tool: &str) -> Result<Vec<String>> {
+    let Some(info) = offline_info(tool).await? else {
+        return Ok(Vec::new());
+    };
+    let Ok(entries) = std::fs::read_dir(home.join(info.lang).join(info.name)) else {
+        // Nothing installed yet is not an error.
+        return Ok(Vec::new());
+    };
+    let mut versions: Vec<String> = entries
+        .flatten()
+        .filter(|e| e.path().is_dir())
+        .map(|e| e.file_name().to_string_lossy().to_string())
+        .collect();
+    versions.sort_by_key(|v| version_key(v));
+    versions.reverse();
+    Ok(versions)
+}
@@ -6,7 +6,29 @@
 // in `tests/` and the binary in `src/main.rs`, which depends on this library).
 // Without `pub`, the module would be private to this crate's library target.
 pub mod blueprint;
+pub mod bundle;
+pub mod client;
+pub mod cmake_preset;
+pub mod completions;
 pub mod consts;
+pub mod current;
+pub mod detect;
+pub mod export;
+pub mod fs;
+pub mod github;
+pub mod history;
+pub mod http;
 pub mod languages;
+pub mod linker;
+pub mod locking;
+pub mod lockfile;
+pub mod logging;
+pub mod plugin;
+pub mod progress;
+pub mod project;
+pub mod receipt;
+pub mod sbom;
+pub mod self_update;
 pub mod shared;
+pub mod tui;
 pub mod types;
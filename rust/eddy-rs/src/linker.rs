@@ -0,0 +1,133 @@
+// How a tool's executables get onto PATH. On Unix that's a symlink in
+// `eddy_bin_dir()`. On Windows symlinks need admin rights (or Developer Mode),
+// so each link is a tiny `.cmd` shim that forwards its arguments instead.
+//
+// `Platform` is a runtime value rather than `#[cfg(windows)]` branches so both
+// strategies are compiled — and tested — on every OS. `Platform::current()` is
+// the only place that consults the compile target.
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+
+use crate::fs::FileSystem;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Platform {
+    Unix,
+    Windows,
+}
+
+impl Platform {
+    pub fn current() -> Self {
+        // cfg!(...) is a compile-time constant bool usable in ordinary expressions;
+        // the untaken branch is still type-checked, unlike a #[cfg] attribute.
+        if cfg!(windows) { Platform::Windows } else { Platform::Unix }
+    }
+
+    // The file name of executable `name` on this platform: "go" → "go.exe".
+    // Names that already carry the suffix are left alone.
+    pub fn exe_name(self, name: &str) -> String {
+        match self {
+            Platform::Windows if !name.ends_with(".exe") => format!("{name}.exe"),
+            _ => name.to_string(),
+        }
+    }
+
+    // The file name of the PATH entry for `name`: the symlink itself on Unix,
+    // "go.cmd" on Windows (cmd.exe and PowerShell both resolve it via PATHEXT).
+    pub fn link_name(self, name: &str) -> String {
+        // trim_end_matches so a ".exe"-suffixed link name doesn't become "bazel.exe.cmd".
+        match self {
+            Platform::Unix => name.to_string(),
+            Platform::Windows => format!("{}.cmd", name.trim_end_matches(".exe")),
+        }
+    }
+}
+
+// Content of a Windows shim. `%*` forwards every argument; the quotes keep paths
+// with spaces (C:\Users\Jane Doe\...) intact. CRLF line endings, as cmd expects.
+pub fn cmd_shim(target: &Path) -> String {
+    format!("@echo off\r\n\"{}\" %*\r\n", target.display())
+}
+
+// Exposes `src_dir/<name>` in `link_dir` and returns the path of the created entry.
+// Any previous link for `name` (including a dangling one) is replaced atomically:
+// the new link is created under a temporary name and renamed over the old one.
+// rename(2) replaces the destination in a single step, so a concurrent process
+// running the tool sees either the old link or the new one — never a gap where
+// neither exists, which the old remove-then-symlink sequence allowed.
+pub fn link_bin(
+    fs: &impl FileSystem,
+    platform: Platform,
+    src_dir: &Path,
+    link_dir: &Path,
+    name: &str,
+) -> Result<PathBuf> {
+    if !fs.exists(link_dir) {
+        fs.create_dir_all(link_dir)?;
+    }
+    let src = src_dir.join(platform.exe_name(name));
+    let link_name = platform.link_name(name);
+    let link = link_dir.join(&link_name);
+    // The PID keeps two processes from racing on the same temporary name.
+    // Leading dot: hidden from `ls`, and never a valid tool name on PATH.
+    let tmp = link_dir.join(format!(".{link_name}.{}.tmp", std::process::id()));
+
+    // Leftover from a crashed run with a recycled PID. `exists()` follows links
+    // and is false for a dangling one, so check `is_symlink()` too.
+    if fs.exists(&tmp) || fs.is_symlink(&tmp) {
+        fs.remove_file(&tmp)?;
+    }
+
+    match platform {
+        Platform::Unix => fs
+            .symlink(&src, &tmp)
+            .with_context(|| format!("symlink {} -> {}", src.display(), tmp.display()))?,
+        Platform::Windows => fs
+            .write(&tmp, cmd_shim(&src).as_bytes())
+            .with_context(|| format!("write shim {}", tmp.display()))?,
+    }
+
+    if let Err(e) = fs.rename(&tmp, &link) {
+        // Don't leave the temporary behind if the swap itself failed.
+        let _ = fs.remove_file(&tmp);
+        return Err(e).with_context(|| format!("replace {}", link.display()));
+    }
+    Ok(link)
+}
+
+// The executable a PATH entry created by `link_bin` points at: the symlink target
+// on Unix, the quoted path inside the shim on Windows (see `cmd_shim`). None for
+// a missing entry or one eddy didn't write.
+pub fn link_target(fs: &impl FileSystem, platform: Platform, link: &Path) -> Option<PathBuf> {
+    match platform {
+        Platform::Unix => fs.read_link(link).ok(),
+        Platform::Windows => {
+            let shim = String::from_utf8(fs.read(link).ok()?).ok()?;
+            Some(PathBuf::from(shim.split('"').nth(1)?))
+        }
+    }
+}
+
+// Removes the entries for `names` in `link_dir` that point into `dir`, and returns
+// their paths. Links to anything else — another version of the same tool — stay.
+// Used before deleting a version directory, so no dangling link is left behind.
+pub fn remove_links_into(
+    fs: &impl FileSystem,
+    platform: Platform,
+    link_dir: &Path,
+    names: &[&str],
+    dir: &Path,
+) -> Result<Vec<PathBuf>> {
+    let mut removed = Vec::new();
+    for name in names {
+        let link = link_dir.join(platform.link_name(name));
+        // `is_some_and` is `Option`'s version of "exists and satisfies".
+        if link_target(fs, platform, &link).is_some_and(|target| target.starts_with(dir)) {
+            fs.remove_file(&link)
+                .with_context(|| format!("failed to remove {}", link.display()))?;
+            removed.push(link);
+        }
+    }
+    Ok(removed)
+}
@@ -0,0 +1,113 @@
+// `eddy.lock` — the exact artifacts a project resolved, in the spirit of Cargo.lock.
+// The manifest (`eddy.toml`) says what a project *wants* (`go = "latest"`,
+// `cmake = "4.1"`); the lock says what it *got*: one version per tool, and for
+// every platform a teammate installed on, the URL and SHA-256 of the archive.
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+pub const LOCK_FILE: &str = "eddy.lock";
+
+// Bumped if the on-disk layout ever changes incompatibly, so an older eddy can
+// refuse a lock it doesn't understand instead of silently misreading it.
+const LOCK_VERSION: u32 = 1;
+
+const HEADER: &str = "# This file is generated by eddy. Do not edit it by hand.\n\n";
+
+// `#[serde(rename = "tool")]` makes the TOML read naturally as `[[tool]]` entries
+// while the Rust field keeps its plural name.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Lockfile {
+    pub version: u32,
+    #[serde(default, rename = "tool")]
+    pub tools: Vec<LockedTool>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LockedTool {
+    pub name: String,
+    pub version: String,
+    #[serde(default, rename = "artifact")]
+    pub artifacts: Vec<Artifact>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Artifact {
+    pub platform: String,
+    pub url: String,
+    pub sha256: String,
+}
+
+impl Lockfile {
+    // A missing lock is not an error — it's the state of every project before
+    // its first install. Only an unreadable or malformed one is.
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self { version: LOCK_VERSION, tools: Vec::new() });
+        }
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        let lock: Self =
+            toml::from_str(&text).with_context(|| format!("failed to parse {}", path.display()))?;
+        if lock.version > LOCK_VERSION {
+            anyhow::bail!(
+                "{} was written by a newer eddy (lock version {}); please upgrade",
+                path.display(),
+                lock.version
+            );
+        }
+        Ok(lock)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        // Sort a copy so the file is byte-for-byte stable regardless of the order
+        // tools were installed in — otherwise every install would produce a noisy diff.
+        let mut sorted = self.clone();
+        sorted.version = LOCK_VERSION;
+        sorted.tools.sort_by(|a, b| a.name.cmp(&b.name));
+        for tool in &mut sorted.tools {
+            tool.artifacts.sort_by(|a, b| a.platform.cmp(&b.platform));
+        }
+        let body = toml::to_string(&sorted)?;
+        std::fs::write(path, format!("{HEADER}{body}"))
+            .with_context(|| format!("failed to write {}", path.display()))?;
+        Ok(())
+    }
+
+    pub fn get(&self, name: &str) -> Option<&LockedTool> {
+        self.tools.iter().find(|t| t.name == name)
+    }
+
+    pub fn artifact(&self, name: &str, platform: &str) -> Option<&Artifact> {
+        self.get(name)?.artifacts.iter().find(|a| a.platform == platform)
+    }
+
+    // Records the artifact a tool resolved to on one platform. If the tool was
+    // locked at a different version, the old entry — including the artifacts
+    // other platforms recorded for that version — is replaced: they would point
+    // at the wrong release.
+    pub fn record(&mut self, name: &str, version: &str, artifact: Artifact) {
+        // `position` + index instead of `iter_mut().find()`: we may need to push a
+        // new entry afterwards, which a live mutable borrow from `find` would block.
+        let idx = match self.tools.iter().position(|t| t.name == name) {
+            Some(i) if self.tools[i].version == version => i,
+            Some(i) => {
+                self.tools[i] = LockedTool { name: name.to_string(), version: version.to_string(), artifacts: Vec::new() };
+                i
+            }
+            None => {
+                self.tools.push(LockedTool { name: name.to_string(), version: version.to_string(), artifacts: Vec::new() });
+                self.tools.len() - 1
+            }
+        };
+        let artifacts = &mut self.tools[idx].artifacts;
+        artifacts.retain(|a| a.platform != artifact.platform);
+        artifacts.push(artifact);
+    }
+
+    // Drops entries for tools the manifest no longer pins.
+    pub fn retain_tools(&mut self, keep: impl Fn(&str) -> bool) {
+        self.tools.retain(|t| keep(&t.name));
+    }
+}
@@ -0,0 +1,126 @@
+// Advisory file locks that serialise eddy processes working on the same tool
+// version — two shells (or two CI jobs sharing a home directory) running
+// `eddy install go 1.22.3` at once would otherwise interleave downloads,
+// extractions and renames in the same directory.
+//
+// The lock is held by an open file handle: the OS releases it when the handle
+// is closed, which happens when `ToolLock` is dropped — or when the process dies,
+// so a crashed eddy can never leave a stale lock behind (unlike a PID file).
+use std::fs::{File, OpenOptions, TryLockError};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+
+use crate::progress::Progress;
+
+// RAII guard: holding a ToolLock means holding the lock. There is no unlock()
+// method — drop the value (or let it go out of scope) instead.
+pub struct ToolLock {
+    // Never read; kept alive for its Drop side effect (closing releases the lock).
+    _file: File,
+}
+
+impl ToolLock {
+    // Locks live under the eddy home they protect (`<home>/locks`), so two
+    // clients with different homes never wait on each other.
+    fn path(home: &Path, lang: &str, name: &str, version: &str) -> Result<PathBuf> {
+        // Flat file names: `go-go-language-1.22.3.lock`. One lock per version, so
+        // installing go 1.22 and go 1.23 in parallel still works.
+        Self::in_dir(&home.join("locks"), &format!("{lang}-{name}-{version}.lock"))
+    }
+
+    // `<home>/locks/links/<name>.lock`. Keyed by ToolInfo::name alone because
+    // that is all a history entry records, and it is what the links belong to.
+    fn links_path(home: &Path, name: &str) -> Result<PathBuf> {
+        Self::in_dir(&home.join("locks").join("links"), &format!("{name}.lock"))
+    }
+
+    fn in_dir(dir: &Path, file: &str) -> Result<PathBuf> {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("failed to create {}", dir.display()))?;
+        Ok(dir.join(file))
+    }
+
+    fn open(path: &Path) -> Result<File> {
+        // truncate(false): the file's content is irrelevant, we only lock it.
+        OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(path)
+            .with_context(|| format!("failed to open lock file {}", path.display()))
+    }
+
+    fn try_lock(path: &Path) -> Result<Option<Self>> {
+        let file = Self::open(path)?;
+        // File::try_lock (std, Rust 1.89+) takes an exclusive advisory lock:
+        // flock(2) on Unix, LockFileEx on Windows.
+        match file.try_lock() {
+            Ok(()) => Ok(Some(Self { _file: file })),
+            Err(TryLockError::WouldBlock) => Ok(None),
+            Err(TryLockError::Error(e)) => Err(e.into()),
+        }
+    }
+
+    // Blocks until the lock is free, reporting a note first so a waiting process
+    // doesn't look hung. `what` names the thing being waited for.
+    fn lock(path: &Path, what: &str, progress: &dyn Progress) -> Result<(Self, bool)> {
+        if let Some(lock) = Self::try_lock(path)? {
+            return Ok((lock, false));
+        }
+        progress.message(&format!("Waiting for another eddy process to finish with {what}..."));
+        let file = Self::open(path)?;
+        file.lock()?;
+        Ok((Self { _file: file }, true))
+    }
+
+    // Returns Ok(None) instead of waiting when another process holds the lock.
+    pub fn try_acquire(home: &Path, lang: &str, name: &str, version: &str) -> Result<Option<Self>> {
+        Self::try_lock(&Self::path(home, lang, name, version)?)
+    }
+
+    // Blocks until the lock is free. Returns whether it had to wait: a caller
+    // that waited should re-check the state the other process may have just changed.
+    pub fn acquire(
+        home: &Path,
+        lang: &str,
+        name: &str,
+        version: &str,
+        progress: &dyn Progress,
+    ) -> Result<(Self, bool)> {
+        let path = Self::path(home, lang, name, version)?;
+        Self::lock(&path, &format!("{name}@{version}"), progress)
+    }
+
+    // The async flavour for use inside tokio tasks: the blocking wait runs on
+    // tokio's blocking thread pool, so it can't stall the executor's workers.
+    // The arguments are owned (and the reporter an Arc) because the closure must
+    // be 'static — it may outlive this stack frame on another thread.
+    pub async fn acquire_async(
+        home: PathBuf,
+        lang: String,
+        name: String,
+        version: String,
+        progress: Arc<dyn Progress>,
+    ) -> Result<(Self, bool)> {
+        tokio::task::spawn_blocking(move || {
+            Self::acquire(&home, &lang, &name, &version, progress.as_ref())
+        })
+        .await?
+    }
+
+    // The lock on a tool's links in `<home>/bin`, whatever version they point to.
+    // The version locks don't cover them: `eddy use go 1.22` and `eddy use go 1.23`
+    // lock different versions but rewrite the same links, and each must read what
+    // is active, relink and record that in the history as one step. Always taken
+    // after a version lock, never before, so two processes can't deadlock.
+    pub fn acquire_links(home: &Path, name: &str, progress: &dyn Progress) -> Result<Self> {
+        let path = Self::links_path(home, name)?;
+        Ok(Self::lock(&path, &format!("the {name} links"), progress)?.0)
+    }
+
+    pub async fn acquire_links_async(home: PathBuf, name: String, progress: Arc<dyn Progress>) -> Result<Self> {
+        tokio::task::spawn_blocking(move || Self::acquire_links(&home, &name, progress.as_ref())).await?
+    }
+}
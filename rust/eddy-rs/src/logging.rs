@@ -0,0 +1,93 @@
+// Diagnostic logging, for when an install fails and the one-line error doesn't
+// say why. Off by default; the user-facing output stays the eprintln!/Progress
+// messages it always was.
+//
+//   eddy -v install go 1.22.3        spans per install, download and step
+//   eddy -vv install go 1.22.3       + every HTTP request, redirect, tar command, fs op
+//   eddy --log-file eddy.log ...     all of it, to a file to attach to a bug report
+//
+// The code only emits: `#[tracing::instrument]` on a function opens a span that
+// lives as long as the call and records its arguments, and `tracing::debug!(..)`
+// is an event inside the current span. Where they end up (if anywhere) is
+// decided once, here, by the subscriber — the same split as a logger interface
+// and its transports in a Node app.
+use std::path::Path;
+use std::sync::Mutex;
+
+use anyhow::{Context, Result};
+use tracing::level_filters::LevelFilter;
+use tracing_subscriber::{
+    field::RecordFields,
+    filter::Targets,
+    fmt::{
+        FormatFields,
+        format::{DefaultFields, FmtSpan, Writer},
+    },
+    prelude::*,
+};
+
+// -v → info, -vv → debug, -vvv and up → trace.
+pub fn level(verbosity: u8) -> LevelFilter {
+    match verbosity {
+        0 => LevelFilter::OFF,
+        1 => LevelFilter::INFO,
+        2 => LevelFilter::DEBUG,
+        _ => LevelFilter::TRACE,
+    }
+}
+
+// Only eddy's own spans: reqwest, hyper and rustls log through the `log` crate,
+// and at debug level they'd bury ours in connection-pool chatter.
+fn eddy_only(level: LevelFilter) -> Targets {
+    // The library is `eddy_rs`, the binary `eddy`.
+    Targets::new().with_target("eddy_rs", level).with_target("eddy", level)
+}
+
+// Span fields are formatted once per field formatter *type* and then shared by
+// every layer that uses that type, so with DefaultFields on both layers the
+// stderr layer's colour codes end up in the file. A newtype is a type of its own
+// and gets its own copy; DefaultFields leaves the colours out for a writer
+// without ANSI.
+struct PlainFields(DefaultFields);
+
+impl<'writer> FormatFields<'writer> for PlainFields {
+    fn format_fields<R: RecordFields>(&self, writer: Writer<'writer>, fields: R) -> std::fmt::Result {
+        self.0.format_fields(writer, fields)
+    }
+}
+
+// Installs the global subscriber: `verbosity` to stderr and, with a `log_file`,
+// everything down to debug into it (truncated first, so it holds one run).
+pub fn init(verbosity: u8, log_file: Option<&Path>) -> Result<()> {
+    // Each span is logged when it's entered and when it closes, the latter with
+    // how long it took.
+    let stderr = tracing_subscriber::fmt::layer()
+        .with_writer(std::io::stderr)
+        .with_span_events(FmtSpan::NEW | FmtSpan::CLOSE)
+        .with_filter(eddy_only(level(verbosity)));
+
+    // `Option<Layer>` is itself a layer (a no-op when None), so the file is optional
+    // without two differently-typed subscribers.
+    let file = match log_file {
+        Some(path) => {
+            let file = std::fs::File::create(path)
+                .with_context(|| format!("failed to create {}", path.display()))?;
+            let file_level = level(verbosity).max(LevelFilter::DEBUG);
+            // A Mutex<File> is a MakeWriter: events from any thread take turns.
+            let layer = tracing_subscriber::fmt::layer()
+                .with_writer(Mutex::new(file))
+                .with_ansi(false)
+                .fmt_fields(PlainFields(DefaultFields::new()))
+                .with_span_events(FmtSpan::NEW | FmtSpan::CLOSE)
+                .with_filter(eddy_only(file_level));
+            Some(layer)
+        }
+        None => None,
+    };
+
+    tracing_subscriber::registry()
+        .with(stderr)
+        .with(file)
+        .try_init()
+        .context("failed to set up logging")
+}
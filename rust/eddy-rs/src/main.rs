@@ -1,8 +1,24 @@
-use anyhow::Result;
-use clap::{Parser, Subcommand};
+use std::io::IsTerminal;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use clap::{CommandFactory, Parser, Subcommand};
 // The binary (`src/main.rs`) links against the library (`src/lib.rs`) by its crate name.
 // The crate name is "eddy-rs" in Cargo.toml but Rust normalizes hyphens to underscores.
-use eddy_rs::{blueprint::ToolBlueprint, languages, types::Version};
+use eddy_rs::{
+    blueprint::{ArtifactSource, ToolBlueprint},
+    client::Eddy,
+    bundle, cmake_preset, completions, current, detect, export, history, http,
+    languages::{self, go_tool::GoTool},
+    logging,
+    progress::{Progress, TerminalProgress},
+    project::Project,
+    sbom, self_update,
+    shared::now_secs,
+    tui,
+    types::Version,
+};
 
 // #[derive(Parser)] is a proc-macro from the `clap` crate. At compile time it reads
 // the struct definition and generates all CLI parsing boilerplate:
@@ -11,12 +27,24 @@ use eddy_rs::{blueprint::ToolBlueprint, languages, types::Version};
 //   - error messages for missing/invalid arguments
 // This replaces ~50 lines of manual commander setup from the TS version.
 #[derive(Parser)]
-#[command(name = "eddy", version = "0.1.0", about = "CLI to install self-contained toolchains")]
+// A bare `version` makes clap print CARGO_PKG_VERSION, so `eddy --version` can't
+// drift from Cargo.toml (self-update compares against the same value).
+#[command(name = "eddy", version, about = "CLI to install self-contained toolchains")]
 struct Cli {
     // A nested enum tagged with #[command(subcommand)] becomes subcommand dispatch.
     // clap maps the variant name to the CLI subcommand name (Install → "install").
+    // Optional: plain `eddy` opens the interactive UI (tui/).
     #[command(subcommand)]
-    command: Commands,
+    command: Option<Commands>,
+    // `global = true` lets the flag go before or after the subcommand:
+    // `eddy -v install go` and `eddy install go -v` both work.
+    // ArgAction::Count counts repetitions, so `-vv` is 2.
+    /// Log what eddy does to stderr: -v for each install step, -vv for every request and file operation
+    #[arg(short, long, action = clap::ArgAction::Count, global = true)]
+    verbose: u8,
+    /// Also write a detailed log to this file, e.g. to attach to a bug report
+    #[arg(long, value_name = "PATH", global = true)]
+    log_file: Option<PathBuf>,
 }
 
 // #[derive(Subcommand)] generates subcommand routing. Each variant is a subcommand.
@@ -26,21 +54,198 @@ struct Cli {
 enum Commands {
     // `///` doc comments on variants become the subcommand description in --help.
     // This is the same slot as `.description(...)` in commander.
-    /// Install a tool at a specific version (or latest)
+    /// Install a tool at a specific version (or latest), or `go-tool <name>@<version>`
     Install {
         tool: String,
-        version: String,
+        // Option<String> makes the positional argument optional. When omitted
+        // inside a project, the version comes from eddy.lock / eddy.toml.
+        version: Option<String>,
+        /// Install from a local archive instead of downloading (air-gapped machines)
+        // `requires` makes clap reject `--from` without a version; `conflicts_with`
+        // rejects passing both sources at once.
+        #[arg(long, value_name = "ARCHIVE", requires = "version", conflicts_with = "url")]
+        from: Option<PathBuf>,
+        /// Download the archive from this URL instead of the official release
+        #[arg(long, requires = "version")]
+        url: Option<String>,
     },
-    /// Symlink an installed tool version into PATH
+    /// Select a tool version: globally (default), for this project, or this shell
     Use {
         tool: String,
         version: String,
+        // Flags in the same `group` are mutually exclusive — clap's ArgGroup
+        // defaults to "at most one of these".
+        /// Pin the version in the current project's eddy.toml (created if missing)
+        #[arg(long, group = "scope")]
+        local: bool,
+        /// Link the version into eddy's bin directory for every shell (the default)
+        #[arg(long, group = "scope")]
+        global: bool,
+        /// Print exports for this shell only: `eval "$(eddy use go 1.22.3 --shell)"`
+        #[arg(long, group = "scope")]
+        shell: bool,
+    },
+    /// Show the selected version of each tool and which scope selects it
+    Current {
+        /// Only show this tool
+        tool: Option<String>,
+    },
+    /// Show the log of installs, uses and deletes
+    History {
+        /// Only show this tool
+        tool: Option<String>,
     },
-    /// Delete an installed tool version
+    /// Switch back to the version that was active before the last `eddy use`
+    Rollback {
+        /// The tool to roll back (default: whichever was switched last)
+        tool: Option<String>,
+    },
+    /// Delete an installed tool version (or every version with --all)
     Delete {
         tool: String,
-        version: String,
+        // `required_unless_present`: either a version or --all, and
+        // `conflicts_with` rejects both at once.
+        #[arg(required_unless_present = "all")]
+        version: Option<String>,
+        /// Delete every installed version of the tool
+        #[arg(long, conflicts_with = "version")]
+        all: bool,
+        /// Also delete the active version, removing its links from PATH
+        #[arg(long)]
+        force: bool,
+    },
+    /// Install and activate every tool pinned in eddy.toml, honouring eddy.lock
+    Sync {
+        /// Also pin tools from .tool-versions, go.mod, CMakeLists.txt and .bazelversion
+        #[arg(long)]
+        detect: bool,
+    },
+    /// Write eddy.lock for the tools pinned in eddy.toml
+    Lock {
+        // `#[arg(long)]` turns a bool field into a `--update` flag (false when absent).
+        /// Re-resolve every pin instead of keeping locked versions
+        #[arg(long)]
+        update: bool,
+    },
+    // clap turns the variant name into kebab-case: SelfUpdate → `self-update`.
+    /// Replace this eddy binary with the newest release
+    SelfUpdate {
+        /// Reinstall even if already on the newest version
+        #[arg(long)]
+        force: bool,
+        /// Restore the binary that the last self-update replaced
+        #[arg(long, conflicts_with = "force")]
+        rollback: bool,
+    },
+    /// Pack installed tool versions into one archive, e.g. `go@1.22.3 cmake@4.1.4`
+    Bundle {
+        // `required = true` on a Vec positional: at least one spec must be given.
+        #[arg(required = true, value_name = "TOOL@VERSION")]
+        tools: Vec<String>,
+        /// Output archive; the extension picks the compression (.tar.zst, .tar.gz)
+        #[arg(short, long)]
+        output: PathBuf,
+    },
+    /// Restore the toolchains in a bundle and link them into PATH
+    Unbundle { bundle: PathBuf },
+    /// Write a CMakeUserPresets.json preset (or a toolchain file) using the eddy-managed tools
+    CmakePreset {
+        /// Write a CMake toolchain file instead of a preset
+        #[arg(long)]
+        toolchain: bool,
+        /// Name of the configure and build presets
+        #[arg(long, default_value = "eddy")]
+        name: String,
+        /// Where to write (default: CMakeUserPresets.json or eddy-toolchain.cmake in the project root)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Print a bill of materials (CycloneDX or SPDX JSON) for the managed toolchains
+    Sbom {
+        // `value_enum` makes clap parse the value through SbomFormat's ValueEnum impl.
+        #[arg(long, value_enum, default_value = "cyclonedx")]
+        format: sbom::SbomFormat,
+        /// Only the tools pinned in eddy.toml, at their locked versions
+        #[arg(long)]
+        project: bool,
+        /// Write the document here instead of to stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Write a Dockerfile or devcontainer that installs the project's locked toolchains
+    Export {
+        #[command(subcommand)]
+        target: ExportTarget,
     },
+    /// Print a shell completion script, e.g. `source <(eddy completions bash)`
+    Completions {
+        // clap_complete::Shell implements clap's ValueEnum, so clap parses and
+        // lists the accepted values (bash, zsh, fish, powershell, elvish) itself.
+        shell: clap_complete::Shell,
+    },
+    // Called by the completion scripts, not by people — `hide` keeps it out of --help.
+    #[command(name = "__complete", hide = true)]
+    Complete {
+        #[command(subcommand)]
+        target: CompleteTarget,
+    },
+}
+
+#[derive(Subcommand)]
+enum CompleteTarget {
+    Tools,
+    Versions { tool: String },
+}
+
+#[derive(Subcommand)]
+enum ExportTarget {
+    /// A Dockerfile with one layer per pinned tool
+    Dockerfile {
+        /// Base image; the setup uses apt-get, so a Debian or Ubuntu one
+        #[arg(long, default_value = export::DEFAULT_BASE)]
+        base: String,
+        /// Write the Dockerfile here instead of to stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// A Dockerfile plus devcontainer.json for VS Code and other devcontainer clients
+    Devcontainer {
+        /// Base image; the setup uses apt-get, so a Debian or Ubuntu one
+        #[arg(long, default_value = export::DEFAULT_BASE)]
+        base: String,
+        /// Directory to write both files to (default: .devcontainer in the project root)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+}
+
+// Loads the project around the current directory, or explains how to create one.
+fn current_project(progress: &Arc<dyn Progress>) -> Result<Project> {
+    let cwd = std::env::current_dir()?;
+    let project = Project::find(&cwd)?.ok_or_else(|| {
+        anyhow::anyhow!("no eddy.toml found in {} or any parent directory", cwd.display())
+    })?;
+    Ok(project.with_progress(progress.clone()))
+}
+
+// Like `current_project`, but a missing eddy.toml is fine: the pins are read from
+// the files the repository already has. Explicit eddy.toml pins still take priority.
+fn detected_project(progress: &Arc<dyn Progress>) -> Result<Project> {
+    let cwd = std::env::current_dir()?;
+    let mut project = match Project::find(&cwd)? {
+        Some(project) => project,
+        None => Project::without_manifest(&cwd)?,
+    }
+    .with_progress(progress.clone());
+    let detected = detect::detect(&project.root)?;
+    for d in &detected {
+        progress.message(&format!("Detected {} {} from {}", d.tool, d.spec, d.source));
+    }
+    project.adopt(detected);
+    if project.manifest.tools.is_empty() {
+        anyhow::bail!("nothing to sync: no eddy.toml pins and no toolchain files detected");
+    }
+    Ok(project)
 }
 
 // #[tokio::main] is a proc-macro that wraps the async main function in a tokio
@@ -55,34 +260,270 @@ async fn main() -> Result<()> {
     // derived schema, and either returns a populated `Cli` or exits with a
     // help/error message. No manual argv parsing needed.
     let cli = Cli::parse();
+    // The TUI owns the screen, so there the log only goes to --log-file.
+    let verbose = if cli.command.is_some() { cli.verbose } else { 0 };
+    logging::init(verbose, cli.log_file.as_deref())?;
+    // Status lines — from the library calls below and from main itself — go
+    // through this one reporter; every blueprint and project built here gets it.
+    let progress: Arc<dyn Progress> = Arc::new(TerminalProgress::new());
+    if let Some(warning) = http::proxy_warning() {
+        progress.message(&warning);
+    }
+    let Some(command) = cli.command else {
+        // Piped or in CI there's nobody to interact with, so that keeps clap's
+        // "missing subcommand" usage error (and exit code 2).
+        if std::io::stdin().is_terminal() && std::io::stdout().is_terminal() {
+            return tui::run().await;
+        }
+        Cli::command()
+            .error(clap::error::ErrorKind::MissingSubcommand, "a subcommand is required outside a terminal")
+            .exit();
+    };
+    // Checked before `command` is moved by the match below.
+    // Completion runs on every <Tab>; it must never wait on the network.
+    let check_for_update = !matches!(
+        command,
+        Commands::SelfUpdate { .. } | Commands::Completions { .. } | Commands::Complete { .. }
+    );
 
-    // Pattern matching on the enum consumes `cli.command`, binding the fields.
+    // Pattern matching on the enum consumes `command`, binding the fields.
     // This is a destructuring assignment: `tool` and `version` are moved out
     // of the enum variant and become local variables.
-    match cli.command {
-        Commands::Install { tool, version } => {
-            // `.into()` calls `Version::from(&str)` via the blanket impl.
-            // The type annotation `Version` on the left drives which `Into` impl
-            // is selected — without it, the compiler can't resolve the ambiguity.
+    match command {
+        // `eddy install go-tool golangci-lint@v1.59.0`: the "version" is a tool spec,
+        // built with `go install` rather than downloaded.
+        Commands::Install { tool, version: Some(spec), from, url } if tool == "go-tool" => {
+            if from.is_some() || url.is_some() {
+                anyhow::bail!("go tools are built with `go install`; --from and --url don't apply");
+            }
+            GoTool::parse(&spec)?.with_progress(progress.clone()).install().await?;
+        }
+        Commands::Install { tool, version: Some(version), from, url } => {
+            // Asking for exactly the version the project pins installs it the way
+            // `eddy install <tool>` does, so the locked checksum is enforced.
+            let cwd = std::env::current_dir()?;
+            if from.is_none()
+                && url.is_none()
+                && let Some(project) = Project::find(&cwd)?
+                && project.pins_version(&tool, &version)
+            {
+                let mut project = project.with_progress(progress.clone());
+                project.install(&tool).await?;
+                project.save_lock()?;
+            } else {
+                // `.into()` calls `Version::from(&str)` via the blanket impl.
+                // The type annotation `Version` on the left drives which `Into` impl
+                // is selected — without it, the compiler can't resolve the ambiguity.
+                let ver: Version = version.as_str().into();
+                // clap already guarantees at most one of `from` / `url` is set.
+                let source = match (from, url) {
+                    (Some(path), _) => ArtifactSource::File(path),
+                    (None, Some(url)) => ArtifactSource::Url(url),
+                    (None, None) => ArtifactSource::Release,
+                };
+                source.check_version(&tool, &ver)?;
+                let info = languages::build(&tool, ver).await?;
+                // `mut` is required because `install()` takes `&mut self`.
+                let mut blueprint =
+                    ToolBlueprint::new(info).with_source(source).with_progress(progress.clone());
+                blueprint.install().await?;
+            }
+        }
+        // No version given: install what the project pins (and locks), so
+        // `eddy install go` in a checkout gives everyone the same build.
+        Commands::Install { tool, version: None, .. } => {
+            let mut project = current_project(&progress)?;
+            project.install(&tool).await?;
+            project.save_lock()?;
+        }
+        Commands::Use { tool, version, local: true, .. } => {
+            // A pin may be partial ("1.22") or "latest"; `eddy sync` resolves it.
+            // Only the tool name is checked here, so this works offline.
+            languages::ensure_known(&tool)?;
+            let cwd = std::env::current_dir()?;
+            let mut project = match Project::find(&cwd)? {
+                Some(project) => project,
+                None => Project::without_manifest(&cwd)?,
+            };
+            let previous = project.set_pin(&tool, &version);
+            project.save_manifest()?;
+            let path = project.manifest_path();
+            match previous {
+                Some(old) if old != version => progress.message(&format!(
+                    "Changed {tool} from \"{old}\" to \"{version}\" in {}",
+                    path.display()
+                )),
+                _ => progress.message(&format!("Pinned {tool} = \"{version}\" in {}", path.display())),
+            }
+            progress.message("Run `eddy sync` to install and link the pinned versions");
+        }
+        Commands::Use { tool, version, shell: true, .. } => {
             let ver: Version = version.as_str().into();
             let info = languages::build(&tool, ver).await?;
-            // `mut` is required because `install()` takes `&mut self`.
-            let mut blueprint = ToolBlueprint::new(info);
-            blueprint.install().await?;
+            let blueprint = ToolBlueprint::new(info).with_progress(progress.clone());
+            // "latest" has been resolved by now; export the concrete version.
+            let version = blueprint.info.version.as_str();
+            if !blueprint.is_installed() {
+                anyhow::bail!("{tool}@{version} is not installed; run `eddy install {tool} {version}` first");
+            }
+            // stdout carries only the exports, so `eval "$(...)"` stays clean.
+            let kind = current::ShellKind::detect();
+            print!("{}", current::shell_exports(kind, &tool, version, &blueprint.bin_dir()));
         }
-        Commands::Use { tool, version } => {
+        Commands::Use { tool, version, .. } => {
             let ver: Version = version.as_str().into();
             let info = languages::build(&tool, ver).await?;
             // No `mut` needed: use_tool() takes `&self` (immutable borrow).
-            let blueprint = ToolBlueprint::new(info);
+            let blueprint = ToolBlueprint::new(info).with_progress(progress.clone());
             blueprint.use_tool()?;
         }
-        Commands::Delete { tool, version } => {
+        Commands::Current { tool } => {
+            let project = Project::find(&std::env::current_dir()?)?;
+            // `.as_deref()` turns Option<String> into Option<&str>, and `.as_ref()`
+            // Option<Project> into Option<&Project>, without moving either.
+            let rows = current::rows(tool.as_deref(), project.as_ref()).await?;
+            if rows.is_empty() {
+                match tool {
+                    Some(tool) => progress.message(&format!("No version of {tool} is selected")),
+                    None => progress.message("No tool versions are selected; try `eddy use <tool> <version>`"),
+                }
+            }
+            for row in &rows {
+                println!("{row}");
+            }
+        }
+        Commands::History { tool } => {
+            let entries = history::entries(tool.as_deref()).await?;
+            if entries.is_empty() {
+                progress.message("No history yet");
+            }
+            for entry in &entries {
+                println!("{entry}");
+            }
+        }
+        Commands::Rollback { tool } => history::rollback(tool.as_deref(), progress.clone()).await?,
+        Commands::Delete { tool, version: Some(version), force, .. } => {
             let ver: Version = version.as_str().into();
             let info = languages::build(&tool, ver).await?;
-            let blueprint = ToolBlueprint::new(info);
-            blueprint.delete().await?;
+            let blueprint = ToolBlueprint::new(info).with_progress(progress.clone());
+            blueprint.delete(force).await?;
+        }
+        // clap guarantees --all when the version is missing.
+        Commands::Delete { tool, version: None, force, .. } => {
+            Eddy::new().with_progress(progress.clone()).delete_all(&tool, force).await?
+        }
+        Commands::Sync { detect: false } => current_project(&progress)?.sync().await?,
+        Commands::Sync { detect: true } => detected_project(&progress)?.sync().await?,
+        Commands::Lock { update } => current_project(&progress)?.update_lock(update).await?,
+        Commands::SelfUpdate { rollback: true, .. } => self_update::rollback(progress.as_ref())?,
+        Commands::SelfUpdate { force, .. } => self_update::self_update(force, progress.clone()).await?,
+        Commands::Bundle { tools, output } => bundle::bundle(&tools, &output, progress.clone()).await?,
+        Commands::Unbundle { bundle } => bundle::unbundle(&bundle, progress.clone()).await?,
+        Commands::CmakePreset { toolchain: as_file, name, output } => {
+            let cwd = std::env::current_dir()?;
+            let project = Project::find(&cwd)?;
+            let root = project.as_ref().map_or(cwd, |p| p.root.clone());
+            let toolchain = cmake_preset::resolve(project.as_ref()).await?;
+            if toolchain.is_empty() {
+                anyhow::bail!(
+                    "no eddy-managed cmake, ninja or compiler is active; `eddy use` them first"
+                );
+            }
+
+            let (path, text) = if as_file {
+                let path = output.unwrap_or_else(|| root.join(cmake_preset::TOOLCHAIN_FILE));
+                (path, cmake_preset::toolchain_file(&toolchain))
+            } else {
+                let path = output.unwrap_or_else(|| root.join(cmake_preset::PRESETS_FILE));
+                let existing = match std::fs::read_to_string(&path) {
+                    Ok(text) => serde_json::from_str(&text)
+                        .with_context(|| format!("failed to parse {}", path.display()))?,
+                    Err(e) if e.kind() == std::io::ErrorKind::NotFound => serde_json::Value::Null,
+                    Err(e) => return Err(e).with_context(|| format!("failed to read {}", path.display())),
+                };
+                let presets = cmake_preset::merge_presets(existing, &toolchain, &name)?;
+                (path, serde_json::to_string_pretty(&presets)? + "\n")
+            };
+            std::fs::write(&path, text).with_context(|| format!("failed to write {}", path.display()))?;
+            progress.message(&format!("Wrote {}", path.display()));
+            if as_file {
+                progress.message(&format!("Configure with: cmake -DCMAKE_TOOLCHAIN_FILE={} ...", path.display()));
+            } else {
+                progress.message(&format!("Configure with: cmake --preset {name}"));
+            }
+        }
+        Commands::Sbom { format, project, output } => {
+            let components = if project {
+                sbom::for_project(&current_project(&progress)?).await?
+            } else {
+                sbom::installed().await?
+            };
+            let document = sbom::render(format, &components, now_secs());
+            let text = serde_json::to_string_pretty(&document)? + "\n";
+            match output {
+                Some(path) => {
+                    std::fs::write(&path, text)
+                        .with_context(|| format!("failed to write {}", path.display()))?;
+                    progress.message(&format!("Wrote {} components to {}", components.len(), path.display()));
+                }
+                None => print!("{text}"),
+            }
+        }
+        Commands::Export { target: ExportTarget::Dockerfile { base, output } } => {
+            let pins = export::locked_pins(&current_project(&progress)?)?;
+            let text = export::dockerfile(&pins, &base);
+            match output {
+                Some(path) => {
+                    std::fs::write(&path, text)
+                        .with_context(|| format!("failed to write {}", path.display()))?;
+                    progress.message(&format!("Wrote {}", path.display()));
+                }
+                None => print!("{text}"),
+            }
         }
+        Commands::Export { target: ExportTarget::Devcontainer { base, output } } => {
+            let project = current_project(&progress)?;
+            let pins = export::locked_pins(&project)?;
+            let dir = output.unwrap_or_else(|| project.root.join(".devcontainer"));
+            // The project directory's name, as VS Code would show it anyway.
+            let name = project
+                .root
+                .file_name()
+                .map_or("eddy".to_string(), |n| n.to_string_lossy().into_owned());
+            let json = serde_json::to_string_pretty(&export::devcontainer(&pins, &name))? + "\n";
+            std::fs::create_dir_all(&dir)
+                .with_context(|| format!("failed to create {}", dir.display()))?;
+            for (file, text) in [("Dockerfile", export::dockerfile(&pins, &base)), ("devcontainer.json", json)] {
+                let path = dir.join(file);
+                std::fs::write(&path, text)
+                    .with_context(|| format!("failed to write {}", path.display()))?;
+                progress.message(&format!("Wrote {}", path.display()));
+            }
+        }
+        Commands::Completions { shell } => {
+            // CommandFactory::command() rebuilds the clap definition derived from `Cli`.
+            let mut stdout = std::io::stdout();
+            clap_complete::generate(shell, &mut Cli::command(), "eddy", &mut stdout);
+            if let Some(script) = completions::dynamic_script(shell) {
+                print!("{script}");
+            }
+        }
+        Commands::Complete { target: CompleteTarget::Tools } => {
+            for tool in completions::tools() {
+                println!("{tool}");
+            }
+        }
+        Commands::Complete { target: CompleteTarget::Versions { tool } } => {
+            for version in completions::installed_versions(&tool).await? {
+                println!("{version}");
+            }
+        }
+    }
+
+    // Only reached when the command succeeded: a failing command's error
+    // shouldn't be buried under an upgrade notice.
+    if check_for_update {
+        self_update::notify_if_outdated(progress.as_ref()).await;
     }
 
     // Explicit Ok(()) at the end: `main` returns `Result<()>`, and the last
@@ -0,0 +1,305 @@
+// External tool providers. A tool eddy has no builder for (say, an in-house
+// compiler whose versions live behind an internal artifact API) can be provided
+// by an `eddy-plugin-<tool>` executable anywhere on PATH — the same convention
+// as `git-<cmd>` or `cargo-<cmd>`.
+//
+// Protocol (version 1): eddy starts the plugin once per request, writes one JSON
+// object to its stdin, closes stdin, and reads one JSON object from its stdout.
+//
+//   → {"protocol":1,"request":"list-versions","tool":"acme"}
+//   ← {"versions":["2.1.0","2.0.3"]}                                  (newest first)
+//
+//   → {"protocol":1,"request":"resolve","tool":"acme","spec":"latest"}
+//   ← {"version":"2.1.0"}
+//
+//   → {"protocol":1,"request":"tool-info","tool":"acme","version":"2.1.0","platform":"linux-x86_64"}
+//   ← {"name":"acme","version":"2.1.0","pkg_name":"acme-2.1.0.tar.gz","url":"https://...",
+//      "custom_bin_path":"acme/bin","links":["acme"],"steps":["extract"],"sha256":null,
+//      "license":"MIT"}
+//
+// A plugin reports failure by exiting non-zero; whatever it printed to stderr
+// becomes the error message. The plugin's stderr is otherwise passed on as a
+// progress message.
+use std::ffi::OsStr;
+use std::path::{Component, Path, PathBuf};
+use std::process::Stdio;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+
+use crate::{
+    progress::{Progress, TerminalProgress},
+    shared::platform,
+    types::{InstallStep, ToolInfo, Version},
+};
+
+pub const PREFIX: &str = "eddy-plugin-";
+pub const PROTOCOL_VERSION: u32 = 1;
+// How long one request may take. Generous, since a plugin may query a slow
+// internal API, but a plugin that never answers must not hang `eddy install`.
+const TIMEOUT: Duration = Duration::from_secs(120);
+
+// `tag = "request"` makes serde write the variant name into a "request" field
+// next to the variant's own fields (an "internally tagged" enum) — the shape a
+// TS discriminated union would have. rename_all turns ListVersions → "list-versions".
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "request", rename_all = "kebab-case")]
+pub enum Request {
+    ListVersions { tool: String },
+    Resolve { tool: String, spec: String },
+    ToolInfo { tool: String, version: String, platform: String },
+}
+
+// The envelope actually written to stdin. `#[serde(flatten)]` inlines the
+// request's fields, so the JSON stays flat: {"protocol":1,"request":...,"tool":...}.
+#[derive(Serialize)]
+struct Envelope<'a> {
+    protocol: u32,
+    #[serde(flatten)]
+    request: &'a Request,
+}
+
+#[derive(Debug, Deserialize)]
+struct VersionsResponse {
+    versions: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ResolveResponse {
+    version: String,
+}
+
+// ToolInfo as a plugin sends it. ToolInfo itself can't be deserialized directly:
+// its names are `&'static str` (literals in the built-in builders), and JSON read
+// at runtime produces owned Strings. `into_tool_info` bridges the two.
+#[derive(Debug, Clone, Deserialize)]
+pub struct PluginToolInfo {
+    // Install directory is `<lang>/<name>/<version>`; plugins rarely care, so it
+    // defaults to "plugin" to keep their tools apart from the built-in ones.
+    #[serde(default = "default_lang")]
+    pub lang: String,
+    pub name: String,
+    pub version: String,
+    pub pkg_name: String,
+    pub url: String,
+    #[serde(default)]
+    pub custom_bin_path: Option<PathBuf>,
+    #[serde(default)]
+    pub links: Option<Vec<String>>,
+    #[serde(default)]
+    pub steps: Vec<InstallStep>,
+    #[serde(default)]
+    pub sha256: Option<String>,
+    // SPDX identifier, for `eddy sbom`.
+    #[serde(default)]
+    pub license: Option<String>,
+}
+
+fn default_lang() -> String {
+    "plugin".to_string()
+}
+
+// Box::leak hands back a `&'static str` by giving up ownership of the allocation
+// for the rest of the process. That's a deliberate (tiny) leak: eddy runs one
+// command and exits, and each plugin call leaks a few short strings at most.
+// Improvement: make ToolInfo's names `Cow<'static, str>` so both literals and
+// runtime strings fit without leaking.
+fn leak(s: String) -> &'static str {
+    Box::leak(s.into_boxed_str())
+}
+
+// Everything a plugin names ends up in a path under eddy_dir(): the install
+// directory is `<lang>/<name>/<version>`, the download is `pkg_name` inside it,
+// and each link is a file in the bin directory. A `..`, a separator or an
+// absolute path would let a plugin write, unpack or link outside those.
+fn check_component(field: &str, value: &str) -> Result<()> {
+    let mut components = Path::new(value).components();
+    let single = matches!(components.next(), Some(Component::Normal(_))) && components.next().is_none();
+    if !single || value.contains(['/', '\\']) {
+        anyhow::bail!("plugin returned an invalid {field} \"{value}\": it must be a plain file name");
+    }
+    Ok(())
+}
+
+// `custom_bin_path` may name a nested directory (`acme/bin`), but only one
+// inside the install directory.
+fn check_relative(field: &str, path: &Path) -> Result<()> {
+    let inside = path.components().all(|c| matches!(c, Component::Normal(_) | Component::CurDir));
+    if !inside || path.as_os_str().is_empty() {
+        anyhow::bail!(
+            "plugin returned an invalid {field} \"{}\": it must be a relative path without `..`",
+            path.display()
+        );
+    }
+    Ok(())
+}
+
+impl PluginToolInfo {
+    pub fn into_tool_info(self) -> Result<ToolInfo> {
+        // A plugin answering with "latest" would defeat the point of `resolve`;
+        // the version here must be concrete so the install directory is stable.
+        if self.version == "latest" {
+            anyhow::bail!("plugin returned \"latest\" for {}; tool-info needs a concrete version", self.name);
+        }
+        check_component("lang", &self.lang)?;
+        check_component("name", &self.name)?;
+        check_component("version", &self.version)?;
+        check_component("pkg_name", &self.pkg_name)?;
+        if let Some(custom) = &self.custom_bin_path {
+            check_relative("custom_bin_path", custom)?;
+        }
+        for link in self.links.iter().flatten() {
+            check_component("link", link)?;
+        }
+        Ok(ToolInfo {
+            lang: leak(self.lang),
+            name: leak(self.name),
+            version: Version::SemVer(self.version),
+            pkg_name: self.pkg_name,
+            url: self.url,
+            custom_bin_path: self.custom_bin_path,
+            links: self.links.map(|links| links.into_iter().map(leak).collect()),
+            steps: self.steps,
+            sha256: self.sha256,
+            license: self.license.map(leak),
+        })
+    }
+}
+
+pub struct Plugin {
+    pub tool: String,
+    pub path: PathBuf,
+    // Receives what the plugin prints to stderr on success.
+    progress: Arc<dyn Progress>,
+    timeout: Duration,
+}
+
+impl Plugin {
+    // Looks for `eddy-plugin-<tool>` on PATH.
+    pub fn find(tool: &str) -> Option<Self> {
+        let path = std::env::var_os("PATH")?;
+        Self::find_in(tool, &path)
+    }
+
+    // Same as `find`, but searches the given PATH-style list. Split out so
+    // tests don't have to modify the process environment.
+    pub fn find_in(tool: &str, path: &OsStr) -> Option<Self> {
+        let name = format!("{PREFIX}{tool}");
+        // On Windows a plugin may be an .exe or a .cmd/.bat script.
+        let candidates: Vec<String> = if cfg!(windows) {
+            ["exe", "cmd", "bat"].iter().map(|ext| format!("{name}.{ext}")).collect()
+        } else {
+            vec![name]
+        };
+        // split_paths knows the platform's separator (`:` on Unix, `;` on Windows).
+        std::env::split_paths(path)
+            .flat_map(|dir| candidates.iter().map(move |c| dir.join(c)))
+            .find(|p| p.is_file())
+            .map(|path| Self {
+                tool: tool.to_string(),
+                path,
+                progress: Arc::new(TerminalProgress::new()),
+                timeout: TIMEOUT,
+            })
+    }
+
+    pub fn with_progress(mut self, progress: Arc<dyn Progress>) -> Self {
+        self.progress = progress;
+        self
+    }
+
+    // How long to wait for each answer before killing the plugin.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub async fn list_versions(&self) -> Result<Vec<String>> {
+        let resp: VersionsResponse = self
+            .call(&Request::ListVersions { tool: self.tool.clone() })
+            .await?;
+        Ok(resp.versions)
+    }
+
+    pub async fn resolve(&self, spec: &str) -> Result<String> {
+        let resp: ResolveResponse = self
+            .call(&Request::Resolve { tool: self.tool.clone(), spec: spec.to_string() })
+            .await?;
+        Ok(resp.version)
+    }
+
+    pub async fn tool_info(&self, version: &str) -> Result<ToolInfo> {
+        let info: PluginToolInfo = self
+            .call(&Request::ToolInfo {
+                tool: self.tool.clone(),
+                version: version.to_string(),
+                platform: platform(),
+            })
+            .await?;
+        info.into_tool_info()
+    }
+
+    // What `languages::build` calls: resolve "latest" (or any non-exact version)
+    // through the plugin, then ask for the artifact.
+    pub async fn build(&self, version: Version) -> Result<ToolInfo> {
+        let version = self.resolve(version.as_str()).await?;
+        self.tool_info(&version).await
+    }
+
+    // `DeserializeOwned` is the bound for "deserializable without borrowing from
+    // the input" — needed because the stdout buffer is dropped inside this function.
+    async fn call<T: DeserializeOwned>(&self, request: &Request) -> Result<T> {
+        // tokio::process rather than std::process so a slow plugin (network
+        // lookups) doesn't block the executor thread.
+        use tokio::io::AsyncWriteExt;
+
+        let input = serde_json::to_vec(&Envelope { protocol: PROTOCOL_VERSION, request })?;
+        let mut child = tokio::process::Command::new(&self.path)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            // Dropping the child (on timeout, below) kills it rather than leaving
+            // it running in the background.
+            .kill_on_drop(true)
+            .spawn()
+            .with_context(|| format!("failed to run {}", self.path.display()))?;
+
+        // `take()` moves the stdin handle out of the Child; dropping it at the end
+        // of this block closes the pipe, which is how the plugin sees EOF.
+        {
+            let mut stdin = child.stdin.take().context("plugin stdin unavailable")?;
+            // A plugin that fails early may exit without reading its input, which
+            // makes this write fail with EPIPE. Its exit status and stderr (checked
+            // below) explain what went wrong far better than "broken pipe" would.
+            if let Err(e) = stdin.write_all(&input).await
+                && e.kind() != std::io::ErrorKind::BrokenPipe
+            {
+                return Err(e).context("failed to send the request to the plugin");
+            }
+        }
+
+        let output = tokio::time::timeout(self.timeout, child.wait_with_output())
+            .await
+            .map_err(|_| {
+                anyhow::anyhow!("{} did not answer within {:?}", self.path.display(), self.timeout)
+            })??;
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if !output.status.success() {
+            anyhow::bail!(
+                "{} failed ({}): {}",
+                self.path.display(),
+                output.status,
+                stderr.trim()
+            );
+        }
+        // Diagnostics from a successful plugin are still worth showing.
+        if !stderr.trim().is_empty() {
+            self.progress.message(stderr.trim_end());
+        }
+        serde_json::from_slice(&output.stdout).with_context(|| {
+            format!("{} sent an invalid response to {request:?}", self.path.display())
+        })
+    }
+}
@@ -0,0 +1,77 @@
+// Where eddy reports what it's doing. The CLI draws indicatif bars and prints
+// status lines to stderr; a program embedding eddy (see client.rs) usually wants
+// neither on its terminal, so every report goes through this trait instead.
+//
+// A trait with default (no-op) methods is the Rust take on an options object of
+// optional callbacks in TS: `{ onMessage?, onDownloadStarted?, ... }`. An
+// implementation overrides only the events it cares about.
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use indicatif::{ProgressBar, ProgressStyle};
+
+// `Send + Sync` are supertraits: every implementation must be shareable across
+// threads, which is what lets one `Arc<dyn Progress>` serve installs running in
+// several tokio tasks at once. Downloads are told apart by `name` (the archive's
+// file name), since more than one can be in flight.
+pub trait Progress: Send + Sync {
+    // A one-line status update ("Extracting ...", "Successfully installed ...").
+    fn message(&self, _text: &str) {}
+    // `total` is the Content-Length, when the server sent one.
+    fn download_started(&self, _name: &str, _total: Option<u64>) {}
+    fn download_advanced(&self, _name: &str, _bytes: u64) {}
+    fn download_finished(&self, _name: &str) {}
+}
+
+// Reports nothing.
+pub struct Silent;
+
+impl Progress for Silent {}
+
+// The CLI's reporter: stderr lines and one progress bar per download.
+#[derive(Default)]
+pub struct TerminalProgress {
+    // Mutex because the trait methods take `&self` but have to add and remove bars.
+    bars: Mutex<HashMap<String, ProgressBar>>,
+}
+
+impl TerminalProgress {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Progress for TerminalProgress {
+    fn message(&self, text: &str) {
+        eprintln!("{text}");
+    }
+
+    fn download_started(&self, name: &str, total: Option<u64>) {
+        // Without a Content-Length there's no percentage to show.
+        let Some(len) = total else {
+            return;
+        };
+        let bar = ProgressBar::new(len);
+        bar.set_style(
+            ProgressStyle::with_template("Downloading {msg}: [{bar:25}] {percent}%")
+                // .unwrap() is safe here because the template string is a compile-time
+                // constant — it can only fail if we typo'd the format string.
+                .unwrap()
+                .progress_chars("=> "),
+        );
+        bar.set_message(name.to_string());
+        self.bars.lock().unwrap().insert(name.to_string(), bar);
+    }
+
+    fn download_advanced(&self, name: &str, bytes: u64) {
+        if let Some(bar) = self.bars.lock().unwrap().get(name) {
+            bar.inc(bytes);
+        }
+    }
+
+    fn download_finished(&self, name: &str) {
+        if let Some(bar) = self.bars.lock().unwrap().remove(name) {
+            bar.finish_and_clear();
+        }
+    }
+}
@@ -0,0 +1,286 @@
+// A project is a directory with an `eddy.toml` manifest pinning the tools it needs:
+//
+//   [tools]
+//   go = "1.22"        # newest 1.22.x
+//   cmake = "4.1.4"    # exactly this version
+//   ninja = "latest"
+//
+// Next to it lives `eddy.lock` (see lockfile.rs), recording what those pins
+// resolved to, so every machine installs the same artifacts.
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    blueprint::ToolBlueprint,
+    detect::Detected,
+    languages,
+    lockfile::{Artifact, LOCK_FILE, Lockfile},
+    progress::{Progress, TerminalProgress},
+    shared::platform,
+    types::{ToolInfo, Version},
+};
+
+pub const MANIFEST_FILE: &str = "eddy.toml";
+
+// BTreeMap (not HashMap) keeps the keys sorted, so saving the manifest never
+// reorders lines the user wrote and iteration order is deterministic.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Manifest {
+    #[serde(default)]
+    pub tools: BTreeMap<String, String>,
+}
+
+impl Manifest {
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        toml::from_str(&text).with_context(|| format!("failed to parse {}", path.display()))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        std::fs::write(path, toml::to_string(self)?)
+            .with_context(|| format!("failed to write {}", path.display()))
+    }
+}
+
+// Does `version` satisfy the pin `spec`? A spec is "latest", an exact version,
+// or a dotted prefix: "1.22" matches "1.22" and "1.22.3", but not "1.220.0" —
+// the `.` in the format! below is what enforces the component boundary.
+pub fn spec_matches(spec: &str, version: &str) -> bool {
+    spec == "latest" || version == spec || version.starts_with(&format!("{spec}."))
+}
+
+pub struct Project {
+    pub root: PathBuf,
+    pub manifest: Manifest,
+    pub lock: Lockfile,
+    // Where installs and lock updates report, down to the blueprints they create.
+    progress: Arc<dyn Progress>,
+}
+
+impl Project {
+    // Walks up from `start` until it finds a directory containing `eddy.toml`,
+    // the same way cargo finds Cargo.toml from a subdirectory.
+    // Returns Ok(None) rather than an error: "not in a project" is a normal state.
+    pub fn find(start: &Path) -> Result<Option<Self>> {
+        // `ancestors()` yields start, start/.., start/../.., ... up to the root.
+        match start.ancestors().find(|dir| dir.join(MANIFEST_FILE).is_file()) {
+            Some(root) => Ok(Some(Self::load(root)?)),
+            None => Ok(None),
+        }
+    }
+
+    pub fn load(root: &Path) -> Result<Self> {
+        Ok(Self {
+            root: root.to_path_buf(),
+            manifest: Manifest::load(&root.join(MANIFEST_FILE))?,
+            lock: Lockfile::load(&root.join(LOCK_FILE))?,
+            progress: Arc::new(TerminalProgress::new()),
+        })
+    }
+
+    // A project rooted at `root` that has no eddy.toml (yet) — used by
+    // `sync --detect`, where pins come from go.mod & co. instead.
+    pub fn without_manifest(root: &Path) -> Result<Self> {
+        Ok(Self {
+            root: root.to_path_buf(),
+            manifest: Manifest::default(),
+            lock: Lockfile::load(&root.join(LOCK_FILE))?,
+            progress: Arc::new(TerminalProgress::new()),
+        })
+    }
+
+    pub fn with_progress(mut self, progress: Arc<dyn Progress>) -> Self {
+        self.progress = progress;
+        self
+    }
+
+    // A blueprint reporting to this project's progress.
+    fn blueprint(&self, info: ToolInfo) -> ToolBlueprint {
+        ToolBlueprint::new(info).with_progress(self.progress.clone())
+    }
+
+    // Adds detected pins for tools the manifest doesn't mention. An explicit
+    // eddy.toml entry always beats an inferred one.
+    pub fn adopt(&mut self, detected: Vec<Detected>) {
+        for d in detected {
+            // entry().or_insert() only inserts when the key is absent.
+            self.manifest.tools.entry(d.tool).or_insert(d.spec);
+        }
+    }
+
+    pub fn manifest_path(&self) -> PathBuf {
+        self.root.join(MANIFEST_FILE)
+    }
+
+    pub fn save_manifest(&self) -> Result<()> {
+        self.manifest.save(&self.manifest_path())
+    }
+
+    // Adds or replaces the pin for `tool` (in memory; call `save_manifest` to
+    // persist). Returns the spec it replaced, if any.
+    pub fn set_pin(&mut self, tool: &str, spec: &str) -> Option<String> {
+        self.manifest.tools.insert(tool.to_string(), spec.to_string())
+    }
+
+    pub fn lock_path(&self) -> PathBuf {
+        self.root.join(LOCK_FILE)
+    }
+
+    pub fn save_lock(&self) -> Result<()> {
+        self.lock.save(&self.lock_path())
+    }
+
+    pub fn pin(&self, tool: &str) -> Option<&str> {
+        // `.map(String::as_str)` turns Option<&String> into Option<&str>.
+        self.manifest.tools.get(tool).map(String::as_str)
+    }
+
+    // Whether `version` is exactly what this project installs for `tool`: the pin
+    // itself, or the locked version the pin resolves to.
+    pub fn pins_version(&self, tool: &str, version: &str) -> bool {
+        let Some(spec) = self.pin(tool) else {
+            return false;
+        };
+        spec == version
+            || self.lock.get(tool).is_some_and(|locked| locked.version == version && spec_matches(spec, version))
+    }
+
+    // The ToolInfo to install for `tool`. A lock entry wins as long as it still
+    // satisfies the manifest pin; its URL and checksum are carried over so the
+    // download is verified against what was locked. If the pin was edited so the
+    // lock no longer satisfies it, the pin is resolved afresh (like `cargo build`
+    // after editing Cargo.toml).
+    pub async fn resolve(&self, tool: &str) -> Result<ToolInfo> {
+        let spec = self
+            .pin(tool)
+            .with_context(|| format!("{tool} is not pinned in {MANIFEST_FILE}"))?;
+
+        // let-chains (`if let ... && ...`) are stable in edition 2024.
+        if let Some(locked) = self.lock.get(tool)
+            && spec_matches(spec, &locked.version)
+        {
+            let version = Version::SemVer(locked.version.clone());
+            let mut info = languages::build_with(tool, version, self.progress.clone()).await?;
+            if let Some(artifact) = self.lock.artifact(tool, &platform()) {
+                info.url = artifact.url.clone();
+                info.sha256 = Some(artifact.sha256.clone());
+            }
+            return Ok(info);
+        }
+
+        resolve_pin(tool, spec, self.progress.clone()).await
+    }
+
+    // Writes the artifact `info` was installed from into the lock (in memory;
+    // call `save_lock` to persist). Needs `info.sha256`, which
+    // `ToolBlueprint::install` fills in after downloading.
+    pub fn record(&mut self, tool: &str, info: &ToolInfo) -> Result<()> {
+        let sha256 = info
+            .sha256
+            .clone()
+            .with_context(|| format!("no checksum recorded for {tool}@{}", info.version))?;
+        self.lock.record(
+            tool,
+            info.version.as_str(),
+            Artifact { platform: platform(), url: info.url.clone(), sha256 },
+        );
+        Ok(())
+    }
+
+    // Installs one pinned tool at its locked (or freshly resolved) version and
+    // records it. Already-installed tools are left alone, and locked if they weren't.
+    pub async fn install(&mut self, tool: &str) -> Result<ToolBlueprint> {
+        let info = self.resolve(tool).await?;
+        let mut blueprint = self.blueprint(info);
+
+        if blueprint.is_installed() {
+            // Installed, but not through this project (or before it had a lock):
+            // only the checksum is missing, and re-extracting over a working
+            // install just to get it would be wasteful.
+            if self.lock.artifact(tool, &platform()).is_none() {
+                blueprint.info.sha256 = Some(blueprint.installed_sha256().await?);
+                self.record(tool, &blueprint.info)?;
+            }
+            self.progress.message(&format!("{tool}@{} is already installed", blueprint.info.version));
+            return Ok(blueprint);
+        }
+
+        blueprint.install().await?;
+        self.record(tool, &blueprint.info)?;
+        Ok(blueprint)
+    }
+
+    // Installs and activates every pinned tool, then writes the lock.
+    pub async fn sync(&mut self) -> Result<()> {
+        // Collect the names first: iterating `self.manifest.tools` directly would
+        // hold a shared borrow of `self` across the `&mut self` install call.
+        let tools: Vec<String> = self.manifest.tools.keys().cloned().collect();
+        for tool in &tools {
+            let blueprint = self.install(tool).await?;
+            blueprint.use_tool()?;
+        }
+        self.prune_lock();
+        self.save_lock()
+    }
+
+    // `eddy lock`: fills in missing lock entries for this platform. With
+    // `update`, every pin is re-resolved from scratch (ignoring the current lock),
+    // like `cargo update`. Artifacts are downloaded to compute their checksum, but
+    // nothing is installed (see `ToolBlueprint::release_sha256`).
+    pub async fn update_lock(&mut self, update: bool) -> Result<()> {
+        let pins: Vec<(String, String)> = self
+            .manifest
+            .tools
+            .iter()
+            .map(|(tool, spec)| (tool.clone(), spec.clone()))
+            .collect();
+
+        for (tool, spec) in &pins {
+            let info = if update {
+                resolve_pin(tool, spec, self.progress.clone()).await?
+            } else {
+                let info = self.resolve(tool).await?;
+                if info.sha256.is_some() {
+                    continue;
+                }
+                info
+            };
+
+            let mut blueprint = self.blueprint(info);
+            blueprint.info.sha256 = Some(blueprint.release_sha256().await?);
+            self.progress.message(&format!("Locked {tool}@{}", blueprint.info.version));
+            self.record(tool, &blueprint.info)?;
+        }
+
+        self.prune_lock();
+        self.save_lock()
+    }
+
+    fn prune_lock(&mut self) {
+        // Binding `manifest` first makes the split explicit: a shared borrow of
+        // one field while `self.lock` — a different field — is borrowed mutably.
+        let manifest = &self.manifest;
+        self.lock.retain_tools(|name| manifest.tools.contains_key(name));
+    }
+}
+
+// Resolves a manifest pin without looking at the lock.
+// Exact versions (three or more components) need no network round-trip; "latest"
+// is handed to the tool's own builder; partial pins pick the newest listed match.
+pub async fn resolve_pin(tool: &str, spec: &str, progress: Arc<dyn Progress>) -> Result<ToolInfo> {
+    if spec == "latest" || spec.split('.').count() >= 3 {
+        return languages::build_with(tool, Version::from(spec), progress).await;
+    }
+    let versions = languages::versions_with(tool, progress.clone()).await?;
+    // `versions` is newest-first, so the first match is the highest.
+    let version = versions
+        .into_iter()
+        .find(|v| spec_matches(spec, v))
+        .with_context(|| format!("no {tool} release matches \"{spec}\""))?;
+    languages::build_with(tool, Version::SemVer(version), progress).await
+}
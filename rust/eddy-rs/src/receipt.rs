@@ -0,0 +1,62 @@
+// An install receipt: a small TOML file eddy writes into every version directory
+// it installs, recording where the artifact came from and what it hashed to.
+//
+//   <eddy_dir>/go/go-language/1.22.3/.eddy-receipt.toml
+//
+//   name = "go-language"
+//   version = "1.22.3"
+//   url = "https://go.dev/dl/go1.22.3.linux-amd64.tar.gz"
+//   sha256 = "8920ea52..."
+//   license = "BSD-3-Clause"
+//   source = "release"
+//   installed_at = 1718000000
+//
+// The tool definitions say what an install *should* be; the receipt says what it
+// actually was — a mirror URL, a USB copy — which is what `eddy sbom` reports.
+// Receipts travel with the directory, so `eddy bundle` carries them along.
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+pub const RECEIPT_FILE: &str = ".eddy-receipt.toml";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Receipt {
+    // ToolInfo::name ("go-language"), not the CLI name ("go").
+    pub name: String,
+    pub version: String,
+    // Where the artifact was actually fetched from: the release URL, a --url
+    // mirror, or `file://` + the path given to --from.
+    pub url: String,
+    #[serde(default)]
+    pub sha256: Option<String>,
+    #[serde(default)]
+    pub license: Option<String>,
+    // "release", "url", "file", or "go-install" (languages/go_tool.rs).
+    pub source: String,
+    #[serde(default)]
+    pub installed_at: u64,
+}
+
+impl Receipt {
+    // Ok(None) for a directory without a receipt — installs made before eddy
+    // wrote them, or a version directory that isn't an install at all.
+    pub fn load(dir: &Path) -> Result<Option<Self>> {
+        let path = dir.join(RECEIPT_FILE);
+        if !path.is_file() {
+            return Ok(None);
+        }
+        let text = std::fs::read_to_string(&path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        let receipt =
+            toml::from_str(&text).with_context(|| format!("failed to parse {}", path.display()))?;
+        Ok(Some(receipt))
+    }
+
+    pub fn save(&self, dir: &Path) -> Result<()> {
+        let path = dir.join(RECEIPT_FILE);
+        std::fs::write(&path, toml::to_string(self)?)
+            .with_context(|| format!("failed to write {}", path.display()))
+    }
+}
@@ -0,0 +1,312 @@
+// `eddy sbom`: a software bill of materials for the toolchains eddy manages —
+// which compiler and build-tool versions a release was built with, where each
+// came from, its checksum and its license.
+//
+//   eddy sbom --format cyclonedx            every installed tool version
+//   eddy sbom --format spdx --project       just what eddy.toml pins (via eddy.lock)
+//
+// Facts come from the install receipts (receipt.rs) first: they record the URL
+// an artifact was really fetched from, and its digest. Installs older than
+// receipts fall back to the tool definition and the archive still on disk.
+//
+// Both formats are emitted as JSON: CycloneDX 1.5 and SPDX 2.3. serde_json's
+// `json!` macro builds the documents — writing a struct per nested object of
+// two foreign schemas would be a lot of code for no extra safety.
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde_json::{Value, json};
+use sha2::{Digest, Sha256};
+
+use crate::{
+    blueprint::ToolBlueprint,
+    consts::{eddy_bin_dir, eddy_dir},
+    fs::RealFs,
+    languages::{self, TOOLS},
+    linker::{Platform, link_target},
+    project::{Project, spec_matches},
+    receipt::Receipt,
+    self_update::CURRENT_VERSION,
+    shared::{platform, rfc3339, sha256_file, version_key},
+    types::Version,
+};
+
+// `clap::ValueEnum` lets clap parse `--format cyclonedx|spdx` straight into this
+// enum (variant names lowercased), and list the choices in --help.
+#[derive(Debug, Clone, Copy, PartialEq, clap::ValueEnum)]
+pub enum SbomFormat {
+    Cyclonedx,
+    Spdx,
+}
+
+// One tool version in the bill of materials.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Component {
+    pub tool: String,
+    pub version: String,
+    pub url: Option<String>,
+    pub sha256: Option<String>,
+    pub license: Option<String>,
+    // Linked into eddy_bin_dir(), i.e. what `go` or `cmake` on PATH runs.
+    pub active: bool,
+}
+
+// Every link target in eddy_bin_dir(), to tell which versions are active.
+fn active_targets() -> Vec<PathBuf> {
+    let platform = Platform::current();
+    let Ok(entries) = std::fs::read_dir(eddy_bin_dir()) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter_map(|e| link_target(&RealFs, platform, &e.path()))
+        .collect()
+}
+
+fn is_active(dir: &Path, targets: &[PathBuf]) -> bool {
+    targets.iter().any(|t| t.starts_with(dir))
+}
+
+// The directories exactly `depth` levels below `root`.
+fn dirs_at_depth(root: &Path, depth: usize) -> Vec<PathBuf> {
+    if depth == 0 {
+        return vec![root.to_path_buf()];
+    }
+    let Ok(entries) = std::fs::read_dir(root) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.is_dir())
+        .flat_map(|p| dirs_at_depth(&p, depth - 1))
+        .collect()
+}
+
+// A built-in tool's component: its receipt when there is one, otherwise the
+// tool definition plus the digest of the archive left from the download.
+fn builtin_component(tool: &str, blueprint: &ToolBlueprint, targets: &[PathBuf]) -> Result<Component> {
+    let dir = blueprint.version_path();
+    let component = match Receipt::load(&dir)? {
+        Some(receipt) => Component {
+            tool: tool.to_string(),
+            version: receipt.version,
+            url: Some(receipt.url),
+            sha256: receipt.sha256,
+            license: receipt.license,
+            active: false,
+        },
+        None => {
+            let archive = blueprint.archive_path();
+            Component {
+                tool: tool.to_string(),
+                version: blueprint.info.version.to_string(),
+                url: Some(blueprint.info.url.clone()),
+                sha256: if archive.is_file() { Some(sha256_file(&archive)?) } else { None },
+                license: blueprint.info.license.map(str::to_string),
+                active: false,
+            }
+        }
+    };
+    Ok(Component { active: is_active(&dir, targets), ..component })
+}
+
+// Every installed tool version under eddy_dir(): built-in tools, plugin tools
+// and go tools (the latter two are only known through their receipts).
+pub async fn installed() -> Result<Vec<Component>> {
+    let home = eddy_dir();
+    let targets = active_targets();
+    let mut components = Vec::new();
+    let mut seen: HashSet<PathBuf> = HashSet::new();
+
+    for tool in TOOLS {
+        for version in languages::installed_versions(tool).await? {
+            let info = languages::build(tool, Version::SemVer(version)).await?;
+            let blueprint = ToolBlueprint::new(info);
+            if !blueprint.is_installed() {
+                continue;
+            }
+            seen.insert(blueprint.version_path());
+            components.push(builtin_component(tool, &blueprint, &targets)?);
+        }
+    }
+
+    // `<lang>/<name>/<version>` for plugin tools, `go/tools/<name>/<version>`
+    // for go tools.
+    let dirs = dirs_at_depth(&home, 3).into_iter().chain(dirs_at_depth(&home.join("go/tools"), 2));
+    for dir in dirs {
+        if seen.contains(&dir) {
+            continue;
+        }
+        if let Some(receipt) = Receipt::load(&dir)? {
+            components.push(Component {
+                tool: receipt.name,
+                version: receipt.version,
+                url: Some(receipt.url),
+                sha256: receipt.sha256,
+                license: receipt.license,
+                active: is_active(&dir, &targets),
+            });
+        }
+    }
+
+    components.sort_by(|a, b| {
+        (a.tool.as_str(), version_key(&a.version)).cmp(&(b.tool.as_str(), version_key(&b.version)))
+    });
+    Ok(components)
+}
+
+// The tools `project` pins, at the versions its lock resolves them to for this
+// platform. A pin that isn't locked yet is reported at the newest installed
+// version that satisfies it; one that is neither is an error — a bill of
+// materials with a silent gap would be worse than none.
+pub async fn for_project(project: &Project) -> Result<Vec<Component>> {
+    let targets = active_targets();
+    let mut components = Vec::new();
+    for (tool, spec) in &project.manifest.tools {
+        let locked = project.lock.get(tool).filter(|l| spec_matches(spec, &l.version));
+        let version = match locked {
+            Some(locked) => locked.version.clone(),
+            None => languages::installed_versions(tool)
+                .await?
+                .into_iter()
+                .find(|v| spec_matches(spec, v))
+                .with_context(|| {
+                    format!("{tool} = \"{spec}\" is neither locked nor installed; run `eddy lock` first")
+                })?,
+        };
+
+        let info = languages::build(tool, Version::SemVer(version)).await?;
+        let blueprint = ToolBlueprint::new(info);
+        let mut component = builtin_component(tool, &blueprint, &targets)?;
+        // The lock is the project's record of what it builds with, so its URL and
+        // checksum win over whatever this machine happens to have installed.
+        if let Some(artifact) = project.lock.artifact(tool, &platform()) {
+            component.url = Some(artifact.url.clone());
+            component.sha256 = Some(artifact.sha256.clone());
+        }
+        components.push(component);
+    }
+    Ok(components)
+}
+
+pub fn render(format: SbomFormat, components: &[Component], now: u64) -> Value {
+    match format {
+        SbomFormat::Cyclonedx => cyclonedx(components, now),
+        SbomFormat::Spdx => spdx(components, now),
+    }
+}
+
+// https://cyclonedx.org/docs/1.5/json/
+fn cyclonedx(components: &[Component], now: u64) -> Value {
+    let items: Vec<Value> = components
+        .iter()
+        .map(|c| {
+            let mut item = json!({
+                "type": "application",
+                "bom-ref": format!("{}@{}", c.tool, c.version),
+                "name": c.tool,
+                "version": c.version,
+                "properties": [{ "name": "eddy:active", "value": c.active.to_string() }],
+            });
+            // Optional fields are left out rather than written as null.
+            if let Some(license) = &c.license {
+                item["licenses"] = json!([{ "license": { "id": license } }]);
+            }
+            if let Some(sha256) = &c.sha256 {
+                item["hashes"] = json!([{ "alg": "SHA-256", "content": sha256 }]);
+            }
+            if let Some(url) = &c.url {
+                item["externalReferences"] = json!([{ "type": "distribution", "url": url }]);
+            }
+            item
+        })
+        .collect();
+    json!({
+        "bomFormat": "CycloneDX",
+        "specVersion": "1.5",
+        "serialNumber": format!("urn:uuid:{}", document_id(components, now)),
+        "version": 1,
+        "metadata": {
+            "timestamp": rfc3339(now),
+            "tools": { "components": [{ "type": "application", "name": "eddy", "version": CURRENT_VERSION }] },
+        },
+        "components": items,
+    })
+}
+
+// https://spdx.github.io/spdx-spec/v2.3/
+fn spdx(components: &[Component], now: u64) -> Value {
+    let ids: Vec<String> = components
+        .iter()
+        .map(|c| spdx_id(&format!("{}-{}", c.tool, c.version)))
+        .collect();
+    let packages: Vec<Value> = components
+        .iter()
+        .zip(&ids)
+        .map(|(c, id)| {
+            let mut package = json!({
+                "name": c.tool,
+                "SPDXID": id,
+                "versionInfo": c.version,
+                // SPDX spells "unknown" as NOASSERTION.
+                "downloadLocation": c.url.as_deref().unwrap_or("NOASSERTION"),
+                "filesAnalyzed": false,
+                "licenseConcluded": "NOASSERTION",
+                "licenseDeclared": c.license.as_deref().unwrap_or("NOASSERTION"),
+            });
+            if let Some(sha256) = &c.sha256 {
+                package["checksums"] = json!([{ "algorithm": "SHA256", "checksumValue": sha256 }]);
+            }
+            package
+        })
+        .collect();
+    let relationships: Vec<Value> = ids
+        .iter()
+        .map(|id| {
+            json!({
+                "spdxElementId": "SPDXRef-DOCUMENT",
+                "relationshipType": "DESCRIBES",
+                "relatedSpdxElement": id,
+            })
+        })
+        .collect();
+    json!({
+        "spdxVersion": "SPDX-2.3",
+        "dataLicense": "CC0-1.0",
+        "SPDXID": "SPDXRef-DOCUMENT",
+        "name": "eddy-toolchains",
+        "documentNamespace": format!("https://spdx.org/spdxdocs/eddy-{}", document_id(components, now)),
+        "creationInfo": {
+            "created": rfc3339(now),
+            "creators": [format!("Tool: eddy-{CURRENT_VERSION}")],
+        },
+        "packages": packages,
+        "relationships": relationships,
+    })
+}
+
+// SPDX identifiers allow letters, digits, `.` and `-` only: "go-1.22.3" is fine,
+// a plugin named "acme_cc" becomes "SPDXRef-acme-cc-...".
+pub fn spdx_id(name: &str) -> String {
+    let clean: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '.' || c == '-' { c } else { '-' })
+        .collect();
+    format!("SPDXRef-{clean}")
+}
+
+// A UUID-shaped identifier derived from the document's content and time, so two
+// runs over the same tools a second apart still get distinct serial numbers
+// without pulling in a uuid crate.
+fn document_id(components: &[Component], now: u64) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(now.to_be_bytes());
+    for c in components {
+        hasher.update(format!("{}@{}:{:?}\n", c.tool, c.version, c.sha256));
+    }
+    let hex = format!("{:x}", hasher.finalize());
+    // Version nibble 5 (name-based) and the RFC 4122 variant, as uuid v5 would set.
+    format!("{}-{}-5{}-8{}-{}", &hex[0..8], &hex[8..12], &hex[13..16], &hex[17..20], &hex[20..32])
+}
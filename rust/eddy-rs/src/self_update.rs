@@ -0,0 +1,263 @@
+// `eddy self-update`: replaces the running eddy binary with the newest release,
+// plus the once-a-day "a new version is available" notice.
+//
+// eddy is released from the monorepo with tags like `eddy-v0.2.0`; other projects
+// in the same repository tag their own releases, so only `eddy-v*` tags count.
+// Each release attaches one raw binary per platform (`eddy-linux-x86_64`,
+// `eddy-windows-x86_64.exe`) and a `<binary>.sha256` file next to it.
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    consts::eddy_dir,
+    fs::{FileSystem, RealFs},
+    github::{GithubClient, Release},
+    http::RetryPolicy,
+    linker::Platform,
+    progress::Progress,
+    shared::{
+        chmod_755, download_file_with, ensure_tool_dir, now_secs, platform, remove_path,
+        verify_checksum, version_key,
+    },
+};
+
+pub const REPO: &str = "kurekszymon/365";
+pub const TAG_PREFIX: &str = "eddy-v";
+const CHECK_FILE: &str = "update-check.toml";
+// How often the notice may hit the GitHub API — the unauthenticated quota is 60
+// requests an hour, and it's shared with every `eddy install` of a cpp tool.
+const CHECK_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+// The notice runs after the user's command; it must never make eddy feel slow.
+const CHECK_TIMEOUT: Duration = Duration::from_secs(3);
+
+// `env!` is resolved at compile time from Cargo.toml, so this is always the
+// version of the binary that is running (the same string `eddy --version` prints).
+pub const CURRENT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+// "eddy-linux-x86_64", or "eddy-windows-x86_64.exe".
+pub fn asset_name() -> String {
+    Platform::current().exe_name(&format!("eddy-{}", platform()))
+}
+
+// The newest stable eddy release that ships `asset`, with its version.
+// Pure, so it can be tested against a JSON fixture.
+pub fn pick_update<'a>(releases: &'a [Release], asset: &str) -> Option<(String, &'a Release)> {
+    releases
+        .iter()
+        .filter(|r| r.is_stable())
+        .filter(|r| r.find_asset(|name| name == asset).is_some())
+        // `?` inside filter_map's closure: a tag without the prefix yields None.
+        .filter_map(|r| Some((r.tag_name.strip_prefix(TAG_PREFIX)?.to_string(), r)))
+        .max_by_key(|(version, _)| version_key(version))
+}
+
+pub fn is_newer(candidate: &str, current: &str) -> bool {
+    version_key(candidate) > version_key(current)
+}
+
+// A `.sha256` file is `sha256sum` output: "<hex>  <file name>". Only the digest matters.
+pub fn parse_checksum(text: &str) -> Option<String> {
+    let digest = text.split_whitespace().next()?;
+    (digest.len() == 64 && digest.chars().all(|c| c.is_ascii_hexdigit()))
+        .then(|| digest.to_ascii_lowercase())
+}
+
+// Where the previous binary is kept after an update, for `--rollback`.
+pub fn backup_path(exe: &Path) -> PathBuf {
+    let name = exe.file_name().unwrap_or_default().to_string_lossy();
+    exe.with_file_name(format!("{name}.old"))
+}
+
+// Moves `incoming` into place at `exe`, keeping whatever was at `exe` as the backup.
+//
+// On Unix a rename over a running binary is fine (the process keeps the old inode),
+// so `exe` is replaced by a single atomic rename and is never missing, even if eddy
+// is killed halfway through:
+//   copy exe → parked, incoming → exe, parked → backup
+// The copy goes to `parked` rather than straight to the backup because for a
+// rollback `incoming` *is* the backup, and it has to be moved in before the
+// current binary can take its place.
+//
+// Rollback is the same swap with the backup as `incoming`: the two binaries trade
+// places, so running `--rollback` twice returns to the updated version.
+#[cfg(unix)]
+pub fn swap_in(fs: &impl FileSystem, exe: &Path, incoming: &Path) -> Result<()> {
+    let parked = parked_path(exe);
+    fs.copy(exe, &parked)
+        .with_context(|| format!("failed to copy {} aside", exe.display()))?;
+    if let Err(e) = fs.rename(incoming, exe) {
+        let _ = fs.remove_file(&parked);
+        return Err(e).with_context(|| format!("failed to replace {}", exe.display()));
+    }
+    fs.rename(&parked, &backup_path(exe))
+        .with_context(|| format!("failed to keep a backup of {}", exe.display()))?;
+    Ok(())
+}
+
+// A running binary can't be overwritten on Windows, but it can be renamed, so the
+// current exe is moved aside first. Three renames, each atomic on its own:
+//   exe → parked, incoming → exe, parked → backup
+// If the middle rename fails, the parked binary is moved back.
+#[cfg(windows)]
+pub fn swap_in(fs: &impl FileSystem, exe: &Path, incoming: &Path) -> Result<()> {
+    let parked = parked_path(exe);
+    fs.rename(exe, &parked)
+        .with_context(|| format!("failed to move {} aside", exe.display()))?;
+    if let Err(e) = fs.rename(incoming, exe) {
+        // Best effort: if even this fails, the error below still names the path.
+        let _ = fs.rename(&parked, exe);
+        return Err(e).with_context(|| format!("failed to replace {}", exe.display()));
+    }
+    fs.rename(&parked, &backup_path(exe))
+        .with_context(|| format!("failed to keep a backup of {}", exe.display()))?;
+    Ok(())
+}
+
+fn parked_path(exe: &Path) -> PathBuf {
+    let name = exe.file_name().unwrap_or_default().to_string_lossy();
+    exe.with_file_name(format!(".{name}.parked"))
+}
+
+// `eddy self-update`. With `force`, reinstalls even when already up to date
+// (useful to repair a damaged binary).
+pub async fn self_update(force: bool, progress: Arc<dyn Progress>) -> Result<()> {
+    let asset = asset_name();
+    let releases = GithubClient::new().with_progress(progress.clone()).list_releases(REPO).await?;
+    let (version, release) = pick_update(&releases, &asset)
+        .with_context(|| format!("no eddy release has a binary for {}", platform()))?;
+
+    if !force && !is_newer(&version, CURRENT_VERSION) {
+        progress.message(&format!("eddy {CURRENT_VERSION} is already the latest version"));
+        return Ok(());
+    }
+
+    // Refuse unverifiable binaries outright: a tool that installs toolchains
+    // must not replace itself with something it can't check.
+    let checksum_name = format!("{asset}.sha256");
+    let checksum_asset = release
+        .find_asset(|name| name == checksum_name)
+        .with_context(|| format!("eddy {version} has no {checksum_name}; refusing to update"))?;
+
+    let dir = ensure_tool_dir(&format!("self-update/{version}"));
+    let checksum_path = dir.join(&checksum_name);
+    download_file_with(&checksum_path, &checksum_asset.browser_download_url, progress.as_ref()).await?;
+    let expected = parse_checksum(&std::fs::read_to_string(&checksum_path)?)
+        .with_context(|| format!("{checksum_name} does not contain a sha256 digest"))?;
+
+    // pick_update only returns releases that have the binary, so this can't miss.
+    let binary = release.find_asset(|name| name == asset).context("binary asset missing")?;
+    let downloaded = dir.join(&asset);
+    download_file_with(&downloaded, &binary.browser_download_url, progress.as_ref()).await?;
+    verify_checksum(&downloaded, Some(&expected), &format!("eddy@{version}"))?;
+
+    // Stage the new binary next to the current one: rename only works within a
+    // filesystem, and eddy_dir() may live on a different one than the binary.
+    let exe = std::env::current_exe().context("cannot locate the running eddy binary")?;
+    let exe_name = exe.file_name().unwrap_or_default().to_string_lossy().to_string();
+    let staged = exe.with_file_name(format!(".{exe_name}.new"));
+    std::fs::copy(&downloaded, &staged)
+        .with_context(|| format!("failed to stage {}", staged.display()))?;
+    let staged_name = staged.file_name().unwrap_or_default().to_string_lossy().to_string();
+    chmod_755(exe.parent().unwrap_or(Path::new(".")), &staged_name)?;
+
+    if let Err(e) = swap_in(&RealFs, &exe, &staged) {
+        let _ = remove_path(&staged);
+        return Err(e);
+    }
+    remove_path(&dir)?;
+    progress.message(&format!(
+        "Updated eddy {CURRENT_VERSION} → {version} (run `eddy self-update --rollback` to undo)"
+    ));
+    Ok(())
+}
+
+// `eddy self-update --rollback`: swaps the backup kept by the last update back in.
+pub fn rollback(progress: &dyn Progress) -> Result<()> {
+    let exe = std::env::current_exe().context("cannot locate the running eddy binary")?;
+    let backup = backup_path(&exe);
+    if !backup.exists() {
+        anyhow::bail!("no previous eddy binary to roll back to ({} is missing)", backup.display());
+    }
+    swap_in(&RealFs, &exe, &backup)?;
+    progress.message(&format!("Rolled back eddy {CURRENT_VERSION} to the previous binary"));
+    Ok(())
+}
+
+// What the last background check found. Stored even when the check failed
+// (with an empty `latest`), so an offline machine isn't re-checked on every run.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct UpdateCheck {
+    pub checked_at: u64,
+    #[serde(default)]
+    pub latest: String,
+}
+
+impl UpdateCheck {
+    pub fn is_due(&self, now: u64) -> bool {
+        now.saturating_sub(self.checked_at) >= CHECK_INTERVAL.as_secs()
+    }
+
+    // The message to show, if the recorded release is newer than `current`.
+    pub fn notice(&self, current: &str) -> Option<String> {
+        (!self.latest.is_empty() && is_newer(&self.latest, current)).then(|| {
+            format!(
+                "eddy {} is available (you have {current}); run `eddy self-update` to upgrade",
+                self.latest
+            )
+        })
+    }
+}
+
+fn check_path() -> PathBuf {
+    eddy_dir().join(CHECK_FILE)
+}
+
+// Runs after every command (except self-update itself). At most once per
+// CHECK_INTERVAL it asks GitHub for the latest release and prints a notice when
+// one is newer — so the notice appears at most once a day, too.
+// Every failure is swallowed: a version check must never break the command
+// the user actually ran. EDDY_NO_UPDATE_CHECK=1 turns it off (for CI).
+pub async fn notify_if_outdated(progress: &dyn Progress) {
+    if std::env::var_os("EDDY_NO_UPDATE_CHECK").is_some() {
+        return;
+    }
+    let path = check_path();
+    // `.ok().and_then(...)`: a missing or corrupt file just means "never checked".
+    let previous: UpdateCheck = std::fs::read_to_string(&path)
+        .ok()
+        .and_then(|text| toml::from_str(&text).ok())
+        .unwrap_or_default();
+    let now = now_secs();
+    if !previous.is_due(now) {
+        return;
+    }
+
+    // tokio::time::timeout wraps the future: Err(Elapsed) if it takes too long.
+    let latest = tokio::time::timeout(CHECK_TIMEOUT, latest_version())
+        .await
+        .ok()
+        .and_then(Result::ok)
+        .unwrap_or_default();
+    let check = UpdateCheck { checked_at: now, latest };
+    if let Ok(text) = toml::to_string(&check) {
+        let _ = std::fs::create_dir_all(eddy_dir());
+        let _ = std::fs::write(&path, text);
+    }
+    if let Some(notice) = check.notice(CURRENT_VERSION) {
+        progress.message(&notice);
+    }
+}
+
+async fn latest_version() -> Result<String> {
+    // One quiet attempt: retry chatter would land in the middle of the output of
+    // whatever command the user actually ran.
+    let client = GithubClient::new().with_retry(RetryPolicy::none());
+    let releases = client.list_releases(REPO).await?;
+    pick_update(&releases, &asset_name())
+        .map(|(version, _)| version)
+        .context("no eddy release for this platform")
+}
@@ -5,15 +5,22 @@
 use std::path::{Path, PathBuf};
 
 use anyhow::{Context, Result};
-use indicatif::{ProgressBar, ProgressStyle};
 use regex::Regex;
-use reqwest::Client;
 
-use crate::consts::{eddy_bin_dir, eddy_dir};
+use crate::{
+    consts::{eddy_bin_dir, eddy_dir},
+    fs::RealFs,
+    http::{self, RetryPolicy},
+    linker::{Platform, link_bin},
+    progress::{Progress, TerminalProgress},
+};
 
 // Takes &str (borrowed slice) not String (owned) — the function only needs to
 // read the value, not own it. Callers can pass &str, String, or &String
 // interchangeably via deref coercion. This is the idiomatic Rust input convention.
+// `#[tracing::instrument]` on the filesystem helpers below logs each call with
+// its arguments at -vv (see logging.rs), and `err` the error it returns.
+#[tracing::instrument(level = "debug")]
 pub fn ensure_tool_dir(sub: &str) -> PathBuf {
     let dir = eddy_dir().join(sub);
     if !dir.exists() {
@@ -35,53 +42,78 @@ pub fn ensure_tool_dir_check(sub: &str) -> PathBuf {
 // `async fn` returns an `impl Future<Output = Result<()>>`. Nothing happens until
 // the caller `.await`s it — Rust futures are lazy (unlike JS Promises which start
 // executing immediately on creation).
+//
+// The body is streamed into `<file>.part` and only renamed to `file_path` once it
+// is complete. Each attempt recreates the .part file from scratch, so a retry
+// after a connection reset never appends to (or leaves behind) a truncated file.
 pub async fn download_file(file_path: &Path, url: &str) -> Result<()> {
-    // Builder pattern: configure the client, then `.build()` to finalize.
-    // Rust has no named/optional function parameters, so builders are the standard
-    // way to express "many optional configuration fields".
-    // The `?` at the end of `.build()?` is the error propagation operator:
-    // if build() returns Err(e), the function returns Err(e) immediately.
-    // It replaces every `try { ... } catch` in TS.
-    let client = Client::builder()
-        .redirect(reqwest::redirect::Policy::limited(10))
-        .use_rustls_tls()
-        .build()?;
+    download_file_with(file_path, url, &TerminalProgress::new()).await
+}
 
+// `download_file`, reporting to `progress` instead of the terminal.
+// `&dyn Progress` is a trait object: any implementation, chosen at runtime.
+// It isn't Debug, so the span skips it.
+#[tracing::instrument(skip(progress), err)]
+pub async fn download_file_with(file_path: &Path, url: &str, progress: &dyn Progress) -> Result<()> {
+    let part = part_path(file_path);
+    let policy = RetryPolicy::default();
+    let mut attempt = 1;
+    loop {
+        match download_attempt(&part, url, progress).await {
+            Ok(()) => break,
+            // Match guards (`if ...`) pick the arm only when the condition holds;
+            // otherwise matching falls through to the next arm.
+            Err(e) if attempt < policy.max_attempts && http::is_transient(&e) => {
+                tracing::warn!(attempt, error = %e, "download failed; retrying");
+                progress.message(&format!("Download of {url} failed ({e}); retrying"));
+                tokio::time::sleep(policy.delay(attempt)).await;
+                attempt += 1;
+            }
+            Err(e) => {
+                let _ = std::fs::remove_file(&part);
+                return Err(e.context(format!("failed to download {url}")));
+            }
+        }
+    }
+    // rename is atomic within a directory: readers see no file or the whole file.
+    tracing::debug!(from = %part.display(), to = %file_path.display(), "rename");
+    tokio::fs::rename(&part, file_path).await?;
+    Ok(())
+}
+
+// "go1.22.3.tar.gz" → "go1.22.3.tar.gz.part", next to the destination.
+fn part_path(file_path: &Path) -> PathBuf {
+    let mut name = file_path.file_name().unwrap_or_default().to_os_string();
+    name.push(".part");
+    file_path.with_file_name(name)
+}
+
+async fn download_attempt(file_path: &Path, url: &str, progress: &dyn Progress) -> Result<()> {
+    // The shared client carries the timeouts, proxy and User-Agent (see http.rs).
     // Method chaining with `?` on each step. Each `?` is a potential early return.
     // `error_for_status()` converts a 4xx/5xx response into an Err.
-    let resp = client.get(url).send().await?.error_for_status()?;
+    let resp = http::client().get(url).send().await?;
+    // `resp.url()` is where the redirects (logged by the client) ended up.
+    tracing::debug!(
+        status = %resp.status(),
+        url = %resp.url(),
+        content_length = resp.content_length(),
+        "download response"
+    );
+    let resp = resp.error_for_status()?;
 
+    // OsStr::to_string_lossy() handles non-UTF-8 filenames gracefully by
+    // replacing invalid bytes with U+FFFD. On macOS/Linux, filenames are
+    // arbitrary bytes, not guaranteed UTF-8. The .to_string() call converts
+    // the Cow<str> result to an owned String.
+    let name = file_path
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .trim_end_matches(".part")
+        .to_string();
     // Option<u64>: the server may or may not send Content-Length.
-    let total = resp.content_length();
-
-    // `if let Some(len)` unpacks Option — only executes if the value is Some.
-    // The whole expression evaluates to Option<ProgressBar>.
-    let pb = if let Some(len) = total {
-        let bar = ProgressBar::new(len);
-        bar.set_style(
-            ProgressStyle::with_template(
-                "Downloading {msg}: [{bar:25}] {percent}%",
-            )
-            // .unwrap() is safe here because the template string is a compile-time
-            // constant — it can only fail if we typo'd the format string, which
-            // we'd catch in tests. Alternative: use a const validated at startup.
-            .unwrap()
-            .progress_chars("=> "),
-        );
-        // OsStr::to_string_lossy() handles non-UTF-8 filenames gracefully by
-        // replacing invalid bytes with U+FFFD. On macOS/Linux, filenames are
-        // arbitrary bytes, not guaranteed UTF-8. The .to_string() call converts
-        // the Cow<str> result to an owned String.
-        let name = file_path
-            .file_name()
-            .unwrap_or_default()
-            .to_string_lossy()
-            .to_string();
-        bar.set_message(name);
-        Some(bar)
-    } else {
-        None
-    };
+    progress.download_started(&name, resp.content_length());
 
     // tokio::fs is the async version of std::fs. `.await?` = async equivalent of
     // blocking I/O + error propagation.
@@ -96,50 +128,50 @@ pub async fn download_file(file_path: &Path, url: &str) -> Result<()> {
     use futures_util::StreamExt;   // brings `.next()` into scope for the stream
     use tokio::io::AsyncWriteExt;  // brings `.write_all()` into scope for the file
 
-    // `while let Some(chunk)` drives the stream: keep consuming until it's exhausted.
-    // Each iteration is a `.await` point — the executor can run other tasks while
-    // waiting for the next chunk to arrive from the network.
-    while let Some(chunk) = stream.next().await {
-        // Inner `?`: the chunk itself might be a network error.
-        let chunk = chunk?;
-        // `if let Some(ref bar)`: `ref` borrows from inside the Option instead of
-        // moving out of it. Without `ref`, the match would consume `pb`, but we
-        // need it again at `bar.finish_and_clear()` below.
-        if let Some(ref bar) = pb {
+    // An async block runs the copy loop as one unit, so download_finished is
+    // called on every path out of it — including a chunk that fails half-way.
+    let copied: Result<()> = async {
+        // `while let Some(chunk)` drives the stream: keep consuming until it's exhausted.
+        // Each iteration is a `.await` point — the executor can run other tasks while
+        // waiting for the next chunk to arrive from the network.
+        while let Some(chunk) = stream.next().await {
+            // Inner `?`: the chunk itself might be a network error.
+            let chunk = chunk?;
             // `as u64` is an explicit numeric cast. Unlike TS, Rust never coerces
-            // numeric types implicitly. chunk.len() returns usize; bar.inc() wants u64.
-            bar.inc(chunk.len() as u64);
+            // numeric types implicitly. chunk.len() returns usize.
+            progress.download_advanced(&name, chunk.len() as u64);
+            dest.write_all(&chunk).await?;
         }
-        dest.write_all(&chunk).await?;
-    }
-
-    if let Some(bar) = pb {
-        // Here `pb` is consumed (moved into `bar`) — fine because we no longer need it.
-        bar.finish_and_clear();
+        Ok(())
     }
-    println!();
-    Ok(())
+    .await;
+    progress.download_finished(&name);
+    copied
 }
 
+#[tracing::instrument(level = "debug", err)]
 pub fn extract(archive_path: &Path, out_dir: &Path) -> Result<()> {
     if !out_dir.exists() {
         // `?` on a std::io::Error converts it into anyhow::Error automatically
         // because anyhow implements `From<std::io::Error>`.
         std::fs::create_dir_all(out_dir)?;
     }
-    eprintln!("Extracting {} to {}...", archive_path.display(), out_dir.display());
     // std::process::Command is the synchronous shell-out API.
     // For this project we shell out to system `tar` (same as the TS version).
     // Alternative: pure-Rust extraction with the `tar` + `flate2` + `zip` crates —
     // more portable (no system tar dependency) but ~200 extra lines for the same behavior.
-    let status = std::process::Command::new("tar")
-        // to_string_lossy(): Path → OsStr → Cow<str>; tar only accepts str args.
-        .args(["-xf", &archive_path.to_string_lossy(), "-C", &out_dir.to_string_lossy()])
+    let mut tar = std::process::Command::new("tar");
+    // to_string_lossy(): Path → OsStr → Cow<str>; tar only accepts str args.
+    tar.args(["-xf", &archive_path.to_string_lossy(), "-C", &out_dir.to_string_lossy()]);
+    // Command's Debug output is the quoted command line: "tar" "-xf" "..." "-C" "...".
+    tracing::debug!(command = ?tar, "running");
+    let status = tar
         .status()
         // .context("...") attaches a human-readable message to any error that
         // propagates through `?`. Lazy alternative: .with_context(|| format!("..."))
         // avoids allocating the message string if no error occurs.
         .context("failed to run tar")?;
+    tracing::debug!(%status, "tar finished");
     if !status.success() {
         // anyhow::bail! is a macro that returns Err(anyhow!("...")) immediately.
         // Equivalent to: return Err(anyhow::anyhow!("tar exited with status {}", status));
@@ -148,38 +180,16 @@ pub fn extract(archive_path: &Path, out_dir: &Path) -> Result<()> {
     Ok(())
 }
 
+// Puts `dir/<filename>` on PATH by linking it into `eddy_bin_dir()`: a symlink on
+// Unix, a `.cmd` shim on Windows (see linker.rs). `filename` is the bare tool
+// name — the `.exe` suffix is added by the linker where the platform needs it.
+#[tracing::instrument(level = "debug", err)]
 pub fn symlink_bin(dir: &Path, filename: &str) -> Result<()> {
-    let bin_dir = eddy_bin_dir();
-    if !bin_dir.exists() {
-        std::fs::create_dir_all(&bin_dir)?;
-    }
-    let target = bin_dir.join(filename);
-    // `is_symlink()` returns true even for broken symlinks, while `exists()` returns
-    // false for broken symlinks. We need both checks to handle the "already symlinked
-    // but the target was deleted" case without leaving a dangling link in place.
-    if target.exists() || target.is_symlink() {
-        std::fs::remove_file(&target)?;
-    }
-    let src = dir.join(filename);
-
-    // #[cfg(unix)] is a compile-time conditional — the Windows block is not even
-    // compiled on Unix, not just dead code. Compare to `process.platform` in TS,
-    // which is a runtime check; the wrong branch still compiles and ships.
-    // `cfg!(target_os = "macos")` (with !) would be a runtime bool expression.
-    // `#[cfg(target_os = "macos")]` (without !) is a compile-time gate.
-    #[cfg(unix)]
-    std::os::unix::fs::symlink(&src, &target)
-        // .with_context(|| ...) uses a closure so the format string is only
-        // evaluated if an error actually occurred — avoids the allocation on success.
-        .with_context(|| format!("symlink {} -> {}", src.display(), target.display()))?;
-
-    #[cfg(windows)]
-    std::os::windows::fs::symlink_file(&src, &target)
-        .with_context(|| format!("symlink {} -> {}", src.display(), target.display()))?;
-
+    link_bin(&RealFs, Platform::current(), dir, &eddy_bin_dir(), filename)?;
     Ok(())
 }
 
+#[tracing::instrument(level = "debug", err)]
 pub fn chmod_755(dir: &Path, filename: &str) -> Result<()> {
     let bin = dir.join(filename);
     // The outer #[cfg(unix)] gates the entire block — on Windows this compiles to
@@ -197,10 +207,11 @@ pub fn chmod_755(dir: &Path, filename: &str) -> Result<()> {
     Ok(())
 }
 
-pub fn rename_dir(pathname: &Path, old_name: &str, new_name: &str) -> Result<()> {
+#[tracing::instrument(level = "debug", skip(progress), err)]
+pub fn rename_dir(pathname: &Path, old_name: &str, new_name: &str, progress: &dyn Progress) -> Result<()> {
     let new_path = pathname.join(new_name);
     if new_path.exists() {
-        eprintln!("{} already exists; skipping rename", new_path.display());
+        progress.message(&format!("{} already exists; skipping rename", new_path.display()));
         // Rust has no `try/catch`. Early returns are explicit with `return Ok(())`.
         // The `?` operator is the implicit version of `return Err(...)`.
         return Ok(());
@@ -210,6 +221,38 @@ pub fn rename_dir(pathname: &Path, old_name: &str, new_name: &str) -> Result<()>
     Ok(())
 }
 
+// Seconds since the Unix epoch; 0 if the clock is before 1970.
+pub fn now_secs() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+// Seconds since the epoch → "2024-06-10T06:13:20Z". Days → civil date is Howard
+// Hinnant's `civil_from_days`; std has no calendar support and chrono would be a
+// dependency just for printing timestamps (sbom.rs, history.rs).
+pub fn rfc3339(secs: u64) -> String {
+    let days = (secs / 86_400) as i64;
+    let rem = secs % 86_400;
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+        rem / 3_600,
+        rem % 3_600 / 60,
+        rem % 60
+    )
+}
+
+#[tracing::instrument(level = "debug", err)]
 pub fn remove_path(path: &Path) -> Result<()> {
     if !path.exists() {
         return Ok(());
@@ -222,15 +265,12 @@ pub fn remove_path(path: &Path) -> Result<()> {
     Ok(())
 }
 
-pub async fn resolve_latest_version(url: &str) -> Result<String> {
-    // A new client with no redirect following — we want to inspect the 302 Location
+#[tracing::instrument(skip(progress), err)]
+pub async fn resolve_latest_version(url: &str, progress: &dyn Progress) -> Result<String> {
+    // The client that doesn't follow redirects — we want to inspect the 302 Location
     // header directly rather than let reqwest follow it automatically.
-    let client = Client::builder()
-        .redirect(reqwest::redirect::Policy::none())
-        .use_rustls_tls()
-        .build()?;
-
-    let resp = client.head(url).send().await?;
+    let resp = http::send(http::no_redirect_client().head(url), RetryPolicy::default(), progress).await?;
+    tracing::debug!(status = %resp.status(), location = ?resp.headers().get("location"), "releases/latest");
     let location = resp
         .headers()
         .get("location")
@@ -248,7 +288,9 @@ pub async fn resolve_latest_version(url: &str) -> Result<String> {
     // For a hot path that's called many times, prefer the static version to avoid
     // re-compiling the regex on every invocation.
     let re = Regex::new(r"(\d+\.\d+\.\d+)")?;
-    let caps = re.captures(location).context("no semver in redirect URL")?;
+    let caps = re
+        .captures(location)
+        .with_context(|| format!("no semver in redirect URL {location}"))?;
     // caps[1] is the first capture group. Indexing a Captures panics on out-of-bounds
     // but we know group 1 exists because the regex has exactly one group.
     Ok(caps[1].to_string())
@@ -285,3 +327,57 @@ pub fn base_pkg_name(pkg_name: &str) -> &str {
         .trim_end_matches(".zip")
         .trim_end_matches(".tgz")
 }
+
+// Hex-encoded SHA-256 of a file, streamed so multi-hundred-MB toolchain archives
+// never have to fit in memory.
+#[tracing::instrument(level = "debug", err)]
+pub fn sha256_file(path: &Path) -> Result<String> {
+    // `Digest` is the trait that provides `finalize()`; sha2's hashers also implement
+    // std::io::Write, which is what lets `io::copy` feed the file straight into it.
+    use sha2::{Digest, Sha256};
+    let mut file = std::fs::File::open(path)
+        .with_context(|| format!("failed to open {} for hashing", path.display()))?;
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut file, &mut hasher)?;
+    // `{:x}` formats the digest as lowercase hex (the GenericArray output type
+    // implements LowerHex), matching the format of `sha256sum`.
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+// Hashes a downloaded file and, if a digest was expected, checks it. Returns the
+// actual digest so callers without an expectation can record it (trust on first use).
+// On a mismatch the file is deleted — a tampered or truncated download must not be
+// picked up by a later step — and `what` ("go@1.22.3") names it in the error.
+#[tracing::instrument(level = "debug", err)]
+pub fn verify_checksum(path: &Path, expected: Option<&str>, what: &str) -> Result<String> {
+    let digest = sha256_file(path)?;
+    tracing::debug!(%digest, "sha256");
+    if let Some(expected) = expected
+        && expected != digest
+    {
+        // The error from remove_path is ignored on purpose: the checksum failure
+        // is the error the user needs to see.
+        let _ = remove_path(path);
+        anyhow::bail!("checksum mismatch for {what}: expected {expected}, got {digest}");
+    }
+    Ok(digest)
+}
+
+// "linux-x86_64", "macos-aarch64", ... — identifies the machine an artifact was
+// resolved for. std::env::consts are &'static str values fixed at compile time,
+// so this is the same string for every run of a given binary.
+pub fn platform() -> String {
+    format!("{}-{}", std::env::consts::OS, std::env::consts::ARCH)
+}
+
+// Numeric sort key for a version string: "2.23.0" → [2, 23, 0].
+// Lexicographic string comparison would put "1.9" after "1.10"; comparing Vec<u64>
+// compares element by element, which is exactly semver ordering for the numeric part.
+pub fn version_key(version: &str) -> Vec<u64> {
+    version
+        .split('.')
+        // map_while stops at the first non-numeric segment ("0rc1"), so odd
+        // suffixes sort as if they weren't there instead of failing the parse.
+        .map_while(|part| part.parse().ok())
+        .collect()
+}
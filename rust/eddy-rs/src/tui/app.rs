@@ -0,0 +1,365 @@
+// The TUI's state and what keys do to it. Nothing in here touches the terminal,
+// the network or the disk: a key press returns the `Job`s to start, and a job's
+// results come back as `Message`s. That keeps the whole UI testable as plain data
+// (tests/tui.rs), the same split as Elm or a Redux reducer in TS.
+use std::collections::HashMap;
+
+use ratatui::crossterm::event::KeyCode;
+use ratatui::widgets::ListState;
+
+use crate::{current::Selection, shared::version_key};
+
+// Background work the event loop runs (see mod.rs).
+#[derive(Debug, Clone, PartialEq)]
+pub enum Job {
+    // Installed versions and the one on PATH, read from disk.
+    LoadLocal(String),
+    // Every released version, fetched from the tool's release index.
+    LoadRemote(String),
+    Install(String, String),
+    Use(String, String),
+    Delete(String, String),
+}
+
+impl Job {
+    // Jobs that change what's installed or linked. Quitting while one runs
+    // would cut it off half-way, so the event loop tracks them.
+    pub fn is_change(&self) -> bool {
+        matches!(self, Job::Install(..) | Job::Use(..) | Job::Delete(..))
+    }
+}
+
+// Results of jobs, and the progress reports made while they run.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Message {
+    Local { tool: String, installed: Vec<String>, active: Option<Selection> },
+    // Err holds the error already formatted: anyhow::Error isn't Clone.
+    Remote { tool: String, versions: Result<Vec<String>, String> },
+    Status(String),
+    // An Install, Use or Delete job has finished, whatever its outcome.
+    JobDone,
+    DownloadStarted { name: String, total: Option<u64> },
+    DownloadAdvanced { name: String, bytes: u64 },
+    DownloadFinished { name: String },
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum Remote {
+    #[default]
+    NotLoaded,
+    Loading,
+    Loaded(Vec<String>),
+    Failed(String),
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ToolState {
+    pub installed: Vec<String>,
+    // Whichever scope wins, as `eddy current` decides it.
+    pub active: Option<Selection>,
+    pub remote: Remote,
+}
+
+// One line of the versions pane.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VersionRow {
+    pub version: String,
+    pub installed: bool,
+    pub active: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pane {
+    Tools,
+    Versions,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Download {
+    pub done: u64,
+    pub total: Option<u64>,
+}
+
+pub struct App {
+    pub tools: Vec<String>,
+    pub focus: Pane,
+    // ListState remembers the selection *and* the scroll offset between frames.
+    pub tool_list: ListState,
+    pub version_list: ListState,
+    pub states: HashMap<String, ToolState>,
+    // In-flight downloads by archive name, in the order they started.
+    pub downloads: Vec<(String, Download)>,
+    pub status: String,
+    // `d` asks first; the next key confirms (`y`) or cancels.
+    pub confirm_delete: Option<(String, String)>,
+    // Install/Use/Delete jobs started and not yet done.
+    pub running: usize,
+    // Quitting was asked for while jobs were running; `y` quits anyway.
+    pub confirm_quit: bool,
+    pub quit: bool,
+}
+
+impl App {
+    pub fn new(tools: Vec<String>) -> Self {
+        Self {
+            tools,
+            focus: Pane::Tools,
+            tool_list: ListState::default().with_selected(Some(0)),
+            version_list: ListState::default().with_selected(Some(0)),
+            states: HashMap::new(),
+            downloads: Vec::new(),
+            status: "↑↓ select  ←→ switch pane  i install  u/enter use  d delete  r refresh  q quit"
+                .to_string(),
+            confirm_delete: None,
+            running: 0,
+            confirm_quit: false,
+            quit: false,
+        }
+    }
+
+    // The jobs to run at startup: every tool's local state (for the active
+    // versions in the tools pane), and the remote versions of the first one.
+    pub fn start(&mut self) -> Vec<Job> {
+        let mut jobs: Vec<Job> = self.tools.iter().map(|t| Job::LoadLocal(t.clone())).collect();
+        jobs.extend(self.load_remote());
+        jobs
+    }
+
+    pub fn selected_tool(&self) -> Option<&str> {
+        self.tools.get(self.tool_list.selected()?).map(String::as_str)
+    }
+
+    pub fn state(&self, tool: &str) -> Option<&ToolState> {
+        self.states.get(tool)
+    }
+
+    // The selected tool's versions, newest first: everything installed plus
+    // everything released, once the release index has been loaded.
+    pub fn rows(&self) -> Vec<VersionRow> {
+        let Some(state) = self.selected_tool().and_then(|t| self.states.get(t)) else {
+            return Vec::new();
+        };
+        let mut versions = state.installed.clone();
+        if let Remote::Loaded(remote) = &state.remote {
+            versions.extend(remote.iter().cloned());
+        }
+        // Sort before dedup: dedup only drops *adjacent* duplicates.
+        versions.sort_by(|a, b| version_key(b).cmp(&version_key(a)).then_with(|| b.cmp(a)));
+        versions.dedup();
+        versions
+            .into_iter()
+            .map(|version| VersionRow {
+                installed: state.installed.contains(&version),
+                active: state.active.as_ref().is_some_and(|a| a.version == version),
+                version,
+            })
+            .collect()
+    }
+
+    pub fn selected_row(&self) -> Option<VersionRow> {
+        self.rows().get(self.version_list.selected()?).cloned()
+    }
+
+    // LoadRemote for the selected tool, unless it's loaded or on its way.
+    fn load_remote(&mut self) -> Option<Job> {
+        let tool = self.selected_tool()?.to_string();
+        let state = self.states.entry(tool.clone()).or_default();
+        if matches!(state.remote, Remote::NotLoaded | Remote::Failed(_)) {
+            state.remote = Remote::Loading;
+            return Some(Job::LoadRemote(tool));
+        }
+        None
+    }
+
+    // Moves a list selection by `delta`, clamped to `len` items.
+    fn step(list: &mut ListState, len: usize, delta: isize) {
+        if len == 0 {
+            list.select(None);
+            return;
+        }
+        let current = list.selected().unwrap_or(0) as isize;
+        list.select(Some((current + delta).clamp(0, len as isize - 1) as usize));
+    }
+
+    fn move_selection(&mut self, delta: isize) -> Vec<Job> {
+        match self.focus {
+            Pane::Tools => {
+                Self::step(&mut self.tool_list, self.tools.len(), delta);
+                // A different tool: start at its newest version.
+                self.version_list.select(Some(0));
+                self.load_remote().into_iter().collect()
+            }
+            Pane::Versions => {
+                let len = self.rows().len();
+                Self::step(&mut self.version_list, len, delta);
+                Vec::new()
+            }
+        }
+    }
+
+    pub fn on_key(&mut self, key: KeyCode) -> Vec<Job> {
+        let jobs = self.handle_key(key);
+        self.running += jobs.iter().filter(|job| job.is_change()).count();
+        jobs
+    }
+
+    // `q`: quits right away when nothing is running, otherwise asks first.
+    // While it asks, finishing the last job quits too (see on_message).
+    pub fn request_quit(&mut self) {
+        if self.running == 0 {
+            self.quit = true;
+            return;
+        }
+        let jobs = if self.running == 1 { "1 job is".to_string() } else { format!("{} jobs are", self.running) };
+        self.status = format!("{jobs} still running; quit anyway? (y/n)");
+        self.confirm_quit = true;
+    }
+
+    // Ctrl-C: like `q`, but a second one while asking quits without waiting.
+    pub fn interrupt(&mut self) {
+        if self.confirm_quit {
+            self.quit = true;
+        } else {
+            self.request_quit();
+        }
+    }
+
+    fn handle_key(&mut self, key: KeyCode) -> Vec<Job> {
+        if self.confirm_quit {
+            self.confirm_quit = false;
+            if key == KeyCode::Char('y') {
+                self.quit = true;
+            } else {
+                self.status = "Quit cancelled".to_string();
+            }
+            return Vec::new();
+        }
+        // A pending delete takes the next key, whatever it is.
+        if let Some((tool, version)) = self.confirm_delete.take() {
+            if key == KeyCode::Char('y') {
+                self.status = format!("Deleting {tool}@{version}...");
+                return vec![Job::Delete(tool, version)];
+            }
+            self.status = "Delete cancelled".to_string();
+            return Vec::new();
+        }
+
+        match key {
+            KeyCode::Char('q') | KeyCode::Esc => {
+                self.request_quit();
+                Vec::new()
+            }
+            KeyCode::Up | KeyCode::Char('k') => self.move_selection(-1),
+            KeyCode::Down | KeyCode::Char('j') => self.move_selection(1),
+            KeyCode::PageUp => self.move_selection(-10),
+            KeyCode::PageDown => self.move_selection(10),
+            KeyCode::Left | KeyCode::Char('h') => {
+                self.focus = Pane::Tools;
+                Vec::new()
+            }
+            KeyCode::Right | KeyCode::Char('l') | KeyCode::Tab => {
+                self.focus = Pane::Versions;
+                Vec::new()
+            }
+            KeyCode::Char('r') => {
+                let Some(tool) = self.selected_tool().map(str::to_string) else {
+                    return Vec::new();
+                };
+                self.states.entry(tool.clone()).or_default().remote = Remote::NotLoaded;
+                let mut jobs = vec![Job::LoadLocal(tool)];
+                jobs.extend(self.load_remote());
+                jobs
+            }
+            KeyCode::Char('i') => self.on_version(|tool, row| {
+                if row.installed {
+                    Err(format!("{tool}@{} is already installed", row.version))
+                } else {
+                    Ok(Job::Install(tool, row.version.clone()))
+                }
+            }),
+            KeyCode::Char('u') | KeyCode::Enter => self.on_version(|tool, row| {
+                if row.installed {
+                    Ok(Job::Use(tool, row.version.clone()))
+                } else {
+                    Err(format!("{tool}@{} is not installed; press i to install it", row.version))
+                }
+            }),
+            KeyCode::Char('d') => {
+                let Some(row) = self.selected_row().filter(|_| self.focus == Pane::Versions) else {
+                    return Vec::new();
+                };
+                let tool = self.selected_tool().unwrap_or_default().to_string();
+                if !row.installed {
+                    self.status = format!("{tool}@{} is not installed", row.version);
+                } else {
+                    self.status = format!("Delete {tool}@{}? (y/n)", row.version);
+                    self.confirm_delete = Some((tool, row.version));
+                }
+                Vec::new()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    // Runs `make` on the selected version (versions pane only), putting its
+    // Err in the status line.
+    fn on_version(
+        &mut self,
+        make: impl FnOnce(String, &VersionRow) -> Result<Job, String>,
+    ) -> Vec<Job> {
+        if self.focus != Pane::Versions {
+            return Vec::new();
+        }
+        let tool = self.selected_tool().map(str::to_string);
+        let (Some(tool), Some(row)) = (tool, self.selected_row()) else {
+            return Vec::new();
+        };
+        match make(tool, &row) {
+            Ok(job) => vec![job],
+            Err(message) => {
+                self.status = message;
+                Vec::new()
+            }
+        }
+    }
+
+    pub fn on_message(&mut self, message: Message) {
+        match message {
+            Message::Local { tool, installed, active } => {
+                let state = self.states.entry(tool).or_default();
+                state.installed = installed;
+                state.active = active;
+            }
+            Message::Remote { tool, versions } => {
+                self.states.entry(tool).or_default().remote = match versions {
+                    Ok(versions) => Remote::Loaded(versions),
+                    Err(e) => Remote::Failed(e),
+                };
+            }
+            Message::Status(text) => self.status = text,
+            Message::JobDone => {
+                self.running = self.running.saturating_sub(1);
+                // Still asking: quit if that was the last job, else ask again
+                // (the job's own status line has just replaced the question).
+                if self.confirm_quit {
+                    self.request_quit();
+                }
+            }
+            Message::DownloadStarted { name, total } => {
+                self.downloads.retain(|(n, _)| *n != name);
+                self.downloads.push((name, Download { done: 0, total }));
+            }
+            Message::DownloadAdvanced { name, bytes } => {
+                if let Some((_, download)) = self.downloads.iter_mut().find(|(n, _)| *n == name) {
+                    download.done += bytes;
+                }
+            }
+            Message::DownloadFinished { name } => self.downloads.retain(|(n, _)| *n != name),
+        }
+        // The rows may have changed under the selection.
+        let len = self.rows().len();
+        if self.version_list.selected().is_some_and(|i| i >= len) {
+            self.version_list.select(Some(len.saturating_sub(1)));
+        }
+    }
+}
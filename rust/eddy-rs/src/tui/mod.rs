@@ -0,0 +1,139 @@
+// `eddy` with no arguments: an interactive version of `list`, `install`, `use`
+// and `delete`. Tools on the left, the selected tool's versions (installed and
+// released) on the right; see app.rs for the keys.
+//
+//   mod.rs  the event loop: terminal setup, key events, background jobs
+//   app.rs  state and key handling, no I/O
+//   ui.rs   drawing
+//
+// Installs go through the same `Eddy` client (and so the same ToolBlueprint) as
+// the subcommands. Its progress reports come back over a channel instead of
+// going to stderr, which the UI is drawn over.
+mod app;
+mod ui;
+
+pub use app::{App, Download, Job, Message, Pane, Remote, VersionRow};
+pub use ui::draw;
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Result;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use tokio::sync::mpsc::{UnboundedSender, unbounded_channel};
+
+use crate::{client::Eddy, completions, current, languages, progress::Progress, project::Project};
+
+// Forwards progress reports into the event loop as Messages.
+struct ChannelProgress {
+    tx: UnboundedSender<Message>,
+}
+
+// A failed `send` means the UI has quit; there's nobody left to tell.
+impl Progress for ChannelProgress {
+    fn message(&self, text: &str) {
+        let _ = self.tx.send(Message::Status(text.to_string()));
+    }
+    fn download_started(&self, name: &str, total: Option<u64>) {
+        let _ = self.tx.send(Message::DownloadStarted { name: name.to_string(), total });
+    }
+    fn download_advanced(&self, name: &str, bytes: u64) {
+        let _ = self.tx.send(Message::DownloadAdvanced { name: name.to_string(), bytes });
+    }
+    fn download_finished(&self, name: &str) {
+        let _ = self.tx.send(Message::DownloadFinished { name: name.to_string() });
+    }
+}
+
+// Installed versions and the one on PATH, for the tools pane. The project is
+// re-read each time, so a pin changed in another terminal shows up on refresh.
+async fn load_local(tool: &str) -> Message {
+    let installed = languages::installed_versions(tool).await.unwrap_or_default();
+    let project = std::env::current_dir().ok().and_then(|cwd| Project::find(&cwd).ok().flatten());
+    let found = current::selections(tool, project.as_ref()).await.unwrap_or_default();
+    Message::Local { tool: tool.to_string(), installed, active: current::winner(&found) }
+}
+
+// Runs one job to completion and reports back. Anything that changes what's
+// installed or linked ends with a LoadLocal, so the panes show the result.
+async fn run_job(eddy: Eddy, job: Job, tx: UnboundedSender<Message>) {
+    let (tool, outcome) = match job {
+        Job::LoadLocal(tool) => {
+            let _ = tx.send(load_local(&tool).await);
+            return;
+        }
+        Job::LoadRemote(tool) => {
+            let versions = eddy.versions(&tool).await.map_err(|e| format!("{e:#}"));
+            let _ = tx.send(Message::Remote { tool, versions });
+            return;
+        }
+        Job::Install(tool, version) => {
+            let done = format!("Installed {tool}@{version}");
+            let outcome = eddy.install(&tool, &version).await.map(|_| done);
+            (tool, outcome)
+        }
+        Job::Use(tool, version) => {
+            let done = format!("Now using {tool}@{version}");
+            let outcome = eddy.use_version(&tool, &version).await.map(|_| done);
+            (tool, outcome)
+        }
+        Job::Delete(tool, version) => {
+            let done = format!("Deleted {tool}@{version}");
+            let outcome = eddy.delete(&tool, &version, false).await.map(|_| done);
+            (tool, outcome)
+        }
+    };
+    let status = match outcome {
+        Ok(done) => done,
+        // `{e:#}` prints the whole context chain on one line.
+        Err(e) => format!("error: {e:#}"),
+    };
+    let _ = tx.send(Message::Status(status));
+    let _ = tx.send(Message::JobDone);
+    let _ = tx.send(load_local(&tool).await);
+}
+
+pub async fn run() -> Result<()> {
+    let (tx, mut rx) = unbounded_channel();
+    let eddy = Eddy::new().with_progress(Arc::new(ChannelProgress { tx: tx.clone() }));
+    let mut app = App::new(completions::tools());
+    let spawn = |job: Job| {
+        tokio::spawn(run_job(eddy.clone(), job, tx.clone()));
+    };
+    for job in app.start() {
+        spawn(job);
+    }
+
+    // ratatui::init switches to the alternate screen and raw mode, and installs a
+    // panic hook that switches back, so a panic doesn't leave the terminal unusable.
+    let mut terminal = ratatui::init();
+    let result = (|| -> Result<()> {
+        while !app.quit {
+            terminal.draw(|frame| draw(frame, &mut app))?;
+            // Wait briefly for a key, then pick up whatever the jobs reported.
+            // The short poll blocks this worker thread only; the jobs run on the
+            // others of the multi-threaded runtime `#[tokio::main]` starts.
+            // Improvement: crossterm's async EventStream would avoid polling.
+            if event::poll(Duration::from_millis(50))?
+                && let Event::Key(key) = event::read()?
+                // Windows reports key releases too; only presses count.
+                && key.kind == KeyEventKind::Press
+            {
+                // Raw mode turns Ctrl-C into a key press instead of a SIGINT.
+                if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+                    app.interrupt();
+                } else {
+                    for job in app.on_key(key.code) {
+                        spawn(job);
+                    }
+                }
+            }
+            while let Ok(message) = rx.try_recv() {
+                app.on_message(message);
+            }
+        }
+        Ok(())
+    })();
+    ratatui::restore();
+    result
+}
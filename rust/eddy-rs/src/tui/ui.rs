@@ -0,0 +1,113 @@
+// Drawing. ratatui is immediate-mode: every frame is rendered from `App` from
+// scratch (like a React render function), and ratatui diffs the result against
+// the previous frame so only changed cells reach the terminal.
+//
+//   ┌ Tools ───────────┐┌ go ─────────────────────┐
+//   │> go       1.22.3 ││  1.23.0                  │
+//   │  cmake           ││> 1.22.3      active      │
+//   └──────────────────┘│  1.21.0      installed   │
+//   go1.23.0.linux-amd64.tar.gz 52%  (a gauge per download)
+//   status line
+use ratatui::{
+    Frame,
+    layout::{Constraint, Layout},
+    style::{Modifier, Style, Stylize},
+    text::Line,
+    widgets::{Block, Gauge, List, ListItem, Paragraph},
+};
+
+use super::app::{App, Pane, Remote};
+use crate::{current::Scope, shared::format_bytes};
+
+pub fn draw(frame: &mut Frame, app: &mut App) {
+    // One line per download in flight, then the status line.
+    let [main, downloads, status] = Layout::vertical([
+        Constraint::Min(3),
+        Constraint::Length(app.downloads.len() as u16),
+        Constraint::Length(1),
+    ])
+    .areas(frame.area());
+    let [tools, versions] =
+        Layout::horizontal([Constraint::Length(30), Constraint::Min(20)]).areas(main);
+
+    draw_tools(frame, app, tools);
+    draw_versions(frame, app, versions);
+
+    let rows = Layout::vertical(vec![Constraint::Length(1); app.downloads.len()]).split(downloads);
+    for ((name, download), area) in app.downloads.iter().zip(rows.iter()) {
+        // Without a Content-Length there's no ratio, only a byte count.
+        match download.total {
+            Some(total) if total > 0 => {
+                let ratio = (download.done as f64 / total as f64).min(1.0);
+                let gauge = Gauge::default()
+                    .ratio(ratio)
+                    .label(format!("{name} {}%", (ratio * 100.0) as u8))
+                    .gauge_style(Style::new().cyan());
+                frame.render_widget(gauge, *area);
+            }
+            _ => frame.render_widget(
+                Paragraph::new(format!("{name} {}", format_bytes(download.done))),
+                *area,
+            ),
+        }
+    }
+
+    frame.render_widget(Paragraph::new(app.status.as_str()).dim(), status);
+}
+
+// The focused pane gets the highlighted border.
+fn block(title: String, focused: bool) -> Block<'static> {
+    let block = Block::bordered().title(title);
+    if focused { block.border_style(Style::new().yellow()) } else { block }
+}
+
+fn draw_tools(frame: &mut Frame, app: &mut App, area: ratatui::layout::Rect) {
+    let items: Vec<ListItem> = app
+        .tools
+        .iter()
+        .map(|tool| {
+            // The scope is only spelled out when it isn't the global link.
+            let active = match app.state(tool).and_then(|s| s.active.as_ref()) {
+                Some(a) if a.scope == Scope::Global => a.version.clone(),
+                Some(a) => format!("{} ({})", a.version, a.scope),
+                None => String::new(),
+            };
+            ListItem::new(Line::from(vec![format!("{tool:<8} ").into(), active.green()]))
+        })
+        .collect();
+    let list = List::new(items)
+        .block(block(" Tools ".to_string(), app.focus == Pane::Tools))
+        .highlight_symbol("> ")
+        .highlight_style(Style::new().add_modifier(Modifier::BOLD));
+    frame.render_stateful_widget(list, area, &mut app.tool_list);
+}
+
+fn draw_versions(frame: &mut Frame, app: &mut App, area: ratatui::layout::Rect) {
+    let tool = app.selected_tool().unwrap_or_default().to_string();
+    let remote = app.state(&tool).map(|s| s.remote.clone()).unwrap_or_default();
+    // The title says how complete the list is.
+    let title = match &remote {
+        Remote::Loading => format!(" {tool} (loading releases...) "),
+        Remote::Failed(e) => format!(" {tool} (installed only: {e}) "),
+        _ => format!(" {tool} "),
+    };
+
+    let items: Vec<ListItem> = app
+        .rows()
+        .into_iter()
+        .map(|row| {
+            let version = format!("{:<12}", row.version);
+            let line = match (row.installed, row.active) {
+                (_, true) => Line::from(vec![version.into(), "active".green()]),
+                (true, false) => Line::from(vec![version.into(), "installed".cyan()]),
+                (false, false) => Line::from(version),
+            };
+            ListItem::new(line)
+        })
+        .collect();
+    let list = List::new(items)
+        .block(block(title, app.focus == Pane::Versions))
+        .highlight_symbol("> ")
+        .highlight_style(Style::new().add_modifier(Modifier::BOLD));
+    frame.render_stateful_widget(list, area, &mut app.version_list);
+}
@@ -67,7 +67,9 @@ impl From<&str> for Version {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+// Deserialize so plugins (plugin.rs) can list their steps as "extract", "rename", "chmod".
+#[derive(Debug, Clone, PartialEq, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum InstallStep {
     // No data payloads needed — these are pure tags, like TS's string literal union.
     // But unlike TS string unions, adding Extract2 here would force every `match`
@@ -110,4 +112,13 @@ pub struct ToolInfo {
     pub links: Option<Vec<&'static str>>,
 
     pub steps: Vec<InstallStep>,
+
+    // Expected SHA-256 of the downloaded artifact. Builders leave it None; it's
+    // filled in from `eddy.lock` so a locked install is verified byte-for-byte,
+    // and `ToolBlueprint::install` sets it to the computed digest otherwise.
+    pub sha256: Option<String>,
+
+    // SPDX license identifier of the tool itself ("BSD-3-Clause"), reported by
+    // `eddy sbom`. None when unknown (a plugin that doesn't say).
+    pub license: Option<&'static str>,
 }
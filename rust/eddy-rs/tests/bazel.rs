@@ -82,6 +82,6 @@ async fn deletes_bazel_installation() {
     blueprint.install().await.unwrap();
     assert!(dir.join(info.name).exists());
 
-    blueprint.delete().await.unwrap();
+    blueprint.delete(false).await.unwrap();
     assert!(!dir.join(info.name).exists());
 }
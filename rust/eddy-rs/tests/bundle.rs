@@ -0,0 +1,97 @@
+mod common;
+
+use std::sync::Arc;
+
+use eddy_rs::{
+    blueprint::ToolBlueprint,
+    bundle::{BundledTool, bundle, parse_spec, read_manifest, unbundle},
+    consts::eddy_bin_dir,
+    languages::cpp::bazel,
+    progress::Silent,
+    shared::platform,
+    types::Version,
+};
+use serial_test::serial;
+use tempfile::TempDir;
+
+// Lays out an installed bazel by hand (a bare binary, as InstallStep::Rename
+// leaves it) plus a leftover download, so no network access is needed.
+fn fake_bazel_install() -> ToolBlueprint {
+    let blueprint = ToolBlueprint::new(bazel::build(Version::SemVer("8.5.0".into())));
+    std::fs::create_dir_all(blueprint.bin_dir()).unwrap();
+    std::fs::write(blueprint.bin_dir().join("bazel"), "#!/bin/sh\n").unwrap();
+    std::fs::write(blueprint.archive_path(), "downloaded release").unwrap();
+    blueprint
+}
+
+#[test]
+fn specs_need_an_exact_version() {
+    assert_eq!(parse_spec("go@1.22.3").unwrap(), ("go", "1.22.3"));
+    assert!(parse_spec("go").is_err());
+    assert!(parse_spec("go@").is_err());
+    assert!(parse_spec("go@latest").is_err());
+}
+
+#[tokio::test]
+#[serial]
+async fn round_trips_to_a_fresh_machine() {
+    let out = TempDir::new().unwrap();
+    let archive = out.path().join("toolchain.tar.gz");
+
+    {
+        let _home = common::isolated_eddy_home();
+        let installed = fake_bazel_install();
+        bundle(&["bazel@8.5.0".to_string()], &archive, Arc::new(Silent)).await.unwrap();
+        assert!(installed.is_installed());
+    }
+
+    // The leftover download isn't shipped.
+    let listing = std::process::Command::new("tar").arg("-tf").arg(&archive).output().unwrap();
+    let listing = String::from_utf8_lossy(&listing.stdout);
+    assert!(listing.contains("cpp/bazel/8.5.0/bazel\n"), "{listing}");
+    assert!(!listing.contains("bazel-8.5.0-"), "{listing}");
+
+    let manifest = read_manifest(&archive).unwrap();
+    assert_eq!(manifest.platform, platform());
+    assert_eq!(
+        manifest.tools,
+        [BundledTool { tool: "bazel".into(), version: "8.5.0".into() }]
+    );
+
+    // A second, empty home stands in for the build agent.
+    let _home = common::isolated_eddy_home();
+    unbundle(&archive, Arc::new(Silent)).await.unwrap();
+
+    let restored = ToolBlueprint::new(bazel::build(Version::SemVer("8.5.0".into())));
+    assert!(restored.is_installed());
+    let link = eddy_bin_dir().join("bazel");
+    assert_eq!(std::fs::read_link(&link).unwrap(), restored.bin_dir().join("bazel"));
+}
+
+#[tokio::test]
+#[serial]
+async fn refuses_to_bundle_missing_versions() {
+    let _home = common::isolated_eddy_home();
+    let out = TempDir::new().unwrap();
+    let err = bundle(&["ninja@1.13.2".to_string()], &out.path().join("t.tar.gz"), Arc::new(Silent))
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("not installed"), "{err}");
+}
+
+#[test]
+fn plain_archives_are_not_bundles() {
+    let dir = TempDir::new().unwrap();
+    std::fs::write(dir.path().join("file"), "x").unwrap();
+    let archive = dir.path().join("plain.tar");
+    let status = std::process::Command::new("tar")
+        .arg("-cf")
+        .arg(&archive)
+        .arg("-C")
+        .arg(dir.path())
+        .arg("file")
+        .status()
+        .unwrap();
+    assert!(status.success());
+    assert!(read_manifest(&archive).is_err());
+}
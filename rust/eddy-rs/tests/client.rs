@@ -0,0 +1,98 @@
+// The embedding API. Every client here gets its own home through `with_home`,
+// so unlike the CLI-level tests these don't touch EDDY_HOME and need no #[serial].
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use eddy_rs::{
+    blueprint::ArtifactSource,
+    client::Eddy,
+    progress::{Progress, Silent},
+};
+use tempfile::TempDir;
+
+// Collects status lines instead of printing them.
+#[derive(Default)]
+struct Recorder {
+    messages: Mutex<Vec<String>>,
+}
+
+impl Progress for Recorder {
+    fn message(&self, text: &str) {
+        self.messages.lock().unwrap().push(text.to_string());
+    }
+}
+
+// bazel ships as one bare binary, so any file will do as its "release".
+fn fake_bazel(dir: &Path) -> ArtifactSource {
+    let binary = dir.join("bazel-from-usb");
+    std::fs::write(&binary, "#!/bin/sh\n").unwrap();
+    ArtifactSource::File(binary)
+}
+
+#[test]
+fn client_can_be_shared_between_tasks() {
+    // Compile-time check: a function that only accepts Send + Sync + Clone types.
+    fn assert_shareable<T: Send + Sync + Clone + 'static>() {}
+    assert_shareable::<Eddy>();
+}
+
+#[tokio::test]
+async fn installs_uses_and_deletes_under_its_own_home() {
+    let home = TempDir::new().unwrap();
+    let work = TempDir::new().unwrap();
+    let recorder = Arc::new(Recorder::default());
+    let eddy = Eddy::new()
+        .with_home(home.path().to_path_buf())
+        .with_progress(recorder.clone());
+
+    let bazel = eddy.install_from("bazel", "8.5.0", fake_bazel(work.path())).await.unwrap();
+    assert!(bazel.bin_dir().starts_with(home.path()));
+    eddy.use_version("bazel", "8.5.0").await.unwrap();
+    assert!(eddy.bin_dir().join("bazel").exists());
+    assert_eq!(eddy.list("bazel").await.unwrap(), ["8.5.0"]);
+
+    // Deleting the active version needs `force`, which also removes the link.
+    assert!(eddy.delete("bazel", "8.5.0", false).await.is_err());
+    eddy.delete("bazel", "8.5.0", true).await.unwrap();
+    assert!(eddy.list("bazel").await.unwrap().is_empty());
+    assert!(!eddy.bin_dir().join("bazel").exists());
+
+    let messages = recorder.messages.lock().unwrap();
+    assert!(messages.iter().any(|m| m.starts_with("Using local archive")), "{messages:?}");
+    assert!(messages.iter().any(|m| m == "Successfully deleted bazel@8.5.0"), "{messages:?}");
+}
+
+// Several tasks installing the same version at once: the file lock makes one
+// wait for the other, and both end up with a working install.
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn concurrent_installs_of_one_version() {
+    let home = TempDir::new().unwrap();
+    let work = TempDir::new().unwrap();
+    let eddy = Eddy::new().with_home(home.path().to_path_buf()).with_progress(Arc::new(Silent));
+    let source = fake_bazel(work.path());
+
+    let tasks: Vec<_> = (0..4)
+        .map(|_| {
+            let eddy = eddy.clone();
+            let source = source.clone();
+            tokio::spawn(async move { eddy.install_from("bazel", "8.5.0", source).await })
+        })
+        .collect();
+    for task in tasks {
+        let blueprint = task.await.unwrap().unwrap();
+        assert!(blueprint.is_installed());
+    }
+    assert_eq!(eddy.list("bazel").await.unwrap(), ["8.5.0"]);
+}
+
+#[tokio::test]
+async fn homes_are_independent() {
+    let (a, b) = (TempDir::new().unwrap(), TempDir::new().unwrap());
+    let work = TempDir::new().unwrap();
+    let first = Eddy::new().with_home(a.path().to_path_buf()).with_progress(Arc::new(Silent));
+    let second = Eddy::new().with_home(b.path().to_path_buf()).with_progress(Arc::new(Silent));
+
+    first.install_from("bazel", "8.5.0", fake_bazel(work.path())).await.unwrap();
+    assert_eq!(first.list("bazel").await.unwrap(), ["8.5.0"]);
+    assert!(second.list("bazel").await.unwrap().is_empty());
+}
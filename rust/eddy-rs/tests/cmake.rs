@@ -96,7 +96,7 @@ async fn deletes_cmake_installation() {
     assert!(dir.join(&info.pkg_name).exists());
     assert!(dir.join(&base).exists());
 
-    blueprint.delete().await.unwrap();
+    blueprint.delete(false).await.unwrap();
     assert!(!dir.join(&base).exists());
     assert!(!dir.join(&info.pkg_name).exists());
 }
@@ -0,0 +1,129 @@
+mod common;
+
+use std::path::PathBuf;
+
+use eddy_rs::{
+    blueprint::ToolBlueprint,
+    cmake_preset::{self, Toolchain},
+    languages,
+    linker::Platform,
+    lockfile::Artifact,
+    project::Project,
+    shared::platform,
+    types::Version,
+};
+use serde_json::json;
+use serial_test::serial;
+use tempfile::TempDir;
+
+fn toolchain() -> Toolchain {
+    Toolchain {
+        cmake: Some(PathBuf::from("/eddy/cpp/cmake/4.1.4/bin/cmake")),
+        make_program: Some(PathBuf::from("/eddy/cpp/ninja/1.12.1/ninja")),
+        c_compiler: None,
+        cxx_compiler: None,
+    }
+}
+
+// Lays out an installed version by hand; the builders are offline for an exact version.
+async fn fake_install(tool: &str, version: &str) -> ToolBlueprint {
+    let info = languages::build(tool, Version::SemVer(version.into())).await.unwrap();
+    let blueprint = ToolBlueprint::new(info);
+    let bin_dir = blueprint.bin_dir();
+    std::fs::create_dir_all(&bin_dir).unwrap();
+    for link in blueprint.link_names() {
+        std::fs::write(bin_dir.join(Platform::current().exe_name(link)), "").unwrap();
+    }
+    blueprint
+}
+
+#[test]
+fn toolchain_file_sets_only_what_was_found() {
+    let text = cmake_preset::toolchain_file(&toolchain());
+    assert!(
+        text.contains("set(CMAKE_MAKE_PROGRAM \"/eddy/cpp/ninja/1.12.1/ninja\" CACHE FILEPATH \"\" FORCE)"),
+        "{text}"
+    );
+    assert!(!text.contains("CMAKE_C_COMPILER"), "{text}");
+}
+
+#[test]
+fn preset_uses_ninja_and_the_managed_cmake() {
+    let presets = cmake_preset::merge_presets(serde_json::Value::Null, &toolchain(), "eddy").unwrap();
+    assert_eq!(presets["version"], 3);
+    let configure = &presets["configurePresets"][0];
+    assert_eq!(configure["name"], "eddy");
+    assert_eq!(configure["generator"], "Ninja");
+    assert_eq!(configure["cmakeExecutable"], "/eddy/cpp/cmake/4.1.4/bin/cmake");
+    assert_eq!(configure["cacheVariables"]["CMAKE_MAKE_PROGRAM"], "/eddy/cpp/ninja/1.12.1/ninja");
+    assert_eq!(presets["buildPresets"][0]["configurePreset"], "eddy");
+}
+
+// Rerunning replaces the old "eddy" preset; the user's own presets, and the
+// order they wrote things in, are left alone.
+#[test]
+fn merging_keeps_the_users_presets() {
+    let existing = json!({
+        "version": 2,
+        "configurePresets": [
+            { "name": "debug", "binaryDir": "build/debug" },
+            { "name": "eddy", "generator": "Unix Makefiles" },
+        ],
+    });
+    let presets = cmake_preset::merge_presets(existing, &toolchain(), "eddy").unwrap();
+
+    assert_eq!(presets["version"], 3);
+    let names: Vec<&str> = presets["configurePresets"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|p| p["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, ["debug", "eddy"]);
+    assert_eq!(presets["configurePresets"][1]["generator"], "Ninja");
+    let keys: Vec<&String> = presets.as_object().unwrap().keys().collect();
+    assert_eq!(keys, ["version", "configurePresets", "buildPresets"]);
+}
+
+#[test]
+fn refuses_a_presets_file_that_is_not_an_object() {
+    assert!(cmake_preset::merge_presets(json!([]), &toolchain(), "eddy").is_err());
+}
+
+// cmake comes from the global link, ninja from the project's locked pin, which
+// wins over the (different) globally linked ninja.
+#[tokio::test]
+#[serial]
+async fn resolves_paths_into_the_active_versions() {
+    let _home = common::isolated_eddy_home();
+    let cmake = fake_install("cmake", "4.1.4").await;
+    cmake.use_tool().unwrap();
+    fake_install("ninja", "1.11.1").await.use_tool().unwrap();
+    let ninja = fake_install("ninja", "1.12.1").await;
+
+    let dir = TempDir::new().unwrap();
+    std::fs::write(dir.path().join("eddy.toml"), "[tools]\nninja = \"1.12\"\n").unwrap();
+    let mut project = Project::find(dir.path()).unwrap().unwrap();
+    project.lock.record(
+        "ninja",
+        "1.12.1",
+        Artifact { platform: platform(), url: "https://example.com/ninja".into(), sha256: "00".repeat(32) },
+    );
+
+    let toolchain = cmake_preset::resolve(Some(&project)).await.unwrap();
+    let exe = |name: &str| Platform::current().exe_name(name);
+    assert_eq!(toolchain.cmake, Some(cmake.bin_dir().join(exe("cmake"))));
+    assert_eq!(toolchain.make_program, Some(ninja.bin_dir().join(exe("ninja"))));
+    assert_eq!(toolchain.c_compiler, None);
+
+    // Without the project, the globally linked ninja is used.
+    let global = cmake_preset::resolve(None).await.unwrap();
+    assert!(global.make_program.unwrap().to_string_lossy().contains("1.11.1"));
+}
+
+#[tokio::test]
+#[serial]
+async fn nothing_active() {
+    let _home = common::isolated_eddy_home();
+    assert!(cmake_preset::resolve(None).await.unwrap().is_empty());
+}
@@ -0,0 +1,47 @@
+mod common;
+
+use clap_complete::Shell;
+use eddy_rs::{
+    completions::{dynamic_script, installed_versions, tools},
+    consts::eddy_dir,
+    languages::TOOLS,
+};
+use serial_test::serial;
+
+#[test]
+fn builtin_tools_come_first() {
+    assert_eq!(&tools()[..TOOLS.len()], TOOLS);
+}
+
+#[tokio::test]
+#[serial]
+async fn lists_installed_versions_newest_first() {
+    let _home = common::isolated_eddy_home();
+    for version in ["1.9.0", "1.22.3", "1.10.1"] {
+        std::fs::create_dir_all(eddy_dir().join("go/go-language").join(version)).unwrap();
+    }
+    // A stray file next to the version directories isn't a version.
+    std::fs::write(eddy_dir().join("go/go-language/notes.txt"), "").unwrap();
+
+    let versions = installed_versions("go").await.unwrap();
+    assert_eq!(versions, ["1.22.3", "1.10.1", "1.9.0"]);
+}
+
+#[tokio::test]
+#[serial]
+async fn nothing_installed_is_not_an_error() {
+    let _home = common::isolated_eddy_home();
+    assert!(installed_versions("ninja").await.unwrap().is_empty());
+    // Unknown (plugin) tools are never looked up.
+    assert!(installed_versions("acme").await.unwrap().is_empty());
+}
+
+#[test]
+fn dynamic_scripts_call_back_into_eddy() {
+    for shell in [Shell::Bash, Shell::Zsh, Shell::Fish] {
+        let script = dynamic_script(shell).unwrap();
+        assert!(script.contains("eddy __complete versions"), "{shell}");
+        assert!(script.contains("eddy __complete tools"), "{shell}");
+    }
+    assert!(dynamic_script(Shell::PowerShell).is_none());
+}
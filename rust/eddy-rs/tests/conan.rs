@@ -86,7 +86,7 @@ async fn deletes_conan_installation() {
     assert!(dir.join(&custom).join(info.name).exists());
     assert!(dir.join(&info.pkg_name).exists());
 
-    blueprint.delete().await.unwrap();
+    blueprint.delete(false).await.unwrap();
     assert!(!dir.join(&custom).join(info.name).exists());
     assert!(!dir.join(&info.pkg_name).exists());
 }
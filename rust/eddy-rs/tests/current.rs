@@ -0,0 +1,143 @@
+mod common;
+
+use std::path::Path;
+
+use eddy_rs::{
+    consts::eddy_dir,
+    current::{
+        self, Scope, Selection, ShellKind, note, selections, shell_exports, shell_var, version_from_link, winner,
+    },
+    project::Project,
+};
+use serial_test::serial;
+use tempfile::TempDir;
+
+#[test]
+fn shell_variable_names() {
+    assert_eq!(shell_var("go"), "EDDY_GO_VERSION");
+    assert_eq!(shell_var("my-tool"), "EDDY_MY_TOOL_VERSION");
+}
+
+#[test]
+fn exports_put_the_version_first_on_path() {
+    let bin = Path::new("/home/me/.eddy.sh/go/go-language/1.22.3/go/bin");
+    let posix = shell_exports(ShellKind::Posix, "go", "1.22.3", bin);
+    assert_eq!(
+        posix,
+        "export EDDY_GO_VERSION=\"1.22.3\"\nexport PATH=\"/home/me/.eddy.sh/go/go-language/1.22.3/go/bin:$PATH\"\n"
+    );
+    let fish = shell_exports(ShellKind::Fish, "go", "1.22.3", bin);
+    assert!(fish.starts_with("set -gx EDDY_GO_VERSION \"1.22.3\"\n"));
+    let pwsh = shell_exports(ShellKind::PowerShell, "go", "1.22.3", bin);
+    assert!(pwsh.starts_with("$env:EDDY_GO_VERSION = \"1.22.3\"\n"));
+}
+
+#[test]
+fn version_is_read_from_the_link_target() {
+    let tool_dir = Path::new("/h/.eddy.sh/go/go-language");
+    let target = tool_dir.join("1.22.3/go/bin/go");
+    assert_eq!(version_from_link(&target, tool_dir).as_deref(), Some("1.22.3"));
+    // A link eddy didn't create (pointing elsewhere) has no eddy version.
+    assert_eq!(version_from_link(Path::new("/usr/bin/go"), tool_dir), None);
+}
+
+// All three scopes at once, in scope order; the project pin is shown with its
+// locked version when the lock agrees with it.
+#[cfg(unix)]
+#[tokio::test]
+#[serial]
+async fn lists_every_scope_narrowest_first() {
+    let _home = common::isolated_eddy_home();
+    let bin_dir = eddy_dir().join("go/go-language/1.21.0/go/bin");
+    std::fs::create_dir_all(&bin_dir).unwrap();
+    std::fs::write(bin_dir.join("go"), "").unwrap();
+    std::fs::create_dir_all(eddy_dir().join("bin")).unwrap();
+    std::os::unix::fs::symlink(bin_dir.join("go"), eddy_dir().join("bin/go")).unwrap();
+
+    let dir = TempDir::new().unwrap();
+    std::fs::write(dir.path().join("eddy.toml"), "[tools]\ngo = \"1.22\"\n").unwrap();
+    let project = Project::find(dir.path()).unwrap().unwrap();
+
+    // SAFETY: #[serial] keeps other tests from reading the environment meanwhile.
+    unsafe { std::env::set_var("EDDY_GO_VERSION", "1.23.0") };
+    let found = selections("go", Some(&project)).await;
+    unsafe { std::env::remove_var("EDDY_GO_VERSION") };
+    let found = found.unwrap();
+
+    let scopes: Vec<(Scope, &str)> =
+        found.iter().map(|s| (s.scope, s.version.as_str())).collect();
+    assert_eq!(
+        scopes,
+        [(Scope::Shell, "1.23.0"), (Scope::Local, "1.22"), (Scope::Global, "1.21.0")]
+    );
+    assert_eq!(found[1].origin, dir.path().join("eddy.toml").display().to_string());
+}
+
+#[tokio::test]
+#[serial]
+async fn nothing_selected() {
+    let _home = common::isolated_eddy_home();
+    assert!(selections("cmake", None).await.unwrap().is_empty());
+    // Plugin tools have no global link eddy can read, and no lookup is attempted.
+    assert!(selections("acme", None).await.unwrap().is_empty());
+}
+
+// `eddy current` gets its rows back to print; nothing selected means no rows.
+#[tokio::test]
+#[serial]
+async fn rows_follow_the_selections() {
+    let _home = common::isolated_eddy_home();
+    assert!(current::rows(Some("cmake"), None).await.unwrap().is_empty());
+
+    let dir = TempDir::new().unwrap();
+    std::fs::write(dir.path().join("eddy.toml"), "[tools]\nacme = \"2.1\"\n").unwrap();
+    let project = Project::find(dir.path()).unwrap().unwrap();
+    let rows = current::rows(None, Some(&project)).await.unwrap();
+    assert_eq!(rows.len(), 1);
+    assert!(rows[0].starts_with("acme     2.1        local"), "{}", rows[0]);
+    assert!(rows[0].ends_with("(pinned, not linked; run `eddy sync`)"), "{}", rows[0]);
+}
+
+fn selection(scope: Scope, version: &str) -> Selection {
+    Selection { tool: "go".into(), version: version.into(), scope, origin: String::new() }
+}
+
+// A pin in eddy.toml doesn't change PATH by itself: until `eddy sync` links it,
+// the global link is what runs, and `eddy current` must say so.
+#[test]
+fn a_pin_is_only_active_once_linked() {
+    let pin = selection(Scope::Local, "1.22");
+    let global = selection(Scope::Global, "1.21.0");
+    let found = [pin.clone(), global.clone()];
+    assert_eq!(note(&pin, &found), Some("pinned, not linked; run `eddy sync`"));
+    assert_eq!(note(&global, &found), None);
+
+    let synced = [pin.clone(), selection(Scope::Global, "1.22.3")];
+    assert_eq!(note(&pin, &synced), None);
+    assert_eq!(note(&pin, std::slice::from_ref(&pin)), Some("pinned, not linked; run `eddy sync`"));
+}
+
+#[test]
+fn the_shell_scope_overrides_the_global_link() {
+    let shell = selection(Scope::Shell, "1.23.0");
+    let global = selection(Scope::Global, "1.21.0");
+    let found = [shell.clone(), global.clone()];
+    assert_eq!(note(&shell, &found), None);
+    assert_eq!(note(&global, &found), Some("overridden in this shell"));
+}
+
+// What the TUI shows as active: the same selection `eddy current` leaves unnoted.
+#[test]
+fn the_winner_is_what_runs() {
+    let shell = selection(Scope::Shell, "1.23.0");
+    let pin = selection(Scope::Local, "1.22");
+    let linked = selection(Scope::Global, "1.22.3");
+    let other = selection(Scope::Global, "1.21.0");
+
+    assert_eq!(winner(&[shell.clone(), pin.clone(), linked.clone()]), Some(shell));
+    // A linked pin wins with the version it's linked at.
+    let won = winner(&[pin.clone(), linked]).unwrap();
+    assert_eq!((won.scope, won.version.as_str()), (Scope::Local, "1.22.3"));
+    assert_eq!(winner(&[pin.clone(), other.clone()]), Some(other));
+    assert_eq!(winner(&[pin]), None);
+}
@@ -0,0 +1,94 @@
+mod common;
+
+use eddy_rs::{
+    blueprint::ToolBlueprint,
+    client::Eddy,
+    consts::{eddy_bin_dir, eddy_dir},
+    languages,
+    linker::Platform,
+    types::Version,
+};
+use serial_test::serial;
+
+// Lays out an installed go version by hand (go's builder is offline for an
+// exact version), so these tests never download anything.
+async fn fake_install(version: &str) -> ToolBlueprint {
+    let info = languages::build("go", Version::SemVer(version.into())).await.unwrap();
+    let blueprint = ToolBlueprint::new(info);
+    let bin_dir = blueprint.bin_dir();
+    std::fs::create_dir_all(&bin_dir).unwrap();
+    for link in blueprint.link_names() {
+        std::fs::write(bin_dir.join(Platform::current().exe_name(link)), "").unwrap();
+    }
+    blueprint
+}
+
+fn version_dir(version: &str) -> std::path::PathBuf {
+    eddy_dir().join("go/go-language").join(version)
+}
+
+#[tokio::test]
+#[serial]
+async fn refuses_to_delete_the_active_version() {
+    let _home = common::isolated_eddy_home();
+    let blueprint = fake_install("1.22.3").await;
+    blueprint.use_tool().unwrap();
+    assert!(blueprint.is_active());
+
+    let err = blueprint.delete(false).await.unwrap_err();
+    assert!(err.to_string().contains("active version"), "{err}");
+    assert!(version_dir("1.22.3").exists());
+}
+
+#[tokio::test]
+#[serial]
+async fn force_removes_the_links_too() {
+    let _home = common::isolated_eddy_home();
+    let blueprint = fake_install("1.22.3").await;
+    blueprint.use_tool().unwrap();
+
+    blueprint.delete(true).await.unwrap();
+    assert!(!version_dir("1.22.3").exists());
+    for link in blueprint.link_names() {
+        let link = eddy_bin_dir().join(Platform::current().link_name(link));
+        // symlink_metadata doesn't follow links, so a dangling one would still be found.
+        assert!(std::fs::symlink_metadata(&link).is_err(), "{} left behind", link.display());
+    }
+}
+
+#[tokio::test]
+#[serial]
+async fn inactive_version_keeps_the_other_links() {
+    let _home = common::isolated_eddy_home();
+    let old = fake_install("1.21.0").await;
+    let active = fake_install("1.22.3").await;
+    active.use_tool().unwrap();
+
+    old.delete(false).await.unwrap();
+    assert!(!version_dir("1.21.0").exists());
+    assert!(active.is_active());
+}
+
+#[tokio::test]
+#[serial]
+async fn missing_version_is_an_error() {
+    let _home = common::isolated_eddy_home();
+    let info = languages::build("go", Version::SemVer("1.22.3".into())).await.unwrap();
+    let err = ToolBlueprint::new(info).delete(false).await.unwrap_err();
+    assert!(err.to_string().contains("not installed"), "{err}");
+}
+
+#[tokio::test]
+#[serial]
+async fn delete_all_is_all_or_nothing_without_force() {
+    let _home = common::isolated_eddy_home();
+    fake_install("1.21.0").await;
+    fake_install("1.22.3").await.use_tool().unwrap();
+
+    assert!(Eddy::new().delete_all("go", false).await.is_err());
+    assert!(version_dir("1.21.0").exists());
+
+    Eddy::new().delete_all("go", true).await.unwrap();
+    assert!(!eddy_dir().join("go/go-language").exists());
+    assert!(Eddy::new().delete_all("go", false).await.is_err());
+}
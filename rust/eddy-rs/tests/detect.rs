@@ -0,0 +1,76 @@
+use eddy_rs::detect::{
+    detect, parse_bazelversion, parse_cmake_minimum, parse_go_mod, parse_tool_versions,
+};
+use tempfile::TempDir;
+
+#[test]
+fn tool_versions_keeps_known_installable_tools() {
+    let text = "\
+# toolchain for CI
+golang 1.22.3 1.21.0
+cmake 3.28.1   # pinned for presets
+nodejs 20.11.0
+ninja system
+bazel ref:abc123
+";
+    assert_eq!(
+        parse_tool_versions(text),
+        vec![
+            ("go".to_string(), "1.22.3".to_string()),
+            ("cmake".to_string(), "3.28.1".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn go_mod_prefers_toolchain_directive() {
+    let text = "module example.com/app\n\ngo 1.22\n\ntoolchain go1.22.3\n";
+    assert_eq!(parse_go_mod(text).as_deref(), Some("1.22.3"));
+}
+
+#[test]
+fn go_mod_falls_back_to_go_directive() {
+    assert_eq!(parse_go_mod("module m\n\ngo 1.21\n").as_deref(), Some("1.21"));
+    // `toolchain default` doesn't name a version.
+    assert_eq!(parse_go_mod("go 1.21\ntoolchain default\n").as_deref(), Some("1.21"));
+    assert_eq!(parse_go_mod("module m\n"), None);
+}
+
+#[test]
+fn cmake_minimum_required_forms() {
+    assert_eq!(
+        parse_cmake_minimum("cmake_minimum_required(VERSION 3.20)\nproject(x)").as_deref(),
+        Some("3.20")
+    );
+    assert_eq!(
+        parse_cmake_minimum("CMAKE_MINIMUM_REQUIRED( VERSION 3.16...3.28 FATAL_ERROR )").as_deref(),
+        Some("3.28")
+    );
+    assert_eq!(parse_cmake_minimum("project(x)"), None);
+}
+
+#[test]
+fn bazelversion_forms() {
+    assert_eq!(parse_bazelversion("7.1.0\n").as_deref(), Some("7.1.0"));
+    assert_eq!(parse_bazelversion("7.x").as_deref(), Some("7"));
+    assert_eq!(parse_bazelversion("latest").as_deref(), Some("latest"));
+    assert_eq!(parse_bazelversion("someuser/7.0.0"), None);
+    assert_eq!(parse_bazelversion("last_green"), None);
+}
+
+#[test]
+fn tool_specific_files_override_tool_versions() {
+    let dir = TempDir::new().unwrap();
+    std::fs::write(dir.path().join(".tool-versions"), "golang 1.21.0\nninja 1.12.1\n").unwrap();
+    std::fs::write(dir.path().join("go.mod"), "module m\n\ngo 1.22\n").unwrap();
+    std::fs::write(dir.path().join(".bazelversion"), "8.5.0\n").unwrap();
+
+    let found = detect(dir.path()).unwrap();
+    let spec = |tool: &str| {
+        found.iter().find(|d| d.tool == tool).map(|d| (d.spec.as_str(), d.source))
+    };
+    assert_eq!(spec("go"), Some(("1.22", "go.mod")));
+    assert_eq!(spec("ninja"), Some(("1.12.1", ".tool-versions")));
+    assert_eq!(spec("bazel"), Some(("8.5.0", ".bazelversion")));
+    assert_eq!(spec("cmake"), None);
+}
@@ -0,0 +1,102 @@
+use eddy_rs::{
+    export::{self, Pin},
+    lockfile::Artifact,
+    project::Project,
+    shared::platform,
+};
+use tempfile::TempDir;
+
+fn pin(tool: &str, version: &str) -> Pin {
+    Pin { tool: tool.to_string(), version: version.to_string() }
+}
+
+// A project pinning `tools` (tool, spec, locked version), without touching EDDY_HOME.
+fn project(dir: &TempDir, tools: &[(&str, &str, Option<&str>)]) -> Project {
+    let mut manifest = "[tools]\n".to_string();
+    for (tool, spec, _) in tools {
+        manifest += &format!("{tool} = \"{spec}\"\n");
+    }
+    std::fs::write(dir.path().join("eddy.toml"), manifest).unwrap();
+    let mut project = Project::find(dir.path()).unwrap().unwrap();
+    for (tool, _, locked) in tools {
+        if let Some(version) = locked {
+            let artifact = Artifact {
+                platform: platform(),
+                url: format!("https://example.com/{tool}-{version}"),
+                sha256: "ab".repeat(32),
+            };
+            project.lock.record(tool, version, artifact);
+        }
+    }
+    project
+}
+
+#[test]
+fn locked_pins_are_the_locked_versions_in_name_order() {
+    let dir = TempDir::new().unwrap();
+    let project = project(&dir, &[("go", "1.22", Some("1.22.3")), ("cmake", "4", Some("4.1.4"))]);
+    let pins = export::locked_pins(&project).unwrap();
+    assert_eq!(pins, vec![pin("cmake", "4.1.4"), pin("go", "1.22.3")]);
+}
+
+// "1.22" would resolve to whatever is newest on the day the image is built.
+#[test]
+fn an_unlocked_pin_is_an_error() {
+    let dir = TempDir::new().unwrap();
+    let project = project(&dir, &[("go", "1.22", Some("1.22.3")), ("ninja", "1", None)]);
+    let err = export::locked_pins(&project).unwrap_err().to_string();
+    assert!(err.contains("ninja = \"1\" is not locked"), "{err}");
+    assert!(err.contains("eddy lock"), "{err}");
+}
+
+// A lock left over from an older pin doesn't satisfy the new one.
+#[test]
+fn a_stale_lock_is_an_error() {
+    let dir = TempDir::new().unwrap();
+    let project = project(&dir, &[("go", "1.23", Some("1.22.3"))]);
+    assert!(export::locked_pins(&project).is_err());
+}
+
+#[test]
+fn no_pins_is_an_error() {
+    let dir = TempDir::new().unwrap();
+    let project = project(&dir, &[]);
+    assert!(export::locked_pins(&project).is_err());
+}
+
+#[test]
+fn dockerfile_has_a_layer_per_tool_after_the_setup() {
+    let text = export::dockerfile(&[pin("cmake", "4.1.4"), pin("go", "1.22.3")], "ubuntu:24.04");
+    assert!(text.contains("\nFROM ubuntu:24.04\n"));
+    assert!(text.contains(&format!("ARG EDDY_VERSION={}\n", env!("CARGO_PKG_VERSION"))));
+    assert!(text.contains("ENV EDDY_HOME=/opt/eddy"));
+
+    let runs: Vec<&str> = text.lines().filter(|l| l.starts_with("RUN eddy install")).collect();
+    assert_eq!(
+        runs,
+        [
+            "RUN eddy install cmake 4.1.4 && eddy use cmake 4.1.4",
+            "RUN eddy install go 1.22.3 && eddy use go 1.22.3",
+        ]
+    );
+    // The tool layers come last, so bumping one keeps the setup layers cached.
+    assert!(text.trim_end().ends_with(runs[1]));
+    // Nothing that changes from one run to the next.
+    assert_eq!(text, export::dockerfile(&[pin("cmake", "4.1.4"), pin("go", "1.22.3")], "ubuntu:24.04"));
+}
+
+#[test]
+fn dockerfile_notes_plugin_tools() {
+    let text = export::dockerfile(&[pin("acme-cc", "2.0.0")], export::DEFAULT_BASE);
+    assert!(text.contains("# acme-cc is a plugin tool: eddy-plugin-acme-cc must be on PATH.\n"));
+    assert!(text.contains("RUN eddy install acme-cc 2.0.0"));
+}
+
+#[test]
+fn devcontainer_builds_the_dockerfile_and_suggests_extensions() {
+    let json = export::devcontainer(&[pin("go", "1.22.3"), pin("ninja", "1.12.1")], "demo");
+    assert_eq!(json["name"], "demo");
+    assert_eq!(json["build"]["dockerfile"], "Dockerfile");
+    assert_eq!(json["customizations"]["vscode"]["extensions"], serde_json::json!(["golang.go"]));
+    assert!(json["remoteEnv"]["PATH"].as_str().unwrap().starts_with("/opt/eddy/.eddy.sh/bin:"));
+}
@@ -0,0 +1,135 @@
+use std::io::{Read, Write};
+use std::net::TcpListener;
+
+use eddy_rs::{
+    github::{GithubClient, Release, next_link, pick_latest, rate_limit_message},
+    http::RetryPolicy,
+};
+
+// Releases are deserialized from JSON (rather than built as struct literals) so the
+// fixtures look like what the API actually returns and exercise the serde mapping.
+fn releases(json: &str) -> Vec<Release> {
+    serde_json::from_str(json).unwrap()
+}
+
+#[test]
+fn skips_prereleases_and_drafts() {
+    let list = releases(
+        r#"[
+            {"tag_name": "9.0.0rc1", "prerelease": true,
+             "assets": [{"name": "bazel-9.0.0rc1-linux-x86_64", "browser_download_url": "u1"}]},
+            {"tag_name": "8.6.0", "draft": true,
+             "assets": [{"name": "bazel-8.6.0-linux-x86_64", "browser_download_url": "u2"}]},
+            {"tag_name": "8.5.0",
+             "assets": [{"name": "bazel-8.5.0-linux-x86_64", "browser_download_url": "u3"}]}
+        ]"#,
+    );
+    let latest = pick_latest(&list, |v| format!("bazel-{v}-linux-x86_64")).unwrap();
+    assert_eq!(latest.version(), "8.5.0");
+}
+
+#[test]
+fn orders_by_version_not_publish_date() {
+    // The API lists newest-created first: a 1.x patch released after 2.23.0
+    // comes first in the response but must not be chosen.
+    let list = releases(
+        r#"[
+            {"tag_name": "1.66.1", "assets": [{"name": "conan-1.66.1.tgz", "browser_download_url": "a"}]},
+            {"tag_name": "2.23.0", "assets": [{"name": "conan-2.23.0.tgz", "browser_download_url": "b"}]},
+            {"tag_name": "2.9.0",  "assets": [{"name": "conan-2.9.0.tgz",  "browser_download_url": "c"}]}
+        ]"#,
+    );
+    let latest = pick_latest(&list, |v| format!("conan-{v}.tgz")).unwrap();
+    assert_eq!(latest.version(), "2.23.0");
+}
+
+#[test]
+fn skips_releases_without_platform_asset() {
+    let list = releases(
+        r#"[
+            {"tag_name": "v4.2.0", "assets": [{"name": "cmake-4.2.0-windows-x86_64.zip", "browser_download_url": "a"}]},
+            {"tag_name": "v4.1.4", "assets": [{"name": "cmake-4.1.4-linux-x86_64.tar.gz", "browser_download_url": "b"}]}
+        ]"#,
+    );
+    let latest = pick_latest(&list, |v| format!("cmake-{v}-linux-x86_64.tar.gz")).unwrap();
+    // The `v` prefix is stripped from the tag.
+    assert_eq!(latest.version(), "4.1.4");
+    let asset = latest.find_asset(|name| name.ends_with(".tar.gz")).unwrap();
+    assert_eq!(asset.browser_download_url, "b");
+}
+
+#[test]
+fn returns_none_when_nothing_matches() {
+    let list = releases(r#"[{"tag_name": "1.0.0", "prerelease": true}]"#);
+    assert!(pick_latest(&list, |v| v.to_string()).is_none());
+}
+
+#[test]
+fn rate_limit_message_suggests_token() {
+    let msg = rate_limit_message(None, false);
+    assert!(msg.contains("rate limit"));
+    assert!(msg.contains("GITHUB_TOKEN"));
+    // Already authenticated: pointing at GITHUB_TOKEN would be misleading.
+    assert!(!rate_limit_message(None, true).contains("GITHUB_TOKEN"));
+}
+
+#[test]
+fn next_link_is_found_among_the_others() {
+    let link = r#"<https://api.github.com/repositories/1/releases?per_page=100&page=1>; rel="prev", <https://api.github.com/repositories/1/releases?per_page=100&page=3>; rel="next", <https://api.github.com/repositories/1/releases?per_page=100&page=9>; rel="last""#;
+    assert_eq!(
+        next_link(link).as_deref(),
+        Some("https://api.github.com/repositories/1/releases?per_page=100&page=3")
+    );
+    assert_eq!(next_link(r#"<https://api.github.com/x?page=1>; rel="first""#), None);
+}
+
+// A stub API on 127.0.0.1 serving `pages(base_url)` in order, one per connection.
+fn serve(pages: impl FnOnce(&str) -> Vec<String>) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let responses = pages(&url);
+    std::thread::spawn(move || {
+        for response in responses {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0u8; 1024];
+            while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                let n = stream.read(&mut buf).unwrap();
+                if n == 0 {
+                    break;
+                }
+                request.extend_from_slice(&buf[..n]);
+            }
+            stream.write_all(response.as_bytes()).unwrap();
+        }
+    });
+    url
+}
+
+fn page(body: &str, link: Option<&str>) -> String {
+    let link = link.map(|l| format!("Link: {l}\r\n")).unwrap_or_default();
+    format!(
+        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\n{link}Content-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    )
+}
+
+// Older release lines (cmake 3.x) are only on the later pages.
+#[tokio::test]
+async fn versions_come_from_every_page() {
+    let base = serve(|base| {
+        vec![
+            page(
+                r#"[{"tag_name": "v4.1.4", "assets": [{"name": "cmake-4.1.4.tar.gz", "browser_download_url": "a"}]}]"#,
+                Some(&format!(r#"<{base}/repos/Kitware/CMake/releases?per_page=100&page=2>; rel="next""#)),
+            ),
+            page(
+                r#"[{"tag_name": "v3.20.6", "assets": [{"name": "cmake-3.20.6.tar.gz", "browser_download_url": "b"}]}]"#,
+                None,
+            ),
+        ]
+    });
+    let client = GithubClient::new().with_retry(RetryPolicy::none()).with_api_base(base);
+    let versions = client.versions("Kitware/CMake", |v| format!("cmake-{v}.tar.gz")).await.unwrap();
+    assert_eq!(versions, ["4.1.4", "3.20.6"]);
+}
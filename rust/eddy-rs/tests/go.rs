@@ -4,6 +4,7 @@ use eddy_rs::{
     blueprint::ToolBlueprint,
     consts::eddy_bin_dir,
     languages::go,
+    progress::Silent,
     shared::ensure_tool_dir_check,
     types::Version,
 };
@@ -20,14 +21,14 @@ use serial_test::serial;
 async fn checks_pkg_name() {
     // "1.25.5".into() calls Version::from("1.25.5") via the blanket Into impl.
     // The type of the argument (Version) is inferred from go::build's signature.
-    let info = go::build(Version::SemVer("1.25.5".into())).await.unwrap();
+    let info = go::build(Version::SemVer("1.25.5".into()), &Silent).await.unwrap();
     assert_eq!(info.pkg_name, "go1.25.5.darwin-arm64.tar.gz");
 }
 
 #[tokio::test]
 #[serial]
 async fn checks_url() {
-    let info = go::build(Version::SemVer("1.25.5".into())).await.unwrap();
+    let info = go::build(Version::SemVer("1.25.5".into()), &Silent).await.unwrap();
     assert_eq!(info.url, format!("https://go.dev/dl/{}", info.pkg_name));
 }
 
@@ -35,7 +36,7 @@ async fn checks_url() {
 #[serial]
 async fn downloads_go() {
     let _guard = common::isolated_eddy_home();
-    let info = go::build(Version::SemVer("1.25.5".into())).await.unwrap();
+    let info = go::build(Version::SemVer("1.25.5".into()), &Silent).await.unwrap();
     let dir = ensure_tool_dir_check(&format!(
         "{}/{}/{}",
         info.lang, info.name, info.version
@@ -53,7 +54,7 @@ async fn downloads_go() {
 #[serial]
 async fn installs_go() {
     let _guard = common::isolated_eddy_home();
-    let info = go::build(Version::SemVer("1.25.5".into())).await.unwrap();
+    let info = go::build(Version::SemVer("1.25.5".into()), &Silent).await.unwrap();
     let dir = ensure_tool_dir_check(&format!(
         "{}/{}/{}",
         info.lang, info.name, info.version
@@ -86,7 +87,7 @@ async fn installs_go() {
 #[serial]
 async fn deletes_go_installation() {
     let _guard = common::isolated_eddy_home();
-    let info = go::build(Version::SemVer("1.25.5".into())).await.unwrap();
+    let info = go::build(Version::SemVer("1.25.5".into()), &Silent).await.unwrap();
     let dir = ensure_tool_dir_check(&format!(
         "{}/{}/{}",
         info.lang, info.name, info.version
@@ -100,7 +101,7 @@ async fn deletes_go_installation() {
     assert!(dir.join(&custom).exists());
     assert!(dir.join(&info.pkg_name).exists());
 
-    blueprint.delete().await.unwrap();
+    blueprint.delete(false).await.unwrap();
     assert!(!dir.join(&custom).exists());
     assert!(!dir.join(&info.pkg_name).exists());
 }
@@ -0,0 +1,126 @@
+mod common;
+
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use eddy_rs::{
+    consts::{eddy_bin_dir, eddy_dir},
+    languages::go_tool::{GoTool, binary_name},
+    progress::Progress,
+};
+use serial_test::serial;
+use tempfile::TempDir;
+
+#[test]
+fn known_tools_resolve_to_their_package() {
+    let tool = GoTool::parse("golangci-lint@v1.59.0").unwrap();
+    assert_eq!(tool.package, "github.com/golangci/golangci-lint/cmd/golangci-lint");
+    assert_eq!(tool.name, "golangci-lint");
+    assert_eq!(tool.version, "v1.59.0");
+}
+
+#[test]
+fn package_paths_and_bare_versions() {
+    let tool = GoTool::parse("mvdan.cc/gofumpt@0.6.0").unwrap();
+    assert_eq!(tool.name, "gofumpt");
+    // Go module versions carry a `v`.
+    assert_eq!(tool.version, "v0.6.0");
+}
+
+#[test]
+fn rejects_incomplete_specs() {
+    assert!(GoTool::parse("gopls").is_err());
+    assert!(GoTool::parse("gopls@latest").is_err());
+    // Not in the list and not a package path.
+    assert!(GoTool::parse("mystery@v1.0.0").is_err());
+}
+
+#[test]
+fn major_version_suffix_is_not_the_binary_name() {
+    assert_eq!(binary_name("golang.org/x/tools/gopls"), "gopls");
+    assert_eq!(binary_name("example.com/cmd/tool/v2"), "tool");
+    assert_eq!(binary_name("example.com/v2tool"), "v2tool");
+}
+
+// Collects status lines instead of printing them.
+#[derive(Default)]
+struct Recorder {
+    messages: Mutex<Vec<String>>,
+}
+
+impl Progress for Recorder {
+    fn message(&self, text: &str) {
+        self.messages.lock().unwrap().push(text.to_string());
+    }
+}
+
+// A stand-in `go`, linked as the active one under `home`, that "builds" by
+// writing a script into $GOBIN and records the environment it was given.
+#[cfg(unix)]
+fn fake_go(home: &Path) {
+    use std::os::unix::fs::PermissionsExt;
+
+    let go_bin = home.join("go/go-language/1.22.3/go/bin");
+    std::fs::create_dir_all(&go_bin).unwrap();
+    let go = go_bin.join("go");
+    std::fs::write(
+        &go,
+        "#!/bin/sh\necho \"$@ $GOTOOLCHAIN\" > \"$GOBIN/args\"\nprintf '#!/bin/sh\\n' > \"$GOBIN/gopls\"\n",
+    )
+    .unwrap();
+    std::fs::set_permissions(&go, std::fs::Permissions::from_mode(0o755)).unwrap();
+    std::fs::create_dir_all(home.join("bin")).unwrap();
+    std::os::unix::fs::symlink(&go, home.join("bin/go")).unwrap();
+}
+
+#[cfg(unix)]
+#[tokio::test]
+#[serial]
+async fn builds_into_a_versioned_gobin_and_links_it() {
+    let _home = common::isolated_eddy_home();
+    fake_go(&eddy_dir());
+
+    let tool = GoTool::parse("gopls@v0.16.1").unwrap();
+    tool.install().await.unwrap();
+
+    let gobin = eddy_dir().join("go/tools/gopls/v0.16.1");
+    assert_eq!(tool.gobin(), gobin);
+    let args = std::fs::read_to_string(gobin.join("args")).unwrap();
+    assert_eq!(args.trim(), "install golang.org/x/tools/gopls@v0.16.1 local");
+    assert_eq!(std::fs::read_link(eddy_bin_dir().join("gopls")).unwrap(), gobin.join("gopls"));
+
+    // Installed already: linked again without running go.
+    std::fs::remove_file(gobin.join("args")).unwrap();
+    tool.install().await.unwrap();
+    assert!(!gobin.join("args").exists());
+}
+
+// An explicit home is used for the Go lookup, GOBIN and the link alike, and the
+// status lines go to the reporter. EDDY_HOME isn't touched, so no #[serial].
+#[cfg(unix)]
+#[tokio::test]
+async fn installs_under_the_given_home() {
+    let home = TempDir::new().unwrap();
+    fake_go(home.path());
+    let recorder = Arc::new(Recorder::default());
+
+    let tool = GoTool::parse("gopls@v0.16.1")
+        .unwrap()
+        .with_home(home.path().to_path_buf())
+        .with_progress(recorder.clone());
+    tool.install().await.unwrap();
+
+    let gobin = home.path().join("go/tools/gopls/v0.16.1");
+    assert_eq!(tool.gobin(), gobin);
+    assert_eq!(std::fs::read_link(home.path().join("bin/gopls")).unwrap(), gobin.join("gopls"));
+    let messages = recorder.messages.lock().unwrap();
+    assert!(messages.iter().any(|m| m.starts_with("Installed gopls@v0.16.1")), "{messages:?}");
+}
+
+#[tokio::test]
+#[serial]
+async fn needs_an_active_go() {
+    let _home = common::isolated_eddy_home();
+    let err = GoTool::parse("gopls@v0.16.1").unwrap().install().await.unwrap_err();
+    assert!(err.to_string().contains("eddy use go"), "{err}");
+}
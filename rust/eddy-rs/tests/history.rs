@@ -0,0 +1,161 @@
+mod common;
+
+use std::path::Path;
+use std::sync::Arc;
+
+use eddy_rs::{
+    blueprint::{ArtifactSource, ToolBlueprint},
+    client::Eddy,
+    consts::eddy_dir,
+    history::{self, Action, HISTORY_FILE},
+    languages,
+    linker::Platform,
+    progress::Silent,
+    shared,
+    types::Version,
+};
+use serial_test::serial;
+use tempfile::TempDir;
+
+// Lays out an installed go version by hand (go's builder is offline for an
+// exact version), so these tests never download anything.
+async fn fake_install(version: &str) -> ToolBlueprint {
+    let info = languages::build("go", Version::SemVer(version.into())).await.unwrap();
+    let blueprint = ToolBlueprint::new(info);
+    let bin_dir = blueprint.bin_dir();
+    std::fs::create_dir_all(&bin_dir).unwrap();
+    for link in blueprint.link_names() {
+        std::fs::write(bin_dir.join(Platform::current().exe_name(link)), "").unwrap();
+    }
+    blueprint
+}
+
+fn actions(home: &Path) -> Vec<(Action, String, Option<String>)> {
+    history::load(home)
+        .unwrap()
+        .into_iter()
+        .map(|e| (e.action, e.version, e.previous.map(|p| p.version)))
+        .collect()
+}
+
+#[tokio::test]
+#[serial]
+async fn use_records_what_was_active_before() {
+    let _home = common::isolated_eddy_home();
+    let old = fake_install("1.21.0").await;
+    let new = fake_install("1.22.3").await;
+    old.use_tool().unwrap();
+    new.use_tool().unwrap();
+    // Relinking the active version isn't a switch, so it isn't recorded.
+    new.use_tool().unwrap();
+
+    assert_eq!(
+        actions(&eddy_dir()),
+        [
+            (Action::Use, "1.21.0".to_string(), None),
+            (Action::Use, "1.22.3".to_string(), Some("1.21.0".to_string())),
+        ]
+    );
+}
+
+// A rollback is recorded like a use, so a second one undoes the first.
+#[tokio::test]
+#[serial]
+async fn rollback_restores_the_previous_version() {
+    let _home = common::isolated_eddy_home();
+    let old = fake_install("1.21.0").await;
+    let new = fake_install("1.22.3").await;
+    old.use_tool().unwrap();
+    new.use_tool().unwrap();
+
+    history::rollback(Some("go"), Arc::new(Silent)).await.unwrap();
+    assert!(old.is_active());
+    assert!(!new.is_active());
+    let last = history::load(&eddy_dir()).unwrap().pop().unwrap();
+    assert_eq!(last.action, Action::Rollback);
+    assert_eq!(last.previous.unwrap().version, "1.22.3");
+
+    history::rollback(None, Arc::new(Silent)).await.unwrap();
+    assert!(new.is_active());
+}
+
+#[tokio::test]
+#[serial]
+async fn nothing_to_roll_back_to() {
+    let _home = common::isolated_eddy_home();
+    assert!(history::rollback(Some("go"), Arc::new(Silent)).await.is_err());
+
+    fake_install("1.22.3").await.use_tool().unwrap();
+    let err = history::rollback(Some("go"), Arc::new(Silent)).await.unwrap_err();
+    assert!(err.to_string().contains("first version"), "{err}");
+}
+
+#[tokio::test]
+#[serial]
+async fn cannot_roll_back_to_a_deleted_version() {
+    let _home = common::isolated_eddy_home();
+    let old = fake_install("1.21.0").await;
+    old.use_tool().unwrap();
+    fake_install("1.22.3").await.use_tool().unwrap();
+    old.delete(false).await.unwrap();
+
+    let err = history::rollback(Some("go"), Arc::new(Silent)).await.unwrap_err();
+    assert!(err.to_string().contains("deleted"), "{err}");
+    let last = history::load(&eddy_dir()).unwrap().pop().unwrap();
+    assert_eq!((last.action, last.version.as_str()), (Action::Delete, "1.21.0"));
+}
+
+#[tokio::test]
+async fn installs_are_recorded_in_the_clients_home() {
+    let home = TempDir::new().unwrap();
+    let work = TempDir::new().unwrap();
+    let binary = work.path().join("bazel-from-usb");
+    std::fs::write(&binary, "#!/bin/sh\n").unwrap();
+    let eddy = Eddy::new().with_home(home.path().to_path_buf()).with_progress(Arc::new(Silent));
+
+    eddy.install_from("bazel", "8.5.0", ArtifactSource::File(binary)).await.unwrap();
+    let entries = history::load(home.path()).unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!((entries[0].action, entries[0].tool.as_str()), (Action::Install, "bazel"));
+    assert_eq!(entries[0].links, ["bazel"]);
+}
+
+// A torn last line (a crash mid-write) doesn't hide the rest of the history.
+#[test]
+fn unreadable_lines_are_skipped() {
+    let home = TempDir::new().unwrap();
+    let entry = history::Entry::new(Action::Use, "ninja", "1.12.1", "/x".into(), &["ninja"]);
+    history::append(home.path(), &entry).unwrap();
+    let path = home.path().join(HISTORY_FILE);
+    let mut text = std::fs::read_to_string(&path).unwrap();
+    text.push_str("{\"at\":17180");
+    std::fs::write(&path, text).unwrap();
+
+    assert_eq!(history::load(home.path()).unwrap(), [entry]);
+}
+
+// Relinking by hand isn't in the history, so there is no "last switch" to undo.
+#[tokio::test]
+#[serial]
+async fn rollback_refuses_links_changed_outside_eddy() {
+    let _home = common::isolated_eddy_home();
+    let old = fake_install("1.21.0").await;
+    let new = fake_install("1.22.3").await;
+    old.use_tool().unwrap();
+    new.use_tool().unwrap();
+    for link in old.link_names() {
+        shared::symlink_bin(&old.bin_dir(), link).unwrap();
+    }
+
+    let err = history::rollback(Some("go"), Arc::new(Silent)).await.unwrap_err();
+    assert!(err.to_string().contains("changed outside eddy"), "{err}");
+    assert!(old.is_active());
+}
+
+#[test]
+fn history_rows() {
+    let entry = history::Entry::new(Action::Use, "go-language", "1.22.3", "/x".into(), &["go"])
+        .with_previous(Some(history::Target { version: "1.21.0".into(), bin_dir: "/y".into() }));
+    let row = entry.to_string();
+    assert!(row.ends_with("use      go-language  1.22.3  (was 1.21.0)"), "{row}");
+}
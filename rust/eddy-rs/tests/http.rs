@@ -0,0 +1,104 @@
+// Retries are exercised against a throwaway HTTP server on 127.0.0.1 that plays
+// back scripted responses, one per connection — no internet access needed.
+use std::io::{Read, Write};
+use std::net::TcpListener;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use eddy_rs::{
+    http::{RetryPolicy, is_retryable_status},
+    shared::download_file,
+};
+use reqwest::StatusCode;
+use tempfile::TempDir;
+
+// Serves `responses` in order (raw HTTP, written verbatim), then stops accepting.
+// Returns the base URL and a counter of connections served.
+fn serve(responses: Vec<String>) -> (String, Arc<Mutex<usize>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let served = Arc::new(Mutex::new(0));
+    let counter = Arc::clone(&served);
+    std::thread::spawn(move || {
+        for response in responses {
+            let (mut stream, _) = listener.accept().unwrap();
+            // Read until the end of the request headers; the body (none for GET) is ignored.
+            let mut request = Vec::new();
+            let mut buf = [0u8; 1024];
+            while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                let n = stream.read(&mut buf).unwrap();
+                if n == 0 {
+                    break;
+                }
+                request.extend_from_slice(&buf[..n]);
+            }
+            // Counted before writing: once the client has the response, the test may
+            // assert on the count straight away.
+            *counter.lock().unwrap() += 1;
+            stream.write_all(response.as_bytes()).unwrap();
+            // Dropping the stream closes the connection — mid-body, for the truncated case.
+        }
+    });
+    (url, served)
+}
+
+fn response(status: &str, content_length: usize, body: &str) -> String {
+    format!("HTTP/1.1 {status}\r\nContent-Length: {content_length}\r\nConnection: close\r\n\r\n{body}")
+}
+
+#[test]
+fn backoff_doubles_up_to_the_cap() {
+    let policy = RetryPolicy {
+        max_attempts: 10,
+        base_delay: Duration::from_millis(500),
+        max_delay: Duration::from_secs(3),
+    };
+    assert_eq!(policy.delay(1), Duration::from_millis(500));
+    assert_eq!(policy.delay(2), Duration::from_secs(1));
+    assert_eq!(policy.delay(3), Duration::from_secs(2));
+    assert_eq!(policy.delay(4), Duration::from_secs(3));
+    // Far past the point where 2^n overflows u32.
+    assert_eq!(policy.delay(40), Duration::from_secs(3));
+}
+
+#[test]
+fn only_server_errors_are_retried() {
+    assert!(is_retryable_status(StatusCode::SERVICE_UNAVAILABLE));
+    assert!(is_retryable_status(StatusCode::BAD_GATEWAY));
+    assert!(!is_retryable_status(StatusCode::NOT_FOUND));
+    assert!(!is_retryable_status(StatusCode::FORBIDDEN));
+}
+
+#[tokio::test]
+async fn retries_5xx_and_truncated_bodies_without_corrupting_the_file() {
+    let body = "complete archive contents";
+    let (url, served) = serve(vec![
+        response("503 Service Unavailable", 0, ""),
+        // Promises the full length, sends a prefix, then hangs up.
+        response("200 OK", body.len(), &body[..8]),
+        response("200 OK", body.len(), body),
+    ]);
+    let dir = TempDir::new().unwrap();
+    let dest = dir.path().join("tool.tar.gz");
+
+    download_file(&dest, &format!("{url}/tool.tar.gz")).await.unwrap();
+
+    assert_eq!(*served.lock().unwrap(), 3);
+    // Exactly the last attempt's bytes — the truncated prefix wasn't kept.
+    assert_eq!(std::fs::read_to_string(&dest).unwrap(), body);
+    assert!(!dir.path().join("tool.tar.gz.part").exists());
+}
+
+#[tokio::test]
+async fn client_errors_fail_immediately_and_leave_nothing_behind() {
+    let (url, served) = serve(vec![response("404 Not Found", 0, "")]);
+    let dir = TempDir::new().unwrap();
+    let dest = dir.path().join("missing.zip");
+
+    let err = download_file(&dest, &format!("{url}/missing.zip")).await.unwrap_err();
+
+    assert!(format!("{err:#}").contains("404"), "{err:#}");
+    assert_eq!(*served.lock().unwrap(), 1);
+    assert!(!dest.exists());
+    assert!(!dir.path().join("missing.zip.part").exists());
+}
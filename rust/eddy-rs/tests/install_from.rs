@@ -0,0 +1,117 @@
+// `eddy install <tool> <version> --from <archive>`: the archives are built on the
+// fly with the layout the real releases have, so these run without network access.
+mod common;
+
+use std::process::Command;
+use std::sync::Arc;
+
+use eddy_rs::{
+    blueprint::{ArtifactSource, ToolBlueprint},
+    client::Eddy,
+    consts::eddy_bin_dir,
+    languages::cpp::{bazel, cmake},
+    progress::Silent,
+    shared::base_pkg_name,
+    types::Version,
+};
+use serial_test::serial;
+use tempfile::TempDir;
+
+#[tokio::test]
+#[serial]
+async fn installs_and_links_a_local_tarball() {
+    let _guard = common::isolated_eddy_home();
+    let info = cmake::build(Version::SemVer("4.1.4".into()));
+
+    // cmake-4.1.4-<platform>/bin/{ccmake,cmake,cpack,ctest}, tarred up like upstream.
+    let work = TempDir::new().unwrap();
+    let bin = work.path().join(base_pkg_name(&info.pkg_name)).join("bin");
+    std::fs::create_dir_all(&bin).unwrap();
+    for exe in ["ccmake", "cmake", "cpack", "ctest"] {
+        std::fs::write(bin.join(exe), "#!/bin/sh\n").unwrap();
+    }
+    let archive = work.path().join("usb-copy.tar.gz");
+    let status = Command::new("tar")
+        .arg("-czf")
+        .arg(&archive)
+        .arg("-C")
+        .arg(work.path())
+        .arg(base_pkg_name(&info.pkg_name))
+        .status()
+        .unwrap();
+    assert!(status.success());
+
+    let mut blueprint = ToolBlueprint::new(info).with_source(ArtifactSource::File(archive));
+    blueprint.install().await.unwrap();
+    blueprint.use_tool().unwrap();
+
+    assert!(blueprint.is_installed());
+    // The digest of the local file is recorded, as for a download.
+    assert!(blueprint.info.sha256.is_some());
+    let link = eddy_bin_dir().join("cmake");
+    assert_eq!(std::fs::read_link(&link).unwrap(), blueprint.bin_dir().join("cmake"));
+}
+
+#[tokio::test]
+#[serial]
+async fn bare_binaries_are_renamed_like_downloads() {
+    let _guard = common::isolated_eddy_home();
+    let work = TempDir::new().unwrap();
+    // Any file name works: it's copied in under the release's pkg_name first.
+    let binary = work.path().join("bazel-from-usb");
+    std::fs::write(&binary, "#!/bin/sh\n").unwrap();
+
+    let info = bazel::build(Version::SemVer("8.5.0".into()));
+    let mut blueprint = ToolBlueprint::new(info).with_source(ArtifactSource::File(binary));
+    blueprint.install().await.unwrap();
+
+    assert!(blueprint.is_installed());
+}
+
+#[tokio::test]
+#[serial]
+async fn rejects_archives_with_the_wrong_layout() {
+    let _guard = common::isolated_eddy_home();
+    let work = TempDir::new().unwrap();
+    let archive = work.path().join("wrong.tar.gz");
+    std::fs::write(work.path().join("README"), "not cmake").unwrap();
+    let status = Command::new("tar")
+        .arg("-czf")
+        .arg(&archive)
+        .arg("-C")
+        .arg(work.path())
+        .arg("README")
+        .status()
+        .unwrap();
+    assert!(status.success());
+
+    let info = cmake::build(Version::SemVer("4.1.4".into()));
+    let mut blueprint = ToolBlueprint::new(info).with_source(ArtifactSource::File(archive));
+    let err = blueprint.install().await.unwrap_err().to_string();
+    assert!(err.contains("does not have the layout"), "{err}");
+}
+
+#[tokio::test]
+#[serial]
+async fn custom_sources_need_an_explicit_version() {
+    let _guard = common::isolated_eddy_home();
+    let info = bazel::build(Version::Latest);
+    let mut blueprint = ToolBlueprint::new(info)
+        .with_source(ArtifactSource::Url("https://mirror.internal/bazel".into()));
+    assert!(blueprint.install().await.is_err());
+}
+
+// Rejected before "latest" is resolved: go's builder would ask go.dev for it.
+#[tokio::test]
+async fn latest_from_a_custom_source_is_rejected_offline() {
+    let home = TempDir::new().unwrap();
+    let eddy = Eddy::new().with_home(home.path().to_path_buf()).with_progress(Arc::new(Silent));
+    let source = ArtifactSource::Url("https://mirror.internal/go.tar.gz".into());
+
+    let Err(err) = eddy.install_from("go", "latest", source.clone()).await else {
+        panic!("installed go@latest from a mirror");
+    };
+    assert!(err.to_string().contains("needs an explicit version"), "{err}");
+    assert!(source.check_version("go", &Version::SemVer("1.22.3".into())).is_ok());
+    assert!(ArtifactSource::Release.check_version("go", &Version::Latest).is_ok());
+}
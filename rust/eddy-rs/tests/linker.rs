@@ -0,0 +1,113 @@
+// The Windows linking strategy runs here on any OS: the linker takes the platform
+// as a value and the filesystem as a trait object, so `FakeFs` stands in for disk.
+use std::path::Path;
+
+use eddy_rs::{
+    fs::{FakeEntry, FakeFs, FileSystem},
+    linker::{Platform, cmd_shim, link_bin, link_target, remove_links_into},
+};
+
+#[test]
+fn exe_names_only_change_on_windows() {
+    assert_eq!(Platform::Unix.exe_name("go"), "go");
+    assert_eq!(Platform::Windows.exe_name("go"), "go.exe");
+    // Already suffixed (bazel's Windows asset) stays as is.
+    assert_eq!(Platform::Windows.exe_name("bazel.exe"), "bazel.exe");
+    assert_eq!(Platform::Windows.link_name("bazel.exe"), "bazel.cmd");
+}
+
+#[test]
+fn unix_links_are_symlinks() {
+    let fs = FakeFs::new();
+    let src = Path::new("/eddy/go/go-language/1.22.3/go/bin");
+    let bin = Path::new("/eddy/bin");
+    fs.add_file(&src.join("go"), b"ELF");
+
+    let link = link_bin(&fs, Platform::Unix, src, bin, "go").unwrap();
+
+    assert_eq!(link, bin.join("go"));
+    assert_eq!(fs.read_link(&link).unwrap(), src.join("go"));
+}
+
+#[test]
+fn windows_links_are_cmd_shims_to_exe() {
+    let fs = FakeFs::new();
+    let src = Path::new("C:/eddy/go/go-language/1.22.3/go/bin");
+    let bin = Path::new("C:/eddy/bin");
+    fs.add_file(&src.join("go.exe"), b"MZ");
+
+    let link = link_bin(&fs, Platform::Windows, src, bin, "go").unwrap();
+
+    assert_eq!(link, bin.join("go.cmd"));
+    assert!(!fs.is_symlink(&link));
+    let shim = cmd_shim(&src.join("go.exe"));
+    // The shim runs the real executable and forwards all arguments.
+    assert!(shim.contains("go.exe\" %*"));
+    assert_eq!(fs.entry(&link), Some(FakeEntry::File(shim.into_bytes())));
+}
+
+#[test]
+fn relinking_replaces_previous_version() {
+    let fs = FakeFs::new();
+    let bin = Path::new("/eddy/bin");
+    let old = Path::new("/eddy/cpp/ninja/1.12.1");
+    let new = Path::new("/eddy/cpp/ninja/1.13.2");
+    fs.add_file(&old.join("ninja"), b"old");
+    fs.add_file(&new.join("ninja"), b"new");
+
+    link_bin(&fs, Platform::Unix, old, bin, "ninja").unwrap();
+    let link = link_bin(&fs, Platform::Unix, new, bin, "ninja").unwrap();
+
+    assert_eq!(fs.read_link(&link).unwrap(), new.join("ninja"));
+}
+
+#[test]
+fn dangling_link_is_replaced() {
+    let fs = FakeFs::new();
+    let bin = Path::new("/eddy/bin");
+    fs.create_dir_all(bin).unwrap();
+    // A link whose target was deleted: exists() is false, is_symlink() is true.
+    fs.symlink(Path::new("/gone/cmake"), &bin.join("cmake")).unwrap();
+    let src = Path::new("/eddy/cpp/cmake/4.1.4/bin");
+    fs.add_file(&src.join("cmake"), b"cmake");
+
+    let link = link_bin(&fs, Platform::Unix, src, bin, "cmake").unwrap();
+    assert_eq!(fs.read_link(&link).unwrap(), src.join("cmake"));
+}
+
+#[test]
+fn relinking_leaves_no_temporary_behind() {
+    let fs = FakeFs::new();
+    let bin = Path::new("/eddy/bin");
+    let src = Path::new("/eddy/cpp/ninja/1.13.2");
+    fs.add_file(&src.join("ninja"), b"ninja");
+
+    link_bin(&fs, Platform::Unix, src, bin, "ninja").unwrap();
+    link_bin(&fs, Platform::Unix, src, bin, "ninja").unwrap();
+
+    // The swap goes through `.ninja.<pid>.tmp`; after the rename only the link remains.
+    let tmp = bin.join(format!(".ninja.{}.tmp", std::process::id()));
+    assert_eq!(fs.entry(&tmp), None);
+    assert!(fs.is_symlink(&bin.join("ninja")));
+}
+
+#[test]
+fn removes_only_links_into_the_deleted_version() {
+    let fs = FakeFs::new();
+    let bin = Path::new("C:/eddy/bin");
+    let old = Path::new("C:/eddy/go/go-language/1.21.0");
+    let new = Path::new("C:/eddy/go/go-language/1.22.3");
+    fs.add_file(&old.join("go/bin/go.exe"), b"MZ");
+    fs.add_file(&new.join("go/bin/gofmt.exe"), b"MZ");
+    link_bin(&fs, Platform::Windows, &old.join("go/bin"), bin, "go").unwrap();
+    link_bin(&fs, Platform::Windows, &new.join("go/bin"), bin, "gofmt").unwrap();
+
+    assert_eq!(
+        link_target(&fs, Platform::Windows, &bin.join("go.cmd")),
+        Some(old.join("go/bin/go.exe"))
+    );
+    let removed = remove_links_into(&fs, Platform::Windows, bin, &["go", "gofmt"], old).unwrap();
+    assert_eq!(removed, [bin.join("go.cmd")]);
+    assert!(!fs.exists(&bin.join("go.cmd")));
+    assert!(fs.exists(&bin.join("gofmt.cmd")));
+}
@@ -0,0 +1,82 @@
+use eddy_rs::lockfile::{Artifact, Lockfile};
+use tempfile::TempDir;
+
+fn artifact(platform: &str, sha: &str) -> Artifact {
+    Artifact {
+        platform: platform.to_string(),
+        url: format!("https://example.com/{platform}"),
+        sha256: sha.to_string(),
+    }
+}
+
+#[test]
+fn missing_lock_loads_empty() {
+    let dir = TempDir::new().unwrap();
+    let lock = Lockfile::load(&dir.path().join("eddy.lock")).unwrap();
+    assert!(lock.tools.is_empty());
+}
+
+#[test]
+fn round_trips_through_disk() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("eddy.lock");
+
+    let mut lock = Lockfile::default();
+    lock.record("go", "1.22.3", artifact("linux-x86_64", "aaa"));
+    lock.record("go", "1.22.3", artifact("macos-aarch64", "bbb"));
+    lock.save(&path).unwrap();
+
+    let loaded = Lockfile::load(&path).unwrap();
+    assert_eq!(loaded.get("go").unwrap().version, "1.22.3");
+    assert_eq!(loaded.artifact("go", "macos-aarch64").unwrap().sha256, "bbb");
+    assert_eq!(loaded.artifact("go", "linux-x86_64").unwrap().sha256, "aaa");
+}
+
+#[test]
+fn save_is_independent_of_insertion_order() {
+    let dir = TempDir::new().unwrap();
+    let (a, b) = (dir.path().join("a.lock"), dir.path().join("b.lock"));
+
+    let mut first = Lockfile::default();
+    first.record("ninja", "1.13.2", artifact("linux-x86_64", "n"));
+    first.record("cmake", "4.1.4", artifact("linux-x86_64", "c"));
+    first.save(&a).unwrap();
+
+    let mut second = Lockfile::default();
+    second.record("cmake", "4.1.4", artifact("linux-x86_64", "c"));
+    second.record("ninja", "1.13.2", artifact("linux-x86_64", "n"));
+    second.save(&b).unwrap();
+
+    assert_eq!(std::fs::read_to_string(a).unwrap(), std::fs::read_to_string(b).unwrap());
+}
+
+#[test]
+fn recording_a_new_version_drops_stale_artifacts() {
+    let mut lock = Lockfile::default();
+    lock.record("go", "1.22.3", artifact("linux-x86_64", "old-linux"));
+    lock.record("go", "1.22.3", artifact("macos-aarch64", "old-mac"));
+
+    lock.record("go", "1.22.4", artifact("linux-x86_64", "new-linux"));
+
+    assert_eq!(lock.get("go").unwrap().version, "1.22.4");
+    // The macOS artifact belonged to 1.22.3 and must not survive the bump.
+    assert!(lock.artifact("go", "macos-aarch64").is_none());
+    assert_eq!(lock.artifact("go", "linux-x86_64").unwrap().sha256, "new-linux");
+}
+
+#[test]
+fn rerecording_a_platform_replaces_it() {
+    let mut lock = Lockfile::default();
+    lock.record("cmake", "4.1.4", artifact("linux-x86_64", "first"));
+    lock.record("cmake", "4.1.4", artifact("linux-x86_64", "second"));
+    assert_eq!(lock.get("cmake").unwrap().artifacts.len(), 1);
+    assert_eq!(lock.artifact("cmake", "linux-x86_64").unwrap().sha256, "second");
+}
+
+#[test]
+fn rejects_lock_from_newer_eddy() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("eddy.lock");
+    std::fs::write(&path, "version = 99\n").unwrap();
+    assert!(Lockfile::load(&path).is_err());
+}
@@ -0,0 +1,50 @@
+mod common;
+
+use std::time::Duration;
+
+use eddy_rs::{consts::eddy_dir, locking::ToolLock, progress::Silent};
+use serial_test::serial;
+
+#[test]
+#[serial]
+fn second_lock_on_same_version_is_refused() {
+    let _home = common::isolated_eddy_home();
+
+    let held = ToolLock::try_acquire(&eddy_dir(), "go", "go-language", "1.22.3").unwrap();
+    assert!(held.is_some());
+    // A second handle conflicts even within one process: flock locks belong to
+    // the open file, not the process.
+    assert!(ToolLock::try_acquire(&eddy_dir(), "go", "go-language", "1.22.3").unwrap().is_none());
+
+    drop(held);
+    assert!(ToolLock::try_acquire(&eddy_dir(), "go", "go-language", "1.22.3").unwrap().is_some());
+}
+
+#[test]
+#[serial]
+fn different_versions_lock_independently() {
+    let _home = common::isolated_eddy_home();
+
+    let _a = ToolLock::try_acquire(&eddy_dir(), "cpp", "cmake", "4.1.4").unwrap().unwrap();
+    let b = ToolLock::try_acquire(&eddy_dir(), "cpp", "cmake", "4.0.0").unwrap();
+    assert!(b.is_some());
+}
+
+// `eddy use` of two versions of one tool contends on the tool's links, even
+// though the two versions lock independently.
+#[test]
+#[serial]
+fn links_lock_is_per_tool() {
+    let _home = common::isolated_eddy_home();
+
+    let _go = ToolLock::acquire_links(&eddy_dir(), "go-language", &Silent).unwrap();
+    let _version = ToolLock::try_acquire(&eddy_dir(), "go", "go-language", "1.22.3").unwrap().unwrap();
+    // Held by `_go`: from another thread, a second acquire has to wait.
+    let (tx, rx) = std::sync::mpsc::channel();
+    std::thread::spawn(move || {
+        let _lock = ToolLock::acquire_links(&eddy_dir(), "go-language", &Silent);
+        let _ = tx.send(());
+    });
+    assert!(rx.recv_timeout(Duration::from_millis(200)).is_err());
+    assert!(ToolLock::acquire_links(&eddy_dir(), "cmake", &Silent).is_ok());
+}
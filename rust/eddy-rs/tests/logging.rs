@@ -0,0 +1,30 @@
+use eddy_rs::{logging, progress::Silent, shared::rename_dir};
+use tempfile::TempDir;
+use tracing::level_filters::LevelFilter;
+
+#[test]
+fn each_v_is_one_level_more() {
+    assert_eq!(logging::level(0), LevelFilter::OFF);
+    assert_eq!(logging::level(1), LevelFilter::INFO);
+    assert_eq!(logging::level(2), LevelFilter::DEBUG);
+    assert_eq!(logging::level(3), LevelFilter::TRACE);
+    assert_eq!(logging::level(9), LevelFilter::TRACE);
+}
+
+// The only test here that installs the (process-global) subscriber; a second
+// `init` in the same test binary would fail.
+#[test]
+fn log_file_gets_debug_spans_without_colours_even_when_quiet() {
+    let dir = TempDir::new().unwrap();
+    let log = dir.path().join("eddy.log");
+    logging::init(0, Some(&log)).unwrap();
+
+    std::fs::write(dir.path().join("bazel-8.5.0-linux-x86_64"), "").unwrap();
+    rename_dir(dir.path(), "bazel-8.5.0-linux-x86_64", "bazel", &Silent).unwrap();
+
+    let text = std::fs::read_to_string(&log).unwrap();
+    assert!(text.contains("DEBUG"), "{text}");
+    assert!(text.contains("rename_dir{"), "{text}");
+    assert!(text.contains("old_name=\"bazel-8.5.0-linux-x86_64\" new_name=\"bazel\""), "{text}");
+    assert!(!text.contains('\u{1b}'), "ANSI escapes in the log file: {text}");
+}
@@ -0,0 +1,174 @@
+// The plugin side is a tiny shell script, so these run offline. Unix-only because
+// the script needs `/bin/sh`; the protocol itself is the same on Windows.
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use eddy_rs::{
+    plugin::{Plugin, PluginToolInfo},
+    progress::Progress,
+    types::{InstallStep, Version},
+};
+use tempfile::TempDir;
+
+// Answers each request type with a canned response; `fail` exits non-zero.
+#[cfg(unix)]
+const SCRIPT: &str = r#"#!/bin/sh
+req=$(cat)
+case "$req" in
+  *'"protocol":1'*) ;;
+  *) echo "unexpected protocol: $req" >&2; exit 2 ;;
+esac
+case "$req" in
+  *'"request":"list-versions"'*) echo '{"versions":["2.1.0","2.0.3"]}' ;;
+  *'"request":"resolve"'*'"spec":"latest"'*) echo '{"version":"2.1.0"}' ;;
+  *'"request":"resolve"'*) echo '{"version":"2.0.3"}' ;;
+  *'"request":"tool-info"'*)
+    echo '{"name":"acme","version":"2.1.0","pkg_name":"acme-2.1.0.tar.gz",
+           "url":"https://artifacts.example/acme-2.1.0.tar.gz",
+           "custom_bin_path":"acme/bin","links":["acme","acmec"],"steps":["extract"]}' ;;
+  *) echo "unknown request" >&2; exit 1 ;;
+esac
+"#;
+
+#[cfg(unix)]
+fn install_plugin(dir: &Path, tool: &str, script: &str) {
+    use std::os::unix::fs::PermissionsExt;
+    let path = dir.join(format!("eddy-plugin-{tool}"));
+    std::fs::write(&path, script).unwrap();
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn finds_plugins_on_path() {
+    let dir = TempDir::new().unwrap();
+    install_plugin(dir.path(), "acme", SCRIPT);
+    let path = std::env::join_paths(["/nonexistent", dir.path().to_str().unwrap()]).unwrap();
+
+    let plugin = Plugin::find_in("acme", &path).unwrap();
+    assert_eq!(plugin.path, dir.path().join("eddy-plugin-acme"));
+    assert!(Plugin::find_in("other", &path).is_none());
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn speaks_the_json_protocol() {
+    let dir = TempDir::new().unwrap();
+    install_plugin(dir.path(), "acme", SCRIPT);
+    let plugin = Plugin::find_in("acme", dir.path().as_os_str()).unwrap();
+
+    assert_eq!(plugin.list_versions().await.unwrap(), ["2.1.0", "2.0.3"]);
+    assert_eq!(plugin.resolve("2.0").await.unwrap(), "2.0.3");
+
+    let info = plugin.build(Version::Latest).await.unwrap();
+    assert_eq!(info.lang, "plugin");
+    assert_eq!(info.name, "acme");
+    assert_eq!(info.version, Version::SemVer("2.1.0".into()));
+    assert_eq!(info.links, Some(vec!["acme", "acmec"]));
+    assert_eq!(info.steps, vec![InstallStep::Extract]);
+    assert_eq!(info.sha256, None);
+}
+
+// Collects status lines instead of printing them.
+#[derive(Default)]
+struct Recorder {
+    messages: Mutex<Vec<String>>,
+}
+
+impl Progress for Recorder {
+    fn message(&self, text: &str) {
+        self.messages.lock().unwrap().push(text.to_string());
+    }
+}
+
+// What a successful plugin prints to stderr is a status line, not terminal output.
+#[cfg(unix)]
+#[tokio::test]
+async fn plugin_diagnostics_go_to_the_reporter() {
+    let dir = TempDir::new().unwrap();
+    install_plugin(
+        dir.path(),
+        "chatty",
+        "#!/bin/sh\necho 'using the staging mirror' >&2\necho '{\"versions\":[\"1.0.0\"]}'\n",
+    );
+    let recorder = Arc::new(Recorder::default());
+    let plugin = Plugin::find_in("chatty", dir.path().as_os_str())
+        .unwrap()
+        .with_progress(recorder.clone());
+
+    assert_eq!(plugin.list_versions().await.unwrap(), ["1.0.0"]);
+    assert_eq!(*recorder.messages.lock().unwrap(), ["using the staging mirror"]);
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn plugin_failure_surfaces_stderr() {
+    let dir = TempDir::new().unwrap();
+    install_plugin(dir.path(), "broken", "#!/bin/sh\necho 'artifact API unreachable' >&2\nexit 3\n");
+    let plugin = Plugin::find_in("broken", dir.path().as_os_str()).unwrap();
+
+    let err = plugin.list_versions().await.unwrap_err().to_string();
+    assert!(err.contains("artifact API unreachable"), "{err}");
+}
+
+#[test]
+fn tool_info_must_name_a_concrete_version() {
+    let info: PluginToolInfo = serde_json::from_str(
+        r#"{"name":"acme","version":"latest","pkg_name":"acme","url":"https://x"}"#,
+    )
+    .unwrap();
+    assert!(info.into_tool_info().is_err());
+}
+
+// A valid tool-info answer with `field` replaced by `value`.
+fn tool_info(field: &str, value: serde_json::Value) -> PluginToolInfo {
+    let mut json = serde_json::json!({
+        "name": "acme", "version": "2.1.0", "pkg_name": "acme.tar.gz", "url": "https://x",
+    });
+    json[field] = value;
+    serde_json::from_value(json).unwrap()
+}
+
+#[test]
+fn tool_info_names_must_be_plain_file_names() {
+    for field in ["name", "pkg_name"] {
+        for bad in ["/usr/bin/acme", "../acme", "..", "a/b", "a\\b", ""] {
+            let err = tool_info(field, bad.into()).into_tool_info().unwrap_err();
+            assert!(err.to_string().contains(field), "{field}={bad}: {err}");
+        }
+    }
+}
+
+#[test]
+fn links_must_be_plain_file_names() {
+    for bad in ["/usr/bin/acme", "../acme", "bin/acme"] {
+        let err = tool_info("links", serde_json::json!(["acme", bad])).into_tool_info().unwrap_err();
+        assert!(err.to_string().contains("link"), "{bad}: {err}");
+    }
+}
+
+#[test]
+fn custom_bin_path_must_stay_inside_the_install_dir() {
+    for bad in ["/opt/acme/bin", "../bin", "acme/../../bin"] {
+        let err = tool_info("custom_bin_path", bad.into()).into_tool_info().unwrap_err();
+        assert!(err.to_string().contains("custom_bin_path"), "{bad}: {err}");
+    }
+    let info = tool_info("custom_bin_path", "acme/bin".into()).into_tool_info().unwrap();
+    assert_eq!(info.custom_bin_path, Some(Path::new("acme/bin").into()));
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn a_stuck_plugin_times_out() {
+    let dir = TempDir::new().unwrap();
+    install_plugin(dir.path(), "stuck", "#!/bin/sh\nexec sleep 30\n");
+    let plugin = Plugin::find_in("stuck", dir.path().as_os_str())
+        .unwrap()
+        .with_timeout(Duration::from_millis(200));
+
+    let started = Instant::now();
+    let err = plugin.list_versions().await.unwrap_err().to_string();
+    assert!(err.contains("did not answer"), "{err}");
+    assert!(started.elapsed() < Duration::from_secs(10));
+}
@@ -0,0 +1,177 @@
+mod common;
+
+use eddy_rs::{
+    blueprint::ToolBlueprint,
+    lockfile::Artifact,
+    project::{Project, spec_matches},
+    shared::{platform, sha256_file},
+};
+use serial_test::serial;
+use tempfile::TempDir;
+
+#[test]
+fn spec_matching() {
+    assert!(spec_matches("latest", "1.22.3"));
+    assert!(spec_matches("1.22.3", "1.22.3"));
+    assert!(spec_matches("1.22", "1.22.3"));
+    assert!(spec_matches("1", "1.22.3"));
+    // Prefixes only match whole components.
+    assert!(!spec_matches("1.2", "1.22.3"));
+    assert!(!spec_matches("1.22.3", "1.22.4"));
+}
+
+#[test]
+fn finds_manifest_in_parent_directory() {
+    let dir = TempDir::new().unwrap();
+    std::fs::write(dir.path().join("eddy.toml"), "[tools]\ngo = \"1.22\"\n").unwrap();
+    let nested = dir.path().join("src/deeply/nested");
+    std::fs::create_dir_all(&nested).unwrap();
+
+    let project = Project::find(&nested).unwrap().unwrap();
+    assert_eq!(project.root, dir.path());
+    assert_eq!(project.pin("go"), Some("1.22"));
+}
+
+#[test]
+fn no_manifest_means_no_project() {
+    let dir = TempDir::new().unwrap();
+    assert!(Project::find(dir.path()).unwrap().is_none());
+}
+
+// The lock satisfies the pin, so resolution uses the locked version, URL and
+// checksum. An exact version needs no network: cmake's builder is a URL template.
+#[tokio::test]
+async fn locked_artifact_wins_over_pin() {
+    let dir = TempDir::new().unwrap();
+    std::fs::write(dir.path().join("eddy.toml"), "[tools]\ncmake = \"4.1\"\n").unwrap();
+    let mut project = Project::load(dir.path()).unwrap();
+    project.lock.record(
+        "cmake",
+        "4.1.4",
+        Artifact {
+            platform: platform(),
+            url: "https://mirror.example.com/cmake.tar.gz".into(),
+            sha256: "abc123".into(),
+        },
+    );
+
+    let info = project.resolve("cmake").await.unwrap();
+    assert_eq!(info.version.as_str(), "4.1.4");
+    assert_eq!(info.url, "https://mirror.example.com/cmake.tar.gz");
+    assert_eq!(info.sha256.as_deref(), Some("abc123"));
+}
+
+// A tool installed outside the project only needs locking: the archive left in
+// the version directory is hashed, and the install itself is not redone.
+#[tokio::test]
+#[serial]
+async fn installed_but_unlocked_tool_is_locked_without_reinstalling() {
+    let _home = common::isolated_eddy_home();
+    let dir = TempDir::new().unwrap();
+    std::fs::write(dir.path().join("eddy.toml"), "[tools]\ncmake = \"4.1.4\"\n").unwrap();
+    let mut project = Project::load(dir.path()).unwrap();
+
+    let blueprint = ToolBlueprint::new(project.resolve("cmake").await.unwrap());
+    let bin_dir = blueprint.bin_dir();
+    std::fs::create_dir_all(&bin_dir).unwrap();
+    for link in blueprint.link_names() {
+        std::fs::write(bin_dir.join(link), "installed").unwrap();
+    }
+    std::fs::write(blueprint.archive_path(), "the release archive").unwrap();
+
+    project.install("cmake").await.unwrap();
+    let artifact = project.lock.artifact("cmake", &platform()).unwrap();
+    assert_eq!(artifact.sha256, sha256_file(&blueprint.archive_path()).unwrap());
+    assert_eq!(std::fs::read_to_string(bin_dir.join("cmake")).unwrap(), "installed");
+}
+
+// `eddy lock` hashes an installed tool's archive where it is: no download,
+// and the version directory is left exactly as the install made it.
+#[tokio::test]
+#[serial]
+async fn lock_hashes_the_installed_archive_in_place() {
+    let _home = common::isolated_eddy_home();
+    let dir = TempDir::new().unwrap();
+    std::fs::write(dir.path().join("eddy.toml"), "[tools]\ncmake = \"4.1.4\"\n").unwrap();
+    let mut project = Project::load(dir.path()).unwrap();
+
+    let blueprint = ToolBlueprint::new(project.resolve("cmake").await.unwrap());
+    let bin_dir = blueprint.bin_dir();
+    std::fs::create_dir_all(&bin_dir).unwrap();
+    for link in blueprint.link_names() {
+        std::fs::write(bin_dir.join(link), "installed").unwrap();
+    }
+    std::fs::write(blueprint.archive_path(), "the release archive").unwrap();
+
+    project.update_lock(true).await.unwrap();
+    let artifact = project.lock.artifact("cmake", &platform()).unwrap();
+    assert_eq!(artifact.sha256, sha256_file(&blueprint.archive_path()).unwrap());
+    assert!(blueprint.is_installed());
+}
+
+// `eddy install cmake 4.1.4` in this project goes through the lock.
+#[test]
+fn pinned_or_locked_versions_belong_to_the_project() {
+    let dir = TempDir::new().unwrap();
+    std::fs::write(dir.path().join("eddy.toml"), "[tools]\ncmake = \"4.1\"\nninja = \"1.12.1\"\n").unwrap();
+    let mut project = Project::load(dir.path()).unwrap();
+    project.lock.record(
+        "cmake",
+        "4.1.4",
+        Artifact { platform: platform(), url: "u".into(), sha256: "s".into() },
+    );
+
+    assert!(project.pins_version("cmake", "4.1.4"));
+    assert!(project.pins_version("ninja", "1.12.1"));
+    assert!(!project.pins_version("cmake", "4.1.3"));
+    assert!(!project.pins_version("ninja", "1.12.0"));
+    assert!(!project.pins_version("go", "1.22.3"));
+}
+
+#[tokio::test]
+async fn edited_pin_ignores_stale_lock() {
+    let dir = TempDir::new().unwrap();
+    std::fs::write(dir.path().join("eddy.toml"), "[tools]\ncmake = \"4.0.2\"\n").unwrap();
+    let mut project = Project::load(dir.path()).unwrap();
+    project.lock.record(
+        "cmake",
+        "4.1.4",
+        Artifact { platform: platform(), url: "u".into(), sha256: "s".into() },
+    );
+
+    let info = project.resolve("cmake").await.unwrap();
+    assert_eq!(info.version.as_str(), "4.0.2");
+    assert!(info.sha256.is_none());
+}
+
+#[test]
+fn manifest_pins_beat_detected_ones() {
+    use eddy_rs::detect::Detected;
+
+    let dir = TempDir::new().unwrap();
+    std::fs::write(dir.path().join("eddy.toml"), "[tools]\ngo = \"1.22.3\"\n").unwrap();
+    let mut project = Project::load(dir.path()).unwrap();
+    project.adopt(vec![
+        Detected { tool: "go".into(), spec: "1.21".into(), source: "go.mod" },
+        Detected { tool: "cmake".into(), spec: "3.28".into(), source: "CMakeLists.txt" },
+    ]);
+    assert_eq!(project.pin("go"), Some("1.22.3"));
+    assert_eq!(project.pin("cmake"), Some("3.28"));
+}
+
+#[test]
+fn set_pin_creates_the_manifest() {
+    let dir = TempDir::new().unwrap();
+    let mut project = Project::without_manifest(dir.path()).unwrap();
+    assert_eq!(project.set_pin("go", "1.22"), None);
+    project.save_manifest().unwrap();
+    // Re-pinning reports what it replaced, and other pins are left alone.
+    let mut project = Project::find(dir.path()).unwrap().unwrap();
+    project.set_pin("ninja", "latest");
+    assert_eq!(project.set_pin("go", "1.21"), Some("1.22".to_string()));
+    project.save_manifest().unwrap();
+
+    let project = Project::load(dir.path()).unwrap();
+    assert_eq!(project.pin("go"), Some("1.21"));
+    assert_eq!(project.pin("ninja"), Some("latest"));
+}
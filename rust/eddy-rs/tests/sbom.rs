@@ -0,0 +1,164 @@
+mod common;
+
+use std::path::Path;
+use std::sync::Arc;
+
+use eddy_rs::{
+    blueprint::ArtifactSource,
+    client::Eddy,
+    consts::eddy_dir,
+    lockfile::Artifact,
+    progress::Silent,
+    project::Project,
+    receipt::Receipt,
+    sbom::{self, Component, SbomFormat},
+    shared::platform,
+};
+use serial_test::serial;
+use tempfile::TempDir;
+
+fn component(tool: &str, version: &str) -> Component {
+    Component {
+        tool: tool.to_string(),
+        version: version.to_string(),
+        url: Some(format!("https://example.com/{tool}-{version}.tar.gz")),
+        sha256: Some("ab".repeat(32)),
+        license: Some("Apache-2.0".to_string()),
+        active: true,
+    }
+}
+
+// bazel ships as one bare binary, so any file will do as its "release".
+async fn install_fake_bazel(work: &Path) -> Eddy {
+    let binary = work.join("bazel-from-usb");
+    std::fs::write(&binary, "#!/bin/sh\n").unwrap();
+    let eddy = Eddy::new().with_progress(Arc::new(Silent));
+    eddy.install_from("bazel", "8.5.0", ArtifactSource::File(binary)).await.unwrap();
+    eddy
+}
+
+#[test]
+fn spdx_ids_only_keep_allowed_characters() {
+    assert_eq!(sbom::spdx_id("go-1.22.3"), "SPDXRef-go-1.22.3");
+    assert_eq!(sbom::spdx_id("acme_cc@v1+2"), "SPDXRef-acme-cc-v1-2");
+}
+
+#[test]
+fn cyclonedx_lists_each_component() {
+    let mut bare = component("ninja", "1.12.1");
+    bare.sha256 = None;
+    bare.license = None;
+    let bom = sbom::render(SbomFormat::Cyclonedx, &[component("go", "1.22.3"), bare], 1_718_000_000);
+
+    assert_eq!(bom["bomFormat"], "CycloneDX");
+    assert_eq!(bom["specVersion"], "1.5");
+    assert!(bom["serialNumber"].as_str().unwrap().starts_with("urn:uuid:"));
+    assert_eq!(bom["metadata"]["timestamp"], "2024-06-10T06:13:20Z");
+
+    let go = &bom["components"][0];
+    assert_eq!(go["name"], "go");
+    assert_eq!(go["version"], "1.22.3");
+    assert_eq!(go["hashes"][0]["alg"], "SHA-256");
+    assert_eq!(go["hashes"][0]["content"], "ab".repeat(32));
+    assert_eq!(go["licenses"][0]["license"]["id"], "Apache-2.0");
+    assert_eq!(go["externalReferences"][0]["url"], "https://example.com/go-1.22.3.tar.gz");
+    // Unknown facts are left out, not written as null.
+    let ninja = bom["components"][1].as_object().unwrap();
+    assert!(!ninja.contains_key("hashes") && !ninja.contains_key("licenses"));
+}
+
+#[test]
+fn spdx_describes_each_package() {
+    let mut bare = component("ninja", "1.12.1");
+    bare.url = None;
+    bare.license = None;
+    let doc = sbom::render(SbomFormat::Spdx, &[component("go", "1.22.3"), bare], 1_718_000_000);
+
+    assert_eq!(doc["spdxVersion"], "SPDX-2.3");
+    assert_eq!(doc["creationInfo"]["created"], "2024-06-10T06:13:20Z");
+    let go = &doc["packages"][0];
+    assert_eq!(go["SPDXID"], "SPDXRef-go-1.22.3");
+    assert_eq!(go["licenseDeclared"], "Apache-2.0");
+    assert_eq!(go["checksums"][0]["checksumValue"], "ab".repeat(32));
+    let ninja = &doc["packages"][1];
+    assert_eq!(ninja["downloadLocation"], "NOASSERTION");
+    assert_eq!(ninja["licenseDeclared"], "NOASSERTION");
+    assert_eq!(doc["relationships"][1]["relatedSpdxElement"], "SPDXRef-ninja-1.12.1");
+}
+
+// The receipt written at install time is what gets reported: the archive's real
+// origin and digest, not the release URL from the tool definition.
+#[tokio::test]
+#[serial]
+async fn installed_tools_come_from_their_receipts() {
+    let _home = common::isolated_eddy_home();
+    let work = TempDir::new().unwrap();
+    let eddy = install_fake_bazel(work.path()).await;
+    eddy.use_version("bazel", "8.5.0").await.unwrap();
+
+    // A go tool is only known through its receipt.
+    let gobin = eddy_dir().join("go/tools/gopls/v0.16.0");
+    std::fs::create_dir_all(&gobin).unwrap();
+    Receipt {
+        name: "gopls".to_string(),
+        version: "v0.16.0".to_string(),
+        url: "https://pkg.go.dev/golang.org/x/tools/gopls@v0.16.0".to_string(),
+        sha256: Some("cd".repeat(32)),
+        license: None,
+        source: "go-install".to_string(),
+        installed_at: 0,
+    }
+    .save(&gobin)
+    .unwrap();
+
+    let components = sbom::installed().await.unwrap();
+    assert_eq!(components.len(), 2, "{components:?}");
+    let bazel = &components[0];
+    assert_eq!((bazel.tool.as_str(), bazel.version.as_str()), ("bazel", "8.5.0"));
+    assert!(bazel.url.as_deref().unwrap().starts_with("file://"), "{bazel:?}");
+    assert_eq!(bazel.sha256.as_ref().map(String::len), Some(64));
+    assert_eq!(bazel.license.as_deref(), Some("Apache-2.0"));
+    assert!(bazel.active);
+    assert_eq!(components[1].tool, "gopls");
+    assert!(!components[1].active);
+}
+
+// With --project the lock's artifact is reported: it's what the project builds with.
+#[tokio::test]
+#[serial]
+async fn project_sbom_uses_the_locked_artifact() {
+    let _home = common::isolated_eddy_home();
+    let work = TempDir::new().unwrap();
+    install_fake_bazel(work.path()).await;
+
+    let dir = TempDir::new().unwrap();
+    std::fs::write(dir.path().join("eddy.toml"), "[tools]\nbazel = \"8\"\n").unwrap();
+    let mut project = Project::find(dir.path()).unwrap().unwrap();
+    project.lock.record(
+        "bazel",
+        "8.5.0",
+        Artifact {
+            platform: platform(),
+            url: "https://mirror.example.com/bazel-8.5.0".to_string(),
+            sha256: "ef".repeat(32),
+        },
+    );
+
+    let components = sbom::for_project(&project).await.unwrap();
+    assert_eq!(components.len(), 1);
+    assert_eq!(components[0].version, "8.5.0");
+    assert_eq!(components[0].url.as_deref(), Some("https://mirror.example.com/bazel-8.5.0"));
+    assert_eq!(components[0].sha256, Some("ef".repeat(32)));
+}
+
+#[tokio::test]
+#[serial]
+async fn project_pin_that_is_neither_locked_nor_installed() {
+    let _home = common::isolated_eddy_home();
+    let dir = TempDir::new().unwrap();
+    std::fs::write(dir.path().join("eddy.toml"), "[tools]\nbazel = \"8\"\n").unwrap();
+    let project = Project::find(dir.path()).unwrap().unwrap();
+
+    let err = sbom::for_project(&project).await.unwrap_err();
+    assert!(err.to_string().contains("eddy lock"), "{err}");
+}
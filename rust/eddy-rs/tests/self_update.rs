@@ -0,0 +1,155 @@
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use eddy_rs::{
+    fs::{FakeEntry, FakeFs, FileSystem},
+    github::Release,
+    self_update::{UpdateCheck, backup_path, is_newer, parse_checksum, pick_update, swap_in},
+};
+
+#[test]
+fn only_eddy_tags_with_the_platform_binary_count() {
+    // The monorepo also tags other projects; a newer `v3.0.0` must not be offered.
+    let releases: Vec<Release> = serde_json::from_str(
+        r#"[
+            {"tag_name": "v3.0.0", "assets": [{"name": "eddy-linux-x86_64", "browser_download_url": "x"}]},
+            {"tag_name": "eddy-v0.4.0", "assets": [{"name": "eddy-macos-aarch64", "browser_download_url": "m"}]},
+            {"tag_name": "eddy-v0.3.0", "prerelease": true,
+             "assets": [{"name": "eddy-linux-x86_64", "browser_download_url": "p"}]},
+            {"tag_name": "eddy-v0.2.10", "assets": [{"name": "eddy-linux-x86_64", "browser_download_url": "a"}]},
+            {"tag_name": "eddy-v0.2.9", "assets": [{"name": "eddy-linux-x86_64", "browser_download_url": "b"}]}
+        ]"#,
+    )
+    .unwrap();
+
+    let (version, release) = pick_update(&releases, "eddy-linux-x86_64").unwrap();
+    assert_eq!(version, "0.2.10");
+    assert_eq!(release.tag_name, "eddy-v0.2.10");
+    assert!(is_newer(&version, "0.2.9"));
+    assert!(!is_newer(&version, "0.2.10"));
+}
+
+#[test]
+fn checksum_files_in_sha256sum_format() {
+    let digest = "A".repeat(64);
+    assert_eq!(parse_checksum(&format!("{digest}  eddy-linux-x86_64\n")), Some("a".repeat(64)));
+    assert_eq!(parse_checksum("not-a-digest eddy"), None);
+    assert_eq!(parse_checksum(""), None);
+}
+
+#[test]
+fn swap_keeps_the_old_binary_and_rollback_trades_back() {
+    let fs = FakeFs::new();
+    let exe = Path::new("/usr/local/bin/eddy");
+    let staged = Path::new("/usr/local/bin/.eddy.new");
+    fs.add_file(exe, b"v1");
+    fs.add_file(staged, b"v2");
+
+    swap_in(&fs, exe, staged).unwrap();
+    assert_eq!(fs.entry(exe), Some(FakeEntry::File(b"v2".to_vec())));
+    assert_eq!(fs.entry(&backup_path(exe)), Some(FakeEntry::File(b"v1".to_vec())));
+    assert!(!fs.exists(staged));
+
+    // Rollback is the same swap with the backup as the incoming binary.
+    swap_in(&fs, exe, &backup_path(exe)).unwrap();
+    assert_eq!(fs.entry(exe), Some(FakeEntry::File(b"v1".to_vec())));
+    assert_eq!(fs.entry(&backup_path(exe)), Some(FakeEntry::File(b"v2".to_vec())));
+}
+
+// Wraps FakeFs and checks, after every call swap_in makes, that the binary is
+// still there — i.e. that a crash between any two steps leaves a runnable eddy.
+#[cfg(unix)]
+struct AlwaysPresent<'a> {
+    fs: FakeFs,
+    exe: &'a Path,
+    steps: Mutex<usize>,
+}
+
+#[cfg(unix)]
+impl AlwaysPresent<'_> {
+    fn check<T>(&self, result: io::Result<T>) -> io::Result<T> {
+        *self.steps.lock().unwrap() += 1;
+        assert!(self.fs.exists(self.exe), "{} missing after step {}", self.exe.display(), self.steps.lock().unwrap());
+        result
+    }
+}
+
+#[cfg(unix)]
+impl FileSystem for AlwaysPresent<'_> {
+    fn exists(&self, path: &Path) -> bool {
+        self.fs.exists(path)
+    }
+    fn is_symlink(&self, path: &Path) -> bool {
+        self.fs.is_symlink(path)
+    }
+    fn read_link(&self, path: &Path) -> io::Result<PathBuf> {
+        self.fs.read_link(path)
+    }
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+        self.fs.read(path)
+    }
+    fn create_dir_all(&self, path: &Path) -> io::Result<()> {
+        self.check(self.fs.create_dir_all(path))
+    }
+    fn write(&self, path: &Path, contents: &[u8]) -> io::Result<()> {
+        self.check(self.fs.write(path, contents))
+    }
+    fn remove_file(&self, path: &Path) -> io::Result<()> {
+        self.check(self.fs.remove_file(path))
+    }
+    fn rename(&self, from: &Path, to: &Path) -> io::Result<()> {
+        self.check(self.fs.rename(from, to))
+    }
+    fn copy(&self, from: &Path, to: &Path) -> io::Result<()> {
+        self.check(self.fs.copy(from, to))
+    }
+    fn symlink(&self, src: &Path, dst: &Path) -> io::Result<()> {
+        self.check(self.fs.symlink(src, dst))
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn the_binary_exists_after_every_step_of_a_swap() {
+    let exe = Path::new("/usr/local/bin/eddy");
+    let staged = Path::new("/usr/local/bin/.eddy.new");
+    let fs = AlwaysPresent { fs: FakeFs::new(), exe, steps: Mutex::new(0) };
+    fs.fs.add_file(exe, b"v1");
+    fs.fs.add_file(staged, b"v2");
+
+    swap_in(&fs, exe, staged).unwrap();
+    swap_in(&fs, exe, &backup_path(exe)).unwrap();
+    assert_eq!(fs.fs.entry(exe), Some(FakeEntry::File(b"v1".to_vec())));
+    assert!(*fs.steps.lock().unwrap() > 0);
+}
+
+#[test]
+fn failed_swap_leaves_the_current_binary_in_place() {
+    let fs = FakeFs::new();
+    let exe = Path::new("/usr/local/bin/eddy");
+    fs.add_file(exe, b"v1");
+
+    // The staged binary doesn't exist, so the second rename fails.
+    assert!(swap_in(&fs, exe, Path::new("/usr/local/bin/.eddy.new")).is_err());
+    assert_eq!(fs.entry(exe), Some(FakeEntry::File(b"v1".to_vec())));
+}
+
+#[test]
+fn update_check_runs_once_a_day() {
+    let day = 24 * 60 * 60;
+    let check = UpdateCheck { checked_at: 1_000_000, latest: "0.3.0".into() };
+    assert!(!check.is_due(1_000_000 + day - 1));
+    assert!(check.is_due(1_000_000 + day));
+    // Never checked: the default timestamp is 0.
+    assert!(UpdateCheck::default().is_due(1_000_000));
+}
+
+#[test]
+fn notice_only_for_newer_versions() {
+    let check = UpdateCheck { checked_at: 0, latest: "0.3.0".into() };
+    assert!(check.notice("0.2.9").unwrap().contains("eddy 0.3.0 is available"));
+    assert_eq!(check.notice("0.3.0"), None);
+    // A failed check records an empty version, which never produces a notice.
+    assert_eq!(UpdateCheck::default().notice("0.1.0"), None);
+}
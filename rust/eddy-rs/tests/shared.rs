@@ -6,7 +6,7 @@ mod common;
 
 use eddy_rs::{
     consts::eddy_bin_dir,
-    shared::{download_file, ensure_tool_dir, extract, format_bytes, symlink_bin},
+    shared::{download_file, ensure_tool_dir, extract, format_bytes, rfc3339, symlink_bin},
 };
 use serial_test::serial;
 use std::path::Path;
@@ -105,3 +105,11 @@ fn formats_bytes_correctly() {
     assert_eq!(format_bytes(1048576), "1.00 MB");
     assert_eq!(format_bytes(2097152), "2.00 MB");
 }
+
+#[test]
+fn timestamps_are_rfc3339_utc() {
+    assert_eq!(rfc3339(0), "1970-01-01T00:00:00Z");
+    assert_eq!(rfc3339(1_718_000_000), "2024-06-10T06:13:20Z");
+    // 2024 is a leap year.
+    assert_eq!(rfc3339(1_709_164_800), "2024-02-29T00:00:00Z");
+}
@@ -0,0 +1,190 @@
+// The TUI's state machine and drawing, driven without a terminal: keys go in as
+// KeyCodes, job results as Messages, and frames are drawn into ratatui's
+// in-memory TestBackend.
+use eddy_rs::{
+    current::{Scope, Selection},
+    tui::{self, App, Job, Message, Pane, Remote},
+};
+use ratatui::{Terminal, backend::TestBackend, crossterm::event::KeyCode};
+
+fn app() -> App {
+    let mut app = App::new(vec!["go".to_string(), "ninja".to_string()]);
+    app.start();
+    app.on_message(Message::Local {
+        tool: "go".to_string(),
+        installed: vec!["1.22.3".to_string(), "1.21.0".to_string()],
+        active: Some(Selection {
+            tool: "go".to_string(),
+            version: "1.22.3".to_string(),
+            scope: Scope::Global,
+            origin: String::new(),
+        }),
+    });
+    app.on_message(Message::Remote {
+        tool: "go".to_string(),
+        versions: Ok(vec!["1.23.0".to_string(), "1.22.3".to_string(), "1.9.1".to_string()]),
+    });
+    app
+}
+
+fn screen(app: &mut App) -> String {
+    let mut terminal = Terminal::new(TestBackend::new(70, 12)).unwrap();
+    terminal.draw(|frame| tui::draw(frame, app)).unwrap();
+    let buffer = terminal.backend().buffer();
+    buffer
+        .content
+        .chunks(buffer.area.width as usize)
+        .map(|row| row.iter().map(|cell| cell.symbol()).collect::<String>())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[test]
+fn starts_by_loading_every_tool_and_the_first_ones_releases() {
+    let mut app = App::new(vec!["go".to_string(), "ninja".to_string()]);
+    assert_eq!(
+        app.start(),
+        [
+            Job::LoadLocal("go".to_string()),
+            Job::LoadLocal("ninja".to_string()),
+            Job::LoadRemote("go".to_string()),
+        ]
+    );
+    assert_eq!(app.state("go").unwrap().remote, Remote::Loading);
+}
+
+#[test]
+fn installed_and_released_versions_are_merged_newest_first() {
+    let rows = app().rows();
+    let rows: Vec<(&str, bool, bool)> =
+        rows.iter().map(|r| (r.version.as_str(), r.installed, r.active)).collect();
+    assert_eq!(
+        rows,
+        [
+            ("1.23.0", false, false),
+            ("1.22.3", true, true),
+            ("1.21.0", true, false),
+            ("1.9.1", false, false),
+        ]
+    );
+}
+
+#[test]
+fn keys_start_jobs_for_the_selected_version() {
+    let mut app = app();
+    // Version keys do nothing while the tools pane has focus.
+    assert!(app.on_key(KeyCode::Char('i')).is_empty());
+
+    app.on_key(KeyCode::Right);
+    assert_eq!(app.focus, Pane::Versions);
+    assert_eq!(app.on_key(KeyCode::Char('i')), [Job::Install("go".into(), "1.23.0".into())]);
+
+    app.on_key(KeyCode::Down);
+    app.on_key(KeyCode::Down);
+    assert_eq!(app.on_key(KeyCode::Enter), [Job::Use("go".into(), "1.21.0".into())]);
+    // Already installed: reported in the status line instead.
+    assert!(app.on_key(KeyCode::Char('i')).is_empty());
+    assert!(app.status.contains("already installed"), "{}", app.status);
+}
+
+#[test]
+fn delete_asks_first() {
+    let mut app = app();
+    app.on_key(KeyCode::Right);
+    app.on_key(KeyCode::Down);
+
+    assert!(app.on_key(KeyCode::Char('d')).is_empty());
+    assert!(app.status.contains("(y/n)"));
+    assert!(app.on_key(KeyCode::Char('n')).is_empty());
+    assert!(app.confirm_delete.is_none());
+
+    app.on_key(KeyCode::Char('d'));
+    assert_eq!(app.on_key(KeyCode::Char('y')), [Job::Delete("go".into(), "1.22.3".into())]);
+}
+
+#[test]
+fn selecting_another_tool_loads_its_releases_once() {
+    let mut app = app();
+    assert_eq!(app.on_key(KeyCode::Down), [Job::LoadRemote("ninja".to_string())]);
+    assert_eq!(app.selected_tool(), Some("ninja"));
+    app.on_key(KeyCode::Up);
+    assert!(app.on_key(KeyCode::Down).is_empty());
+    assert!(app.on_key(KeyCode::Char('q')).is_empty());
+    assert!(app.quit);
+}
+
+#[test]
+fn download_progress_is_tracked_until_finished() {
+    let mut app = app();
+    let name = "go1.23.0.linux-amd64.tar.gz".to_string();
+    app.on_message(Message::DownloadStarted { name: name.clone(), total: Some(200) });
+    app.on_message(Message::DownloadAdvanced { name: name.clone(), bytes: 50 });
+    app.on_message(Message::DownloadAdvanced { name: name.clone(), bytes: 50 });
+    assert_eq!(app.downloads[0].1.done, 100);
+    assert!(screen(&mut app).contains("go1.23.0.linux-amd64.tar.gz 50%"));
+
+    app.on_message(Message::DownloadFinished { name });
+    assert!(app.downloads.is_empty());
+}
+
+#[test]
+fn draws_tools_versions_and_status() {
+    let mut app = app();
+    app.on_message(Message::Status("Now using go@1.22.3".to_string()));
+    let screen = screen(&mut app);
+    assert!(screen.contains("go       1.22.3"), "{screen}");
+    assert!(screen.contains("1.22.3      active"), "{screen}");
+    assert!(screen.contains("1.21.0      installed"), "{screen}");
+    assert!(screen.contains("Now using go@1.22.3"), "{screen}");
+}
+
+// A version selected in this shell wins over the global link, and says so.
+#[test]
+fn the_tools_pane_names_a_non_global_winner() {
+    let mut app = app();
+    app.on_message(Message::Local {
+        tool: "go".to_string(),
+        installed: vec!["1.22.3".to_string(), "1.21.0".to_string()],
+        active: Some(Selection {
+            tool: "go".to_string(),
+            version: "1.21.0".to_string(),
+            scope: Scope::Shell,
+            origin: "EDDY_GO_VERSION".to_string(),
+        }),
+    });
+    let screen = screen(&mut app);
+    assert!(screen.contains("go       1.21.0 (shell)"), "{screen}");
+    assert!(screen.contains("1.21.0      active"), "{screen}");
+}
+
+// Quitting mid-install would cut the install off: ask, or wait for it to finish.
+#[test]
+fn quitting_waits_for_running_jobs() {
+    let mut app = app();
+    app.on_key(KeyCode::Right);
+    app.on_key(KeyCode::Char('i'));
+    assert_eq!(app.running, 1);
+
+    app.on_key(KeyCode::Char('q'));
+    assert!(!app.quit);
+    assert!(app.status.contains("1 job is still running"), "{}", app.status);
+    app.on_key(KeyCode::Char('n'));
+    assert!(!app.quit && !app.confirm_quit);
+
+    // Asked again, then the install finishes: nothing left to wait for.
+    app.on_key(KeyCode::Char('q'));
+    app.on_message(Message::JobDone);
+    assert!(app.quit);
+}
+
+#[test]
+fn a_second_ctrl_c_quits_without_waiting() {
+    let mut app = app();
+    app.on_key(KeyCode::Right);
+    app.on_key(KeyCode::Char('i'));
+
+    app.interrupt();
+    assert!(app.confirm_quit && !app.quit);
+    app.interrupt();
+    assert!(app.quit);
+}